@@ -0,0 +1,137 @@
+use tetra::graphics::{self, Camera, Color, DrawParams, Rectangle, Texture};
+use tetra::input::{self, Key};
+use tetra::math::Vec2;
+use tetra::{Context, ContextBuilder, Event, State};
+
+const SCREEN_WIDTH: f32 = 640.0;
+const SCREEN_HEIGHT: f32 = 480.0;
+const WORLD_SIZE: f32 = 1600.0;
+const MOVEMENT_SPEED: f32 = 4.0;
+const ZOOM_SPEED: f32 = 0.1;
+
+struct GameState {
+    texture: Texture,
+    camera: Camera,
+    player_position: Vec2<f32>,
+}
+
+impl GameState {
+    fn new(ctx: &mut Context) -> tetra::Result<GameState> {
+        let mut camera = Camera::new(SCREEN_WIDTH, SCREEN_HEIGHT);
+
+        // The camera will lag slightly behind the player, and will only start
+        // moving once the player leaves the area in the middle of the screen:
+        camera.follow_smoothing = 0.15;
+        camera.deadzone = Some(Rectangle::new(-48.0, -32.0, 96.0, 64.0));
+
+        // The camera will never show anything outside of the world:
+        camera.bounds = Some(Rectangle::new(0.0, 0.0, WORLD_SIZE, WORLD_SIZE));
+
+        Ok(GameState {
+            texture: Texture::new(ctx, "./examples/resources/player.png")?,
+            camera,
+            player_position: Vec2::new(WORLD_SIZE / 2.0, WORLD_SIZE / 2.0),
+        })
+    }
+}
+
+impl State for GameState {
+    fn update(&mut self, ctx: &mut Context) -> tetra::Result {
+        let mut movement = Vec2::zero();
+
+        if input::is_key_down(ctx, Key::W) {
+            movement.y -= MOVEMENT_SPEED;
+        }
+
+        if input::is_key_down(ctx, Key::S) {
+            movement.y += MOVEMENT_SPEED;
+        }
+
+        if input::is_key_down(ctx, Key::A) {
+            movement.x -= MOVEMENT_SPEED;
+        }
+
+        if input::is_key_down(ctx, Key::D) {
+            movement.x += MOVEMENT_SPEED;
+        }
+
+        self.player_position = (self.player_position + movement).map(|v| v.clamp(0.0, WORLD_SIZE));
+
+        if input::is_key_pressed(ctx, Key::Space) {
+            self.camera.add_trauma(0.5);
+        }
+
+        // Zooming in and out will keep whatever is under the mouse cursor in place:
+        let mouse_position = input::get_mouse_position(ctx);
+
+        if input::is_mouse_scrolled_up(ctx) {
+            self.camera
+                .zoom_at(mouse_position, self.camera.scale + ZOOM_SPEED);
+        }
+
+        if input::is_mouse_scrolled_down(ctx) {
+            let scale = (self.camera.scale - ZOOM_SPEED).map(|s| s.max(ZOOM_SPEED));
+            self.camera.zoom_at(mouse_position, scale);
+        }
+
+        self.camera.follow(self.player_position);
+
+        Ok(())
+    }
+
+    fn draw(&mut self, ctx: &mut Context) -> tetra::Result {
+        // Advancing the camera in `draw` keeps the smoothing and shake in sync with
+        // the display's refresh rate:
+        self.camera.advance(ctx);
+
+        graphics::clear(ctx, Color::BLACK);
+        graphics::set_transform_matrix(ctx, self.camera.as_matrix());
+
+        // Draw a checkerboard, so that it's easy to see the camera moving:
+        for y in 0..(WORLD_SIZE / 64.0) as i32 {
+            for x in 0..(WORLD_SIZE / 64.0) as i32 {
+                let color = if (x + y) % 2 == 0 {
+                    Color::rgb(0.769, 0.812, 0.631)
+                } else {
+                    Color::rgb(0.678, 0.729, 0.541)
+                };
+
+                self.texture.draw(
+                    ctx,
+                    DrawParams::new()
+                        .position(Vec2::new(x as f32 * 64.0, y as f32 * 64.0))
+                        .scale(Vec2::new(4.0, 4.0))
+                        .color(color.with_alpha(0.25)),
+                );
+            }
+        }
+
+        self.texture.draw(
+            ctx,
+            DrawParams::new()
+                .position(self.player_position)
+                .origin(Vec2::new(8.0, 8.0))
+                .scale(Vec2::new(2.0, 2.0)),
+        );
+
+        graphics::reset_transform_matrix(ctx);
+
+        Ok(())
+    }
+
+    fn event(&mut self, _: &mut Context, event: Event) -> tetra::Result {
+        if let Event::Resized { width, height } = event {
+            self.camera.set_viewport_size(width as f32, height as f32);
+        }
+
+        Ok(())
+    }
+}
+
+fn main() -> tetra::Result {
+    ContextBuilder::new("Camera Follow", SCREEN_WIDTH as i32, SCREEN_HEIGHT as i32)
+        .resizable(true)
+        .quit_on_escape(true)
+        .build()?
+        .run(GameState::new)
+}
@@ -0,0 +1,101 @@
+use bytemuck::{Pod, Zeroable};
+use tetra::graphics::mesh::{GeometryBuilder, Mesh, ShapeStyle, VertexBuffer};
+use tetra::graphics::{self, Color, Rectangle, Shader};
+use tetra::math::Vec2;
+use tetra::{time, Context, ContextBuilder, State};
+
+const COLUMNS: usize = 80;
+const ROWS: usize = 45;
+const INSTANCES: usize = COLUMNS * ROWS;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+struct Instance {
+    offset: [f32; 2],
+    rotation: f32,
+    tint: [u8; 4],
+}
+
+unsafe impl Pod for Instance {}
+unsafe impl Zeroable for Instance {}
+
+tetra::vertex_layout!(Instance {
+    offset => ("a_offset", Vec2),
+    rotation => ("a_rotation", Float),
+    tint => ("a_tint", UnsignedByte4Normalized),
+});
+
+struct GameState {
+    mesh: Mesh,
+    shader: Shader,
+    instances: Vec<Instance>,
+    instance_buffer: VertexBuffer,
+    elapsed: f32,
+}
+
+impl GameState {
+    fn new(ctx: &mut Context) -> tetra::Result<GameState> {
+        let mut mesh = GeometryBuilder::new()
+            .rectangle(ShapeStyle::Fill, Rectangle::new(-6.0, -6.0, 12.0, 12.0))?
+            .build_mesh(ctx)?;
+
+        let mut instances = Vec::with_capacity(INSTANCES);
+
+        for y in 0..ROWS {
+            for x in 0..COLUMNS {
+                instances.push(Instance {
+                    offset: [x as f32 * 16.0 + 8.0, y as f32 * 16.0 + 8.0],
+                    rotation: 0.0,
+                    tint: [(x * 255 / COLUMNS) as u8, (y * 255 / ROWS) as u8, 200, 255],
+                });
+            }
+        }
+
+        let instance_buffer = VertexBuffer::new(ctx, &instances)?;
+        mesh.set_instance_buffer(instance_buffer.clone(), 1);
+
+        let shader = Shader::from_vertex_file(ctx, "./examples/resources/instance_buffer.vert")?;
+
+        Ok(GameState {
+            mesh,
+            shader,
+            instances,
+            instance_buffer,
+            elapsed: 0.0,
+        })
+    }
+}
+
+impl State for GameState {
+    fn update(&mut self, ctx: &mut Context) -> tetra::Result {
+        self.elapsed += time::get_delta_time(ctx).as_secs_f32();
+
+        for (i, instance) in self.instances.iter_mut().enumerate() {
+            let x = (i % COLUMNS) as f32;
+            let y = (i / COLUMNS) as f32;
+
+            instance.rotation = self.elapsed * 2.0 + (x + y) * 0.15;
+        }
+
+        // Updating the buffer will also affect the mesh, as they share the same GPU resource.
+        self.instance_buffer.set_data(ctx, &self.instances, 0);
+
+        Ok(())
+    }
+
+    fn draw(&mut self, ctx: &mut Context) -> tetra::Result {
+        graphics::clear(ctx, Color::rgb(0.094, 0.11, 0.16));
+
+        graphics::set_shader(ctx, &self.shader);
+        self.mesh.draw_instanced(ctx, INSTANCES, Vec2::zero());
+        graphics::reset_shader(ctx);
+
+        Ok(())
+    }
+}
+
+fn main() -> tetra::Result {
+    ContextBuilder::new("Instance Buffers", 1280, 720)
+        .build()?
+        .run(GameState::new)
+}
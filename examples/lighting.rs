@@ -0,0 +1,94 @@
+use std::f32::consts::PI;
+
+use tetra::graphics::lighting::{Light, LightMap, Occluder, ShadowMode};
+use tetra::graphics::mesh::ShapeStyle;
+use tetra::graphics::{self, Color, Rectangle};
+use tetra::input::{self, Key};
+use tetra::math::Vec2;
+use tetra::{time, Context, ContextBuilder, State};
+
+const WIDTH: i32 = 640;
+const HEIGHT: i32 = 480;
+
+struct GameState {
+    light_map: LightMap,
+    walls: Vec<Rectangle>,
+    occluders: Vec<Occluder>,
+    spot_angle: f32,
+}
+
+impl GameState {
+    fn new(ctx: &mut Context) -> tetra::Result<GameState> {
+        let mut light_map = LightMap::new(ctx, WIDTH, HEIGHT)?;
+        light_map.set_ambient(Color::rgb(0.1, 0.1, 0.15));
+        light_map.set_shadow_mode(ShadowMode::Soft {
+            radius: 8.0,
+            samples: 6,
+        });
+
+        let walls = vec![
+            Rectangle::new(120.0, 100.0, 60.0, 60.0),
+            Rectangle::new(420.0, 120.0, 100.0, 30.0),
+            Rectangle::new(280.0, 300.0, 40.0, 100.0),
+            Rectangle::new(480.0, 340.0, 50.0, 50.0),
+        ];
+
+        let occluders = walls.iter().copied().map(Occluder::from).collect();
+
+        Ok(GameState {
+            light_map,
+            walls,
+            occluders,
+            spot_angle: 0.0,
+        })
+    }
+}
+
+impl State for GameState {
+    fn update(&mut self, ctx: &mut Context) -> tetra::Result {
+        self.spot_angle += time::get_delta_time(ctx).as_secs_f32();
+
+        if input::is_key_pressed(ctx, Key::Space) {
+            let mode = match self.light_map.shadow_mode() {
+                ShadowMode::Hard => ShadowMode::Soft {
+                    radius: 8.0,
+                    samples: 6,
+                },
+                ShadowMode::Soft { .. } => ShadowMode::Hard,
+            };
+
+            self.light_map.set_shadow_mode(mode);
+        }
+
+        Ok(())
+    }
+
+    fn draw(&mut self, ctx: &mut Context) -> tetra::Result {
+        let lights = [
+            Light::point(input::get_mouse_position(ctx), 300.0).color(Color::rgb(1.0, 0.9, 0.7)),
+            Light::spot(Vec2::new(100.0, 400.0), 400.0, self.spot_angle, PI / 8.0)
+                .color(Color::rgb(0.3, 0.5, 1.0))
+                .falloff(0.5),
+        ];
+
+        self.light_map.render(ctx, &lights, &self.occluders);
+
+        graphics::clear(ctx, Color::rgb(0.6, 0.6, 0.6));
+
+        for wall in &self.walls {
+            graphics::draw_rectangle(ctx, ShapeStyle::Fill, *wall, Color::rgb(0.8, 0.3, 0.2))?;
+        }
+
+        self.light_map.draw(ctx, Vec2::zero());
+
+        Ok(())
+    }
+}
+
+fn main() -> tetra::Result {
+    ContextBuilder::new("Lighting", WIDTH, HEIGHT)
+        .show_mouse(true)
+        .quit_on_escape(true)
+        .build()?
+        .run(GameState::new)
+}
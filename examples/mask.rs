@@ -0,0 +1,135 @@
+use tetra::graphics::mask::{self, MaskMode};
+use tetra::graphics::mesh::ShapeStyle;
+use tetra::graphics::{self, Canvas, Color, DrawParams, Rectangle, Texture};
+use tetra::input;
+use tetra::math::Vec2;
+use tetra::{Context, ContextBuilder, State};
+
+const MINIMAP_RADIUS: f32 = 96.0;
+
+struct GameState {
+    texture: Texture,
+    minimap: Canvas,
+}
+
+impl GameState {
+    pub fn new(ctx: &mut Context) -> tetra::Result<GameState> {
+        Ok(GameState {
+            texture: Texture::new(ctx, "./examples/resources/wabbit_alpha.png")?,
+
+            // Canvases will automatically get a stencil buffer when they're masked.
+            minimap: Canvas::new(ctx, 256, 256)?,
+        })
+    }
+
+    fn draw_world(&self, ctx: &mut Context) {
+        for y in 0..12 {
+            for x in 0..16 {
+                self.texture.draw(
+                    ctx,
+                    DrawParams::new()
+                        .position(Vec2::new(x as f32 * 56.0 + 8.0, y as f32 * 56.0 + 8.0))
+                        .scale(Vec2::broadcast(1.5))
+                        .color(Color::rgb(
+                            x as f32 / 16.0,
+                            y as f32 / 12.0,
+                            1.0 - x as f32 / 16.0,
+                        )),
+                );
+            }
+        }
+    }
+}
+
+impl State for GameState {
+    fn draw(&mut self, ctx: &mut Context) -> tetra::Result {
+        let mouse_position = input::get_mouse_position(ctx);
+
+        // Draw the minimap to a canvas, clipped to a circle.
+        graphics::set_canvas(ctx, &self.minimap);
+        graphics::clear(ctx, Color::rgba(0.0, 0.0, 0.0, 0.0));
+
+        mask::with_mask(
+            ctx,
+            MaskMode::Inside,
+            |ctx| {
+                graphics::draw_circle(
+                    ctx,
+                    ShapeStyle::Fill,
+                    Vec2::broadcast(128.0),
+                    MINIMAP_RADIUS,
+                    Color::WHITE,
+                )
+            },
+            |ctx| {
+                // Clearing ignores masks, so the background is drawn as a shape instead.
+                graphics::draw_rectangle(
+                    ctx,
+                    ShapeStyle::Fill,
+                    Rectangle::new(0.0, 0.0, 256.0, 256.0),
+                    Color::rgb(0.1, 0.1, 0.2),
+                )?;
+
+                graphics::set_transform_matrix(
+                    ctx,
+                    DrawParams::new()
+                        .position(Vec2::broadcast(128.0))
+                        .origin(mouse_position)
+                        .scale(Vec2::broadcast(0.25))
+                        .to_matrix(),
+                );
+
+                self.draw_world(ctx);
+
+                graphics::reset_transform_matrix(ctx);
+
+                Ok(())
+            },
+        )?;
+
+        graphics::reset_canvas(ctx);
+
+        // Draw the world, with a spotlight around the mouse.
+        graphics::clear(ctx, Color::BLACK);
+
+        self.draw_world(ctx);
+
+        mask::begin(ctx)?;
+        graphics::draw_circle(ctx, ShapeStyle::Fill, mouse_position, 128.0, Color::WHITE)?;
+        mask::apply(ctx, MaskMode::Outside);
+
+        graphics::draw_rectangle(
+            ctx,
+            ShapeStyle::Fill,
+            Rectangle::new(0.0, 0.0, 900.0, 680.0),
+            Color::rgba(0.0, 0.0, 0.0, 0.75),
+        )?;
+
+        // Masks can be nested - this ring is only visible outside of the spotlight.
+        mask::begin(ctx)?;
+        graphics::draw_circle(ctx, ShapeStyle::Fill, mouse_position, 160.0, Color::WHITE)?;
+        mask::apply(ctx, MaskMode::Inside);
+
+        graphics::draw_rectangle(
+            ctx,
+            ShapeStyle::Fill,
+            Rectangle::new(0.0, 0.0, 900.0, 680.0),
+            Color::rgba(1.0, 0.8, 0.2, 0.25),
+        )?;
+
+        mask::end(ctx);
+        mask::end(ctx);
+
+        self.minimap.draw(ctx, Vec2::new(900.0 - 256.0, 0.0));
+
+        Ok(())
+    }
+}
+
+fn main() -> tetra::Result {
+    ContextBuilder::new("Masking", 900, 680)
+        .stencil_buffer(true)
+        .show_mouse(true)
+        .build()?
+        .run(GameState::new)
+}
@@ -1,19 +1,31 @@
-use tetra::graphics::{self, Color, NineSlice, Rectangle, Texture};
+use tetra::graphics::{self, Color, NineSlice, NineSliceMode, Rectangle, Texture};
 use tetra::math::Vec2;
 use tetra::{Context, ContextBuilder, State};
 
 struct GameState {
     texture: Texture,
-    config: NineSlice,
+    stretched: NineSlice,
+    tiled: NineSlice,
+    frame: NineSlice,
 }
 
 impl GameState {
     fn new(ctx: &mut Context) -> tetra::Result<GameState> {
         let texture = Texture::new(ctx, "./examples/resources/panel.png")?;
 
+        let config = NineSlice::with_border(Rectangle::new(0.0, 0.0, 32.0, 32.0), 4.0);
+
         Ok(GameState {
             texture,
-            config: NineSlice::with_border(Rectangle::new(0.0, 0.0, 32.0, 32.0), 4.0),
+
+            // By default, the edges and center are stretched:
+            stretched: config.clone(),
+
+            // They can also be tiled, which keeps pixel art crisp:
+            tiled: config.clone().mode(NineSliceMode::Mirror),
+
+            // The center can also be skipped, to draw a frame around other content:
+            frame: config.edge_mode(NineSliceMode::Repeat).draw_center(false),
         })
     }
 }
@@ -23,7 +35,13 @@ impl State for GameState {
         graphics::clear(ctx, Color::BLACK);
 
         self.texture
-            .draw_nine_slice(ctx, &self.config, 640.0, 480.0, Vec2::zero());
+            .draw_nine_slice(ctx, &self.stretched, 288.0, 200.0, Vec2::new(24.0, 24.0));
+
+        self.texture
+            .draw_nine_slice(ctx, &self.tiled, 288.0, 200.0, Vec2::new(328.0, 24.0));
+
+        self.texture
+            .draw_nine_slice(ctx, &self.frame, 592.0, 208.0, Vec2::new(24.0, 248.0));
 
         Ok(())
     }
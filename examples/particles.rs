@@ -0,0 +1,86 @@
+use std::f32::consts::PI;
+
+use tetra::graphics::particles::{EmitterConfig, EmitterShape, ParticleEmitter};
+use tetra::graphics::{self, Color, DrawParams, Texture};
+use tetra::input::{self, MouseButton};
+use tetra::math::Vec2;
+use tetra::{Context, ContextBuilder, State};
+
+struct GameState {
+    fire: ParticleEmitter,
+    sparks: ParticleEmitter,
+}
+
+impl GameState {
+    fn new(ctx: &mut Context) -> tetra::Result<GameState> {
+        let texture = Texture::new(ctx, "./examples/resources/wabbit_alpha.png")?;
+
+        let mut fire = ParticleEmitter::new(
+            texture.clone(),
+            EmitterConfig::new()
+                .shape(EmitterShape::Line {
+                    from: Vec2::new(-40.0, 0.0),
+                    to: Vec2::new(40.0, 0.0),
+                })
+                .spawn_rate(200.0)
+                .lifetime(0.8, 1.4)
+                .direction(-PI / 2.0, 0.3)
+                .speed(60.0, 120.0)
+                .angular_velocity(-2.0, 2.0)
+                .color(&[
+                    (0.0, Color::rgb(1.0, 0.9, 0.3)),
+                    (0.5, Color::rgb(1.0, 0.3, 0.0)),
+                    (1.0, Color::rgba(0.2, 0.2, 0.2, 0.0)),
+                ])
+                .scale(&[(0.0, 0.5), (1.0, 0.1)]),
+        );
+
+        fire.set_position(Vec2::new(320.0, 400.0));
+
+        let mut sparks = ParticleEmitter::new(
+            texture,
+            EmitterConfig::new()
+                .shape(EmitterShape::Circle { radius: 8.0 })
+                .lifetime(0.5, 1.0)
+                .speed(100.0, 300.0)
+                .acceleration(Vec2::new(0.0, 400.0))
+                .drag(1.5)
+                .color(&[(0.0, Color::WHITE), (1.0, Color::rgba(0.3, 0.6, 1.0, 0.0))])
+                .scale(&[(0.0, 0.4), (1.0, 0.0)]),
+        );
+
+        sparks.stop();
+
+        Ok(GameState { fire, sparks })
+    }
+}
+
+impl State for GameState {
+    fn update(&mut self, ctx: &mut Context) -> tetra::Result {
+        if input::is_mouse_button_pressed(ctx, MouseButton::Left) {
+            self.sparks.set_position(input::get_mouse_position(ctx));
+            self.sparks.burst(100);
+        }
+
+        self.fire.update(ctx);
+        self.sparks.update(ctx);
+
+        Ok(())
+    }
+
+    fn draw(&mut self, ctx: &mut Context) -> tetra::Result {
+        graphics::clear(ctx, Color::rgb(0.094, 0.11, 0.16));
+
+        self.fire.draw(ctx, DrawParams::new());
+        self.sparks.draw(ctx, DrawParams::new());
+
+        Ok(())
+    }
+}
+
+fn main() -> tetra::Result {
+    ContextBuilder::new("Particles (click to spawn sparks)", 640, 480)
+        .quit_on_escape(true)
+        .build()?
+        .run(GameState::new)
+}
@@ -0,0 +1,25 @@
+#version 150
+
+in vec2 a_position;
+in vec2 a_uv;
+in vec4 a_color;
+
+in vec2 a_offset;
+in float a_rotation;
+in vec4 a_tint;
+
+uniform mat4 u_projection;
+
+out vec2 v_uv;
+out vec4 v_color;
+
+void main() {
+    v_color = a_color * a_tint;
+    v_uv = a_uv;
+
+    float s = sin(a_rotation);
+    float c = cos(a_rotation);
+    vec2 position = vec2(a_position.x * c - a_position.y * s, a_position.x * s + a_position.y * c);
+
+    gl_Position = u_projection * vec4(position + a_offset, 0.0, 1.0);
+}
@@ -0,0 +1,83 @@
+use tetra::graphics::mesh::ShapeStyle;
+use tetra::graphics::text::{Font, RichText, VectorFontBuilder};
+use tetra::graphics::{self, Color, DrawParams, Rectangle, Texture};
+use tetra::math::Vec2;
+use tetra::{Context, ContextBuilder, State};
+
+const TEXT_OFFSET: Vec2<f32> = Vec2::new(16.0, 16.0);
+
+struct GameState {
+    dialogue: RichText,
+    tooltip: RichText,
+}
+
+impl GameState {
+    fn new(ctx: &mut Context) -> tetra::Result<GameState> {
+        let builder = VectorFontBuilder::new("./examples/resources/DejaVuSansMono.ttf")?;
+        let font = builder.with_size(ctx, 16.0)?;
+
+        let mut dialogue = RichText::wrapped(
+            "[size=1.5][color=#ffcc00]Old Man[/color][/size]\n\
+             It's [b]dangerous[/b] to go alone! Take [color=#7fdbff]this[/color].\n\n\
+             Press [icon=block] to accept, or [color=#f55][font=small]run away[/font][/color].",
+            font.clone(),
+            400.0,
+        );
+
+        dialogue.add_font("small", builder.with_size(ctx, 12.0)?);
+        dialogue.add_icon(
+            "block",
+            Texture::new(ctx, "./examples/resources/block.png")?,
+        );
+
+        let mut tooltip = RichText::new(
+            "[b]Iron Sword[/b]\n[color=#0f0]+5[/color] attack\n[color=#f00]-2[/color] speed\n[[Legendary]",
+            Font::vector(ctx, "./examples/resources/DejaVuSansMono.ttf", 16.0)?,
+        );
+
+        tooltip.set_bold_font(Some(builder.with_size(ctx, 20.0)?));
+
+        Ok(GameState { dialogue, tooltip })
+    }
+}
+
+impl State for GameState {
+    fn draw(&mut self, ctx: &mut Context) -> tetra::Result {
+        graphics::clear(ctx, Color::rgb(0.392, 0.584, 0.929));
+
+        self.dialogue.draw(ctx, TEXT_OFFSET);
+
+        // Rich text can be measured and positioned the same way as plain text.
+        let position = Vec2::new(640.0, 360.0);
+
+        if let Some(bounds) = self.tooltip.get_bounds(ctx) {
+            graphics::draw_rectangle(
+                ctx,
+                ShapeStyle::Fill,
+                Rectangle::new(
+                    position.x - bounds.width / 2.0 - 8.0,
+                    position.y - bounds.height / 2.0 - 8.0,
+                    bounds.width + 16.0,
+                    bounds.height + 16.0,
+                ),
+                Color::rgba(0.0, 0.0, 0.0, 0.75),
+            )?;
+        }
+
+        self.tooltip.draw(
+            ctx,
+            DrawParams::new()
+                .position(position)
+                .pivot(Vec2::new(0.5, 0.5)),
+        );
+
+        Ok(())
+    }
+}
+
+fn main() -> tetra::Result {
+    ContextBuilder::new("Rich Text", 1280, 720)
+        .quit_on_escape(true)
+        .build()?
+        .run(GameState::new)
+}
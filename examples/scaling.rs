@@ -3,7 +3,7 @@ use tetra::graphics::text::{Font, Text};
 use tetra::graphics::{self, Color, NineSlice, Rectangle, Texture};
 use tetra::input::{self, Key};
 use tetra::math::Vec2;
-use tetra::{Context, ContextBuilder, Event, State};
+use tetra::{Context, ContextBuilder, State};
 
 const LABEL: &str = "Press Space to cycle between scaling modes";
 const SCREEN_WIDTH: f32 = 640.0;
@@ -22,8 +22,15 @@ struct GameState {
 
 impl GameState {
     fn new(ctx: &mut Context) -> tetra::Result<GameState> {
+        // Scalers created with the window size will automatically resize
+        // along with the window:
+        let mut scaler = ScreenScaler::with_window_size(ctx, 640, 480, ScalingMode::Fixed)?;
+
+        // The areas around the scaled screen can be filled with a solid color:
+        scaler.set_letterbox_color(Some(Color::rgb(0.157, 0.157, 0.196)));
+
         Ok(GameState {
-            scaler: ScreenScaler::with_window_size(ctx, 640, 480, ScalingMode::Fixed)?,
+            scaler,
             panel_texture: Texture::new(ctx, "./examples/resources/panel.png")?,
             panel_config: NineSlice::with_border(Rectangle::new(0.0, 0.0, 32.0, 32.0), 4.0),
             text: Text::new(
@@ -46,7 +53,8 @@ impl State for GameState {
                 ScalingMode::Fixed => ScalingMode::Stretch,
                 ScalingMode::Stretch => ScalingMode::ShowAll,
                 ScalingMode::ShowAll => ScalingMode::ShowAllPixelPerfect,
-                ScalingMode::ShowAllPixelPerfect => ScalingMode::Crop,
+                ScalingMode::ShowAllPixelPerfect => ScalingMode::ShowAllHybrid,
+                ScalingMode::ShowAllHybrid => ScalingMode::Crop,
                 ScalingMode::Crop => ScalingMode::CropPixelPerfect,
                 ScalingMode::CropPixelPerfect => ScalingMode::Fixed,
                 _ => ScalingMode::Fixed,
@@ -72,20 +80,11 @@ impl State for GameState {
         self.text.draw(ctx, Vec2::new(PANEL_X + 8.0, PANEL_Y + 8.0));
 
         graphics::reset_canvas(ctx);
-        graphics::clear(ctx, Color::BLACK);
 
         self.scaler.draw(ctx);
 
         Ok(())
     }
-
-    fn event(&mut self, _: &mut Context, event: Event) -> tetra::Result {
-        if let Event::Resized { width, height } = event {
-            self.scaler.set_outer_size(width, height);
-        }
-
-        Ok(())
-    }
 }
 
 fn main() -> tetra::Result {
@@ -0,0 +1,176 @@
+use tetra::graphics::{self, Color, DrawParams, Rectangle, Texture, Viewport};
+use tetra::input::{self, Key};
+use tetra::math::Vec2;
+use tetra::{Context, ContextBuilder, Event, State};
+
+const SCREEN_WIDTH: i32 = 800;
+const SCREEN_HEIGHT: i32 = 480;
+const MOVEMENT_SPEED: f32 = 4.0;
+const MINIMAP_SIZE: i32 = 120;
+
+struct Player {
+    position: Vec2<f32>,
+    color: Color,
+    controls: [Key; 4],
+}
+
+struct GameState {
+    texture: Texture,
+    players: [Player; 2],
+    viewports: [Viewport; 2],
+    minimap: Viewport,
+}
+
+impl GameState {
+    fn new(ctx: &mut Context) -> tetra::Result<GameState> {
+        let mut state = GameState {
+            texture: Texture::new(ctx, "./examples/resources/player.png")?,
+            players: [
+                Player {
+                    position: Vec2::new(-64.0, 0.0),
+                    color: Color::rgb(1.0, 0.5, 0.5),
+                    controls: [Key::W, Key::S, Key::A, Key::D],
+                },
+                Player {
+                    position: Vec2::new(64.0, 0.0),
+                    color: Color::rgb(0.5, 0.5, 1.0),
+                    controls: [Key::Up, Key::Down, Key::Left, Key::Right],
+                },
+            ],
+            viewports: [
+                Viewport::new(Rectangle::new(0, 0, 0, 0)),
+                Viewport::new(Rectangle::new(0, 0, 0, 0)),
+            ],
+            minimap: Viewport::new(Rectangle::new(0, 0, 0, 0)),
+        };
+
+        state.minimap.camera_mut().scale = Vec2::new(0.25, 0.25);
+        state.layout(SCREEN_WIDTH, SCREEN_HEIGHT);
+
+        Ok(state)
+    }
+
+    fn layout(&mut self, width: i32, height: i32) {
+        let half_width = width / 2;
+
+        self.viewports[0].set_rect(Rectangle::new(0, 0, half_width, height));
+        self.viewports[1].set_rect(Rectangle::new(half_width, 0, width - half_width, height));
+
+        self.minimap.set_rect(Rectangle::new(
+            half_width - MINIMAP_SIZE / 2,
+            height - MINIMAP_SIZE - 8,
+            MINIMAP_SIZE,
+            MINIMAP_SIZE,
+        ));
+    }
+
+    fn draw_world(&self, ctx: &mut Context) {
+        for x in -8..=8 {
+            for y in -8..=8 {
+                self.texture.draw(
+                    ctx,
+                    DrawParams::new()
+                        .position(Vec2::new(x as f32 * 64.0, y as f32 * 64.0))
+                        .origin(Vec2::new(8.0, 8.0))
+                        .color(Color::rgba(1.0, 1.0, 1.0, 0.25)),
+                );
+            }
+        }
+
+        for player in &self.players {
+            self.texture.draw(
+                ctx,
+                DrawParams::new()
+                    .position(player.position)
+                    .origin(Vec2::new(8.0, 8.0))
+                    .scale(Vec2::new(2.0, 2.0))
+                    .color(player.color),
+            );
+        }
+    }
+}
+
+impl State for GameState {
+    fn update(&mut self, ctx: &mut Context) -> tetra::Result {
+        for (player, viewport) in self.players.iter_mut().zip(&mut self.viewports) {
+            let [up, down, left, right] = player.controls;
+
+            if input::is_key_down(ctx, up) {
+                player.position.y -= MOVEMENT_SPEED;
+            }
+
+            if input::is_key_down(ctx, down) {
+                player.position.y += MOVEMENT_SPEED;
+            }
+
+            if input::is_key_down(ctx, left) {
+                player.position.x -= MOVEMENT_SPEED;
+            }
+
+            if input::is_key_down(ctx, right) {
+                player.position.x += MOVEMENT_SPEED;
+            }
+
+            let camera = viewport.camera_mut();
+            camera.position = player.position;
+            camera.update();
+        }
+
+        // Each viewport can convert the mouse position into world co-ordinates,
+        // so clicking will teleport the player whose half of the screen was clicked:
+        if input::is_mouse_button_pressed(ctx, input::MouseButton::Left) {
+            let mouse_position = input::get_mouse_position(ctx);
+
+            for (player, viewport) in self.players.iter_mut().zip(&self.viewports) {
+                if viewport.contains_point(mouse_position) {
+                    player.position = viewport.project(mouse_position);
+                }
+            }
+        }
+
+        let center = (self.players[0].position + self.players[1].position) / 2.0;
+        let minimap_camera = self.minimap.camera_mut();
+        minimap_camera.position = center;
+        minimap_camera.update();
+
+        Ok(())
+    }
+
+    fn draw(&mut self, ctx: &mut Context) -> tetra::Result {
+        graphics::clear(ctx, Color::BLACK);
+
+        for (viewport, color) in self.viewports.iter().zip([
+            Color::rgb(0.392, 0.584, 0.929),
+            Color::rgb(0.769, 0.812, 0.631),
+        ]) {
+            // Clearing only affects the area inside the active viewport:
+            graphics::set_viewport(ctx, viewport);
+            graphics::clear(ctx, color);
+            self.draw_world(ctx);
+        }
+
+        graphics::set_viewport(ctx, &self.minimap);
+        graphics::clear(ctx, Color::rgb(0.2, 0.2, 0.2));
+        self.draw_world(ctx);
+
+        graphics::reset_viewport(ctx);
+
+        Ok(())
+    }
+
+    fn event(&mut self, _: &mut Context, event: Event) -> tetra::Result {
+        if let Event::Resized { width, height } = event {
+            self.layout(width, height);
+        }
+
+        Ok(())
+    }
+}
+
+fn main() -> tetra::Result {
+    ContextBuilder::new("Split Screen", SCREEN_WIDTH, SCREEN_HEIGHT)
+        .resizable(true)
+        .quit_on_escape(true)
+        .build()?
+        .run(GameState::new)
+}
@@ -44,7 +44,12 @@ impl Context {
             println!("GLSL Version: {}", device_info.glsl_version);
         }
 
-        let graphics = GraphicsContext::new(&mut device, window_width, window_height)?;
+        let graphics = GraphicsContext::new(
+            &mut device,
+            window_width,
+            window_height,
+            settings.stencil_buffer,
+        )?;
         let input = InputContext::new();
         let time = TimeContext::new(settings.timestep);
 
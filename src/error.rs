@@ -38,6 +38,16 @@ pub enum TetraError {
         path: PathBuf,
     },
 
+    /// Returned when your game fails to save an asset. This is usually caused by an
+    /// incorrect file path, or some form of permission issues.
+    FailedToSaveAsset {
+        /// The underlying reason for the error.
+        reason: io::Error,
+
+        /// The path that the asset was being saved to.
+        path: PathBuf,
+    },
+
     /// Returned when a color is invalid.
     InvalidColor,
 
@@ -47,9 +57,17 @@ pub enum TetraError {
     /// Returned when a shader fails to compile.
     InvalidShader(String),
 
+    /// Returned when a shader uniform could not be set, either because the shader has
+    /// no active uniform with the given name, or because the value does not match
+    /// the uniform's type.
+    InvalidUniform(String),
+
     /// Returned when a font could not be read.
     InvalidFont,
 
+    /// Returned when an SVG document could not be parsed.
+    InvalidSvg(String),
+
     /// Returned when a sound cannot be decoded.
     #[cfg(feature = "audio")]
     InvalidSound(DecoderError),
@@ -85,10 +103,15 @@ impl Display for TetraError {
             TetraError::FailedToLoadAsset { path, .. } => {
                 write!(f, "Failed to load asset from {}", path.to_string_lossy())
             }
+            TetraError::FailedToSaveAsset { path, .. } => {
+                write!(f, "Failed to save asset to {}", path.to_string_lossy())
+            }
             TetraError::InvalidColor => write!(f, "Invalid color"),
             TetraError::InvalidTexture(_) => write!(f, "Invalid texture data"),
             TetraError::InvalidShader(msg) => write!(f, "Invalid shader source: {}", msg),
+            TetraError::InvalidUniform(msg) => write!(f, "Invalid uniform: {}", msg),
             TetraError::InvalidFont => write!(f, "Invalid font data"),
+            TetraError::InvalidSvg(msg) => write!(f, "Invalid SVG data: {}", msg),
             #[cfg(feature = "audio")]
             TetraError::InvalidSound(_) => write!(f, "Invalid sound data"),
             TetraError::NotEnoughData { expected, actual } => write!(
@@ -116,10 +139,13 @@ impl Error for TetraError {
         match self {
             TetraError::PlatformError(_) => None,
             TetraError::FailedToLoadAsset { reason, .. } => Some(reason),
+            TetraError::FailedToSaveAsset { reason, .. } => Some(reason),
             TetraError::InvalidColor => None,
             TetraError::InvalidTexture(reason) => Some(reason),
             TetraError::InvalidShader(_) => None,
+            TetraError::InvalidUniform(_) => None,
             TetraError::InvalidFont => None,
+            TetraError::InvalidSvg(_) => None,
             #[cfg(feature = "audio")]
             TetraError::InvalidSound(reason) => Some(reason),
             TetraError::NotEnoughData { .. } => None,
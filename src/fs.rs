@@ -22,6 +22,18 @@ where
     })
 }
 
+pub(crate) fn write<P>(path: P, data: &[u8]) -> Result
+where
+    P: AsRef<Path>,
+{
+    let path_ref = path.as_ref();
+
+    fs::write(path_ref, data).map_err(|e| TetraError::FailedToSaveAsset {
+        reason: e,
+        path: path_ref.to_owned(),
+    })
+}
+
 pub(crate) fn read_to_image<P>(path: P) -> Result<DynamicImage>
 where
     P: AsRef<Path>,
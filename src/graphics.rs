@@ -11,12 +11,19 @@ mod canvas;
 mod color;
 mod drawparams;
 mod image_data;
+pub mod lighting;
+pub mod mask;
 pub mod mesh;
+pub mod palette;
+pub mod particles;
 mod rectangle;
 pub mod scaling;
 mod shader;
+mod shapes;
+mod stats;
 pub mod text;
 mod texture;
+mod viewport;
 
 pub use camera::*;
 pub use canvas::*;
@@ -25,7 +32,10 @@ pub use drawparams::*;
 pub use image_data::*;
 pub use rectangle::*;
 pub use shader::*;
+pub use shapes::*;
+pub use stats::*;
 pub use texture::*;
+pub use viewport::*;
 
 use crate::error::Result;
 use crate::math::{FrustumPlanes, Mat4, Vec2};
@@ -33,7 +43,7 @@ use crate::platform::{GraphicsDevice, RawIndexBuffer, RawVertexBuffer};
 use crate::window;
 use crate::Context;
 
-use self::mesh::{BufferUsage, Vertex, VertexWinding};
+use self::mesh::{BufferUsage, GeometryBuilder, Vertex, VertexWinding};
 
 const MAX_SPRITES: usize = 2048;
 const MAX_VERTICES: usize = MAX_SPRITES * 4; // Cannot be greater than 32767!
@@ -50,16 +60,41 @@ pub(crate) struct GraphicsContext {
 
     shader: Option<Shader>,
     default_shader: Shader,
+    palette_shader: Option<Shader>,
 
     canvas: Option<Canvas>,
+    backbuffer_stencil: bool,
 
     projection_matrix: Mat4<f32>,
     transform_matrix: Mat4<f32>,
 
     vertex_data: Vec<Vertex>,
     element_count: usize,
+    shape_builder: GeometryBuilder,
 
     blend_state: BlendState,
+    scissor: Option<Rectangle<i32>>,
+    stencil_state: StencilState,
+    color_mask: [bool; 4],
+    state_stack: Vec<SavedState>,
+    masks: Vec<Option<mask::MaskMode>>,
+    mask_base: Option<(StencilState, [bool; 4])>,
+
+    stats: RenderStats,
+    last_stats: RenderStats,
+    gpu_timer: Option<GpuTimer>,
+}
+
+/// A snapshot of the graphics state, saved via [`push`].
+#[derive(Debug, Clone)]
+struct SavedState {
+    transform_matrix: Mat4<f32>,
+    blend_state: BlendState,
+    shader: Option<Shader>,
+    canvas: Option<Canvas>,
+    scissor: Option<Rectangle<i32>>,
+    stencil_state: StencilState,
+    color_mask: [bool; 4],
 }
 
 impl GraphicsContext {
@@ -67,8 +102,10 @@ impl GraphicsContext {
         device: &mut GraphicsDevice,
         window_width: i32,
         window_height: i32,
+        backbuffer_stencil: bool,
     ) -> Result<GraphicsContext> {
-        let vertex_buffer = device.new_vertex_buffer(MAX_VERTICES, BufferUsage::Dynamic)?;
+        let vertex_buffer =
+            device.new_vertex_buffer::<Vertex>(MAX_VERTICES, BufferUsage::Dynamic)?;
         let index_buffer = device.new_index_buffer(MAX_INDICES, BufferUsage::Static)?;
 
         let indices: Vec<u32> = INDEX_ARRAY
@@ -94,8 +131,13 @@ impl GraphicsContext {
 
         let default_shader = Shader::with_device(
             device,
-            shader::DEFAULT_VERTEX_SHADER,
-            shader::DEFAULT_FRAGMENT_SHADER,
+            &ShaderSource::new(shader::DEFAULT_VERTEX_SHADER, "<vertex shader>", None, &[])?,
+            &ShaderSource::new(
+                shader::DEFAULT_FRAGMENT_SHADER,
+                "<fragment shader>",
+                None,
+                &[],
+            )?,
         )?;
 
         Ok(GraphicsContext {
@@ -108,16 +150,29 @@ impl GraphicsContext {
 
             shader: None,
             default_shader,
+            palette_shader: None,
 
             canvas: None,
+            backbuffer_stencil,
 
             projection_matrix: ortho(window_width as f32, window_height as f32, false),
             transform_matrix: Mat4::identity(),
 
             vertex_data: Vec::with_capacity(MAX_VERTICES),
             element_count: 0,
+            shape_builder: GeometryBuilder::new(),
 
             blend_state: BlendState::default(),
+            scissor: None,
+            stencil_state: StencilState::disabled(),
+            color_mask: [true; 4],
+            state_stack: Vec::new(),
+            masks: Vec::new(),
+            mask_base: None,
+
+            stats: RenderStats::default(),
+            last_stats: RenderStats::default(),
+            gpu_timer: None,
         })
     }
 }
@@ -147,13 +202,15 @@ pub(crate) fn push_quad(
     // TODO: This function really needs cleaning up before it can be exposed publicly.
 
     if ctx.graphics.element_count + 6 > MAX_INDICES {
-        flush(ctx);
+        flush_with_reason(ctx, FlushReason::BatchFull);
     }
 
-    let mut fx = (x1 - params.origin.x) * params.scale.x;
-    let mut fy = (y1 - params.origin.y) * params.scale.y;
-    let mut fx2 = (x2 - params.origin.x) * params.scale.x;
-    let mut fy2 = (y2 - params.origin.y) * params.scale.y;
+    let scale = params.flipped_scale();
+
+    let mut fx = (x1 - params.origin.x) * scale.x;
+    let mut fy = (y1 - params.origin.y) * scale.y;
+    let mut fx2 = (x2 - params.origin.x) * scale.x;
+    let mut fy2 = (y2 - params.origin.y) * scale.y;
 
     if fx2 < fx {
         std::mem::swap(&mut fx, &mut fx2);
@@ -166,31 +223,48 @@ pub(crate) fn push_quad(
     }
 
     // Branching here might be a bit of a premature optimization...
-    let (ox1, oy1, ox2, oy2, ox3, oy3, ox4, oy4) = if params.rotation == 0.0 {
-        (
-            params.position.x + fx,
-            params.position.y + fy,
-            params.position.x + fx,
-            params.position.y + fy2,
-            params.position.x + fx2,
-            params.position.y + fy2,
-            params.position.x + fx2,
-            params.position.y + fy,
-        )
-    } else {
-        let sin = params.rotation.sin();
-        let cos = params.rotation.cos();
-        (
-            params.position.x + (cos * fx) - (sin * fy),
-            params.position.y + (sin * fx) + (cos * fy),
-            params.position.x + (cos * fx) - (sin * fy2),
-            params.position.y + (sin * fx) + (cos * fy2),
-            params.position.x + (cos * fx2) - (sin * fy2),
-            params.position.y + (sin * fx2) + (cos * fy2),
-            params.position.x + (cos * fx2) - (sin * fy),
-            params.position.y + (sin * fx2) + (cos * fy),
-        )
-    };
+    let (ox1, oy1, ox2, oy2, ox3, oy3, ox4, oy4) =
+        if params.rotation == 0.0 && params.skew == Vec2::zero() {
+            (
+                params.position.x + fx,
+                params.position.y + fy,
+                params.position.x + fx,
+                params.position.y + fy2,
+                params.position.x + fx2,
+                params.position.y + fy2,
+                params.position.x + fx2,
+                params.position.y + fy,
+            )
+        } else {
+            let sin = params.rotation.sin();
+            let cos = params.rotation.cos();
+            let skew_x = params.skew.x.tan();
+            let skew_y = params.skew.y.tan();
+
+            // Skewing far enough on both axes mirrors the quad, so the corners are swapped
+            // horizontally to keep the winding order the same (otherwise it would be culled).
+            if skew_x * skew_y > 1.0 {
+                std::mem::swap(&mut fx, &mut fx2);
+                std::mem::swap(&mut u1, &mut u2);
+            }
+
+            let corner = |x: f32, y: f32| {
+                let sx = x + skew_x * y;
+                let sy = y + skew_y * x;
+
+                (
+                    params.position.x + (cos * sx) - (sin * sy),
+                    params.position.y + (sin * sx) + (cos * sy),
+                )
+            };
+
+            let (ox1, oy1) = corner(fx, fy);
+            let (ox2, oy2) = corner(fx, fy2);
+            let (ox3, oy3) = corner(fx2, fy2);
+            let (ox4, oy4) = corner(fx2, fy);
+
+            (ox1, oy1, ox2, oy2, ox3, oy3, ox4, oy4)
+        };
 
     ctx.graphics.vertex_data.extend_from_slice(&[
         Vertex::new(Vec2::new(ox1, oy1), Vec2::new(u1, v1), params.color),
@@ -208,8 +282,9 @@ pub(crate) fn set_texture(ctx: &mut Context, texture: &Texture) {
 
 pub(crate) fn set_texture_ex(ctx: &mut Context, texture: Option<&Texture>) {
     if texture != ctx.graphics.texture.as_ref() {
-        flush(ctx);
+        flush_with_reason(ctx, FlushReason::TextureChanged);
         ctx.graphics.texture = texture.cloned();
+        ctx.graphics.stats.texture_switches += 1;
     }
 }
 
@@ -219,7 +294,7 @@ pub(crate) fn set_texture_ex(ctx: &mut Context, texture: Option<&Texture>) {
 /// with the screen (or with a [`Canvas`], if one is active).
 pub fn set_blend_state(ctx: &mut Context, blend_state: BlendState) {
     if blend_state != ctx.graphics.blend_state {
-        flush(ctx);
+        flush_with_reason(ctx, FlushReason::BlendStateChanged);
         ctx.graphics.blend_state = blend_state;
 
         ctx.device.set_blend_state(blend_state);
@@ -247,8 +322,9 @@ pub fn reset_shader(ctx: &mut Context) {
 
 pub(crate) fn set_shader_ex(ctx: &mut Context, shader: Option<&Shader>) {
     if shader != ctx.graphics.shader.as_ref() {
-        flush(ctx);
+        flush_with_reason(ctx, FlushReason::ShaderChanged);
         ctx.graphics.shader = shader.cloned();
+        ctx.graphics.stats.shader_switches += 1;
     }
 }
 
@@ -267,10 +343,11 @@ pub fn reset_canvas(ctx: &mut Context) {
 
 pub(crate) fn set_canvas_ex(ctx: &mut Context, canvas: Option<&Canvas>) {
     if canvas != ctx.graphics.canvas.as_ref() {
-        flush(ctx);
+        flush_with_reason(ctx, FlushReason::CanvasChanged);
         resolve_canvas(ctx);
 
         ctx.graphics.canvas = canvas.cloned();
+        ctx.graphics.stats.canvas_switches += 1;
 
         match &ctx.graphics.canvas {
             None => {
@@ -310,6 +387,10 @@ fn resolve_canvas(ctx: &mut Context) {
 /// as this will reduce the number of draw calls made to the
 /// graphics device.
 pub fn flush(ctx: &mut Context) {
+    flush_with_reason(ctx, FlushReason::Manual);
+}
+
+pub(crate) fn flush_with_reason(ctx: &mut Context, reason: FlushReason) {
     if !ctx.graphics.vertex_data.is_empty() {
         let texture = match &ctx.graphics.texture {
             None => return,
@@ -354,6 +435,14 @@ pub fn flush(ctx: &mut Context) {
             ctx.graphics.element_count,
         );
 
+        let stats = &mut ctx.graphics.stats;
+
+        stats.draw_calls += 1;
+        stats.flushes += 1;
+        stats.vertices += ctx.graphics.vertex_data.len();
+        stats.buffer_uploads += 1;
+        stats.flush_reasons.push(reason);
+
         ctx.graphics.vertex_data.clear();
         ctx.graphics.element_count = 0;
     }
@@ -367,9 +456,53 @@ pub fn flush(ctx: &mut Context) {
 /// You usually will not have to call this manually, as it is called for you at the end of every
 /// frame. Note that calling it will trigger a [`flush`] to the graphics hardware.
 pub fn present(ctx: &mut Context) {
-    flush(ctx);
+    flush_with_reason(ctx, FlushReason::Present);
+
+    if let Some(timer) = &mut ctx.graphics.gpu_timer {
+        timer.end_frame(&mut ctx.device);
+    }
 
     ctx.window.swap_buffers();
+
+    let graphics = &mut ctx.graphics;
+
+    graphics.stats.gpu_time = graphics.gpu_timer.as_ref().and_then(GpuTimer::latest);
+    std::mem::swap(&mut graphics.stats, &mut graphics.last_stats);
+    graphics.stats.reset();
+
+    if let Some(timer) = &mut ctx.graphics.gpu_timer {
+        timer.begin_frame(&mut ctx.device);
+    }
+}
+
+/// Returns statistics about the rendering work that was done in the last frame.
+///
+/// This can be useful for finding out why rendering is slow - for example, whether
+/// batches are being broken up by texture or state changes, or whether too much
+/// geometry is being drawn.
+///
+/// The stats are collected between each call to [`present`] (which is called for you
+/// at the end of every frame), so calling this during your `draw` method will
+/// return the stats for the previous frame.
+pub fn get_stats(ctx: &Context) -> &RenderStats {
+    &ctx.graphics.last_stats
+}
+
+/// Sets whether the time the GPU spends rendering each frame should be measured.
+///
+/// When enabled, the results will be available via [`RenderStats::gpu_time`]. This
+/// requires OpenGL 3.3 or the `GL_ARB_timer_query` extension - if neither is available,
+/// this function will have no effect.
+///
+/// GPU timing is disabled by default, as it adds a small amount of overhead to each frame.
+pub fn set_gpu_timing(ctx: &mut Context, enabled: bool) {
+    if enabled {
+        if ctx.graphics.gpu_timer.is_none() && ctx.device.supports_timer_queries() {
+            ctx.graphics.gpu_timer = Some(GpuTimer::new());
+        }
+    } else if let Some(mut timer) = ctx.graphics.gpu_timer.take() {
+        timer.end_frame(&mut ctx.device);
+    }
 }
 
 /// Returns the filter mode that will be used by newly created textures and canvases.
@@ -415,7 +548,7 @@ pub fn get_transform_matrix(ctx: &Context) -> Mat4<f32> {
 ///
 /// This can be used to apply global transformations to subsequent draw calls.
 pub fn set_transform_matrix(ctx: &mut Context, matrix: Mat4<f32>) {
-    flush(ctx);
+    flush_with_reason(ctx, FlushReason::TransformChanged);
 
     ctx.graphics.transform_matrix = matrix;
 }
@@ -438,7 +571,9 @@ pub fn reset_transform_matrix(ctx: &mut Context) {
 /// Note that the position/size of the scissor rectangle is not affected by the transform
 /// matrix - it always operates in screen/canvas co-ordinates.
 pub fn set_scissor(ctx: &mut Context, scissor_rect: Rectangle<i32>) {
-    flush(ctx);
+    flush_with_reason(ctx, FlushReason::ScissorChanged);
+
+    ctx.graphics.scissor = Some(scissor_rect);
 
     match &ctx.graphics.canvas {
         None => {
@@ -472,11 +607,34 @@ pub fn set_scissor(ctx: &mut Context, scissor_rect: Rectangle<i32>) {
 
 /// Disables the scissor rectangle.
 pub fn reset_scissor(ctx: &mut Context) {
-    flush(ctx);
+    flush_with_reason(ctx, FlushReason::ScissorChanged);
+
+    ctx.graphics.scissor = None;
 
     ctx.device.scissor_test(false);
 }
 
+/// Sets the scissor rectangle, using the same co-ordinate space as drawing operations.
+///
+/// When rendering to the screen, [`set_scissor`] works in physical pixels, so the
+/// rectangle is scaled up to match on high-DPI displays.
+pub(crate) fn set_logical_scissor(ctx: &mut Context, mut scissor_rect: Rectangle<i32>) {
+    if ctx.graphics.canvas.is_none() {
+        let scale = window::get_dpi_scale(ctx);
+
+        if scale != 1.0 {
+            scissor_rect = Rectangle::new(
+                (scissor_rect.x as f32 * scale) as i32,
+                (scissor_rect.y as f32 * scale) as i32,
+                (scissor_rect.width as f32 * scale) as i32,
+                (scissor_rect.height as f32 * scale) as i32,
+            );
+        }
+    }
+
+    set_scissor(ctx, scissor_rect);
+}
+
 /// Sets the global stencil behavior.
 ///
 /// The stencil buffer is an invisible drawing target that you can
@@ -491,14 +649,18 @@ pub fn reset_scissor(ctx: &mut Context) {
 /// to `true` when creating your context. To enable this for a canvas,
 /// initialize it via [`Canvas::builder`], with [`stencil_buffer`](CanvasBuilder::stencil_buffer)
 /// set to true.
+///
+/// For common use cases, such as clipping drawing to a shape, the [`mask`] module provides
+/// a higher level API that manages the stencil state for you.
 pub fn set_stencil_state(ctx: &mut Context, state: StencilState) {
-    flush(ctx);
+    flush_with_reason(ctx, FlushReason::StencilChanged);
     ctx.device.set_stencil_state(state);
+    ctx.graphics.stencil_state = state;
 }
 
 /// Clears the stencil buffer to the specified value.
 pub fn clear_stencil(ctx: &mut Context, value: u8) {
-    flush(ctx);
+    flush_with_reason(ctx, FlushReason::StencilChanged);
     ctx.device.clear_stencil(value);
 }
 
@@ -508,8 +670,89 @@ pub fn clear_stencil(ctx: &mut Context, value: u8) {
 /// to draw to the stencil buffer without also drawing to the
 /// visible pixels on screen.
 pub fn set_color_mask(ctx: &mut Context, red: bool, green: bool, blue: bool, alpha: bool) {
-    flush(ctx);
+    flush_with_reason(ctx, FlushReason::ColorMaskChanged);
     ctx.device.set_color_mask(red, green, blue, alpha);
+    ctx.graphics.color_mask = [red, green, blue, alpha];
+}
+
+/// Saves the current graphics state, so that it can be restored later via [`pop`].
+///
+/// The following state is saved:
+///
+/// * The [transform matrix](set_transform_matrix)
+/// * The [blend state](set_blend_state)
+/// * The [shader](set_shader)
+/// * The [canvas](set_canvas)
+/// * The [scissor rectangle](set_scissor)
+/// * The [stencil state](set_stencil_state)
+/// * The [color mask](set_color_mask)
+///
+/// This allows nested drawing code (or library code) to change the state freely, without
+/// having to remember to reset each piece of it afterwards. Calls to `push` can be nested,
+/// and each call to [`pop`] will restore the state from the most recent `push`.
+///
+/// Note that the contents of the stencil buffer and the [`mask`] stack are not saved.
+pub fn push(ctx: &mut Context) {
+    let state = SavedState {
+        transform_matrix: ctx.graphics.transform_matrix,
+        blend_state: ctx.graphics.blend_state,
+        shader: ctx.graphics.shader.clone(),
+        canvas: ctx.graphics.canvas.clone(),
+        scissor: ctx.graphics.scissor,
+        stencil_state: ctx.graphics.stencil_state,
+        color_mask: ctx.graphics.color_mask,
+    };
+
+    ctx.graphics.state_stack.push(state);
+}
+
+/// Restores the graphics state that was saved by the most recent call to [`push`].
+///
+/// # Panics
+///
+/// Panics if there is no saved state to restore.
+pub fn pop(ctx: &mut Context) {
+    let state = ctx
+        .graphics
+        .state_stack
+        .pop()
+        .expect("graphics::push should be called before graphics::pop");
+
+    // The canvas needs to be restored first, as the scissor rectangle is relative to it.
+    set_canvas_ex(ctx, state.canvas.as_ref());
+
+    match state.scissor {
+        Some(scissor_rect) => set_scissor(ctx, scissor_rect),
+        None => reset_scissor(ctx),
+    }
+
+    set_shader_ex(ctx, state.shader.as_ref());
+    set_blend_state(ctx, state.blend_state);
+    set_transform_matrix(ctx, state.transform_matrix);
+
+    if state.stencil_state != ctx.graphics.stencil_state {
+        set_stencil_state(ctx, state.stencil_state);
+    }
+
+    if state.color_mask != ctx.graphics.color_mask {
+        let [red, green, blue, alpha] = state.color_mask;
+        set_color_mask(ctx, red, green, blue, alpha);
+    }
+}
+
+/// Runs the given function, restoring the graphics state afterwards.
+///
+/// This is a shortcut for calling [`push`], running the function, and then calling [`pop`].
+/// See [`push`] for details of which state will be restored.
+pub fn with_state<F, R>(ctx: &mut Context, f: F) -> R
+where
+    F: FnOnce(&mut Context) -> R,
+{
+    push(ctx);
+    let result = f(ctx);
+    pop(ctx);
+
+    result
 }
 
 pub(crate) fn set_viewport_size(ctx: &mut Context) {
@@ -533,6 +776,47 @@ pub(crate) fn ortho(width: f32, height: f32, flipped: bool) -> Mat4<f32> {
     })
 }
 
+/// Interpolates between a list of keyframes, which should be sorted by time.
+///
+/// `key` returns the time and value of a keyframe. Times before the first keyframe or
+/// after the last will be clamped, and `default` will be returned if there are no keyframes.
+pub(crate) fn interpolate_keyframes<K, T, F, L>(
+    keyframes: &[K],
+    t: f32,
+    default: T,
+    key: F,
+    lerp: L,
+) -> T
+where
+    F: Fn(&K) -> (f32, T),
+    L: Fn(T, T, f32) -> T,
+{
+    let (first, last) = match (keyframes.first(), keyframes.last()) {
+        (Some(first), Some(last)) => (key(first), key(last)),
+        _ => return default,
+    };
+
+    if t <= first.0 {
+        return first.1;
+    }
+
+    for pair in keyframes.windows(2) {
+        let ((from_time, from), (to_time, to)) = (key(&pair[0]), key(&pair[1]));
+
+        if t <= to_time {
+            let range = to_time - from_time;
+
+            if range <= 0.0 {
+                return to;
+            }
+
+            return lerp(from, to, (t - from_time) / range);
+        }
+    }
+
+    last.1
+}
+
 /// Defines a formula for blending two color or alpha values.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BlendOperation {
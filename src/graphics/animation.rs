@@ -1,9 +1,23 @@
 //! Functions and types relating to animations.
 
+#[cfg(any(feature = "texture_gif", feature = "texture_png"))]
+use std::io::Cursor;
+use std::path::Path;
 use std::time::Duration;
 
+#[cfg(feature = "texture_gif")]
+use image::codecs::gif::GifDecoder;
+#[cfg(feature = "texture_png")]
+use image::codecs::png::PngDecoder;
+use image::error::{DecodingError, ImageFormatHint};
+#[cfg(any(feature = "texture_gif", feature = "texture_png"))]
+use image::{AnimationDecoder, ImageFormat};
+use image::{Frame, ImageError};
+
+use crate::error::{Result, TetraError};
+use crate::fs;
 use crate::graphics::texture::Texture;
-use crate::graphics::{DrawParams, Rectangle};
+use crate::graphics::{DrawParams, ImageData, Rectangle, TextureFormat};
 use crate::time;
 use crate::Context;
 
@@ -13,6 +27,11 @@ use crate::Context;
 /// will drive the animation, switching the texture region once the specified
 /// time has passed.
 ///
+/// By default, every frame is displayed for the same amount of time. Animations can also
+/// specify a separate length for each frame, via [`with_frame_lengths`](Self::with_frame_lengths)
+/// or [`set_frame_lengths`](Self::set_frame_lengths). Animated GIF and PNG files can be
+/// loaded as animations via [`AnimatedImage`].
+///
 /// # Examples
 ///
 /// The [`animation`](https://github.com/17cupsofcoffee/tetra/blob/main/examples/animation.rs)
@@ -26,6 +45,7 @@ pub struct Animation {
     texture: Texture,
     frames: Vec<Rectangle>,
     frame_length: Duration,
+    frame_lengths: Option<Vec<Duration>>,
 
     current_frame: usize,
     timer: Duration,
@@ -39,6 +59,7 @@ impl Animation {
             texture,
             frames,
             frame_length,
+            frame_lengths: None,
 
             current_frame: 0,
             timer: Duration::from_secs(0),
@@ -52,6 +73,7 @@ impl Animation {
             texture,
             frames,
             frame_length,
+            frame_lengths: None,
 
             current_frame: 0,
             timer: Duration::from_secs(0),
@@ -59,6 +81,35 @@ impl Animation {
         }
     }
 
+    /// Creates a new looping animation, where each frame is displayed for a different
+    /// amount of time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of frame lengths does not match the number of frames.
+    pub fn with_frame_lengths(
+        texture: Texture,
+        frames: Vec<Rectangle>,
+        frame_lengths: Vec<Duration>,
+    ) -> Animation {
+        assert_eq!(
+            frames.len(),
+            frame_lengths.len(),
+            "number of frame lengths must match the number of frames"
+        );
+
+        Animation {
+            texture,
+            frames,
+            frame_length: frame_lengths.first().copied().unwrap_or_default(),
+            frame_lengths: Some(frame_lengths),
+
+            current_frame: 0,
+            timer: Duration::from_secs(0),
+            repeating: true,
+        }
+    }
+
     /// Draws the current frame to the screen (or to a canvas, if one is enabled).
     pub fn draw<P>(&self, ctx: &mut Context, params: P)
     where
@@ -88,12 +139,18 @@ impl Animation {
         let frames_remaining = self.has_frames_remaining();
 
         if frames_remaining || self.repeating {
-            while self.timer >= self.frame_length {
+            loop {
+                let frame_length = self.current_frame_length();
+
+                if self.timer < frame_length {
+                    break;
+                }
+
                 self.current_frame = (self.current_frame + 1) % self.frames.len();
-                self.timer -= self.frame_length;
+                self.timer -= frame_length;
             }
-        } else if self.timer > self.frame_length {
-            self.timer = self.frame_length;
+        } else if self.timer > self.current_frame_length() {
+            self.timer = self.current_frame_length();
         }
     }
 
@@ -124,21 +181,67 @@ impl Animation {
 
     /// Sets the sections of the texture being displayed for each frame of the animation.
     ///
-    /// This method will reset the animation back to frame zero.
+    /// This method will reset the animation back to frame zero. If the animation has
+    /// per-frame lengths and the number of frames has changed, they will be cleared.
     pub fn set_frames(&mut self, new_frames: Vec<Rectangle>) {
+        if let Some(frame_lengths) = &self.frame_lengths {
+            if frame_lengths.len() != new_frames.len() {
+                self.frame_lengths = None;
+            }
+        }
+
         self.frames = new_frames;
 
         self.restart();
     }
 
     /// Gets the amount of time that each frame of the animation lasts for.
+    ///
+    /// If the animation has per-frame lengths, this value will not be used - see
+    /// [`frame_lengths`](Self::frame_lengths) and [`current_frame_length`](Self::current_frame_length).
     pub fn frame_length(&self) -> Duration {
         self.frame_length
     }
 
     /// Sets the amount of time that each frame of the animation lasts for.
+    ///
+    /// This will clear any per-frame lengths that have been set.
     pub fn set_frame_length(&mut self, new_frame_length: Duration) {
         self.frame_length = new_frame_length;
+        self.frame_lengths = None;
+    }
+
+    /// Gets the amount of time that each individual frame of the animation lasts for,
+    /// if they have been set.
+    pub fn frame_lengths(&self) -> Option<&[Duration]> {
+        self.frame_lengths.as_deref()
+    }
+
+    /// Sets the amount of time that each individual frame of the animation lasts for.
+    ///
+    /// Passing [`None`] will make every frame last for [`frame_length`](Self::frame_length).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of frame lengths does not match the number of frames.
+    pub fn set_frame_lengths(&mut self, new_frame_lengths: Option<Vec<Duration>>) {
+        if let Some(frame_lengths) = &new_frame_lengths {
+            assert_eq!(
+                self.frames.len(),
+                frame_lengths.len(),
+                "number of frame lengths must match the number of frames"
+            );
+        }
+
+        self.frame_lengths = new_frame_lengths;
+    }
+
+    /// Gets the amount of time that the current frame lasts for.
+    pub fn current_frame_length(&self) -> Duration {
+        match &self.frame_lengths {
+            Some(frame_lengths) => frame_lengths[self.current_frame],
+            None => self.frame_length,
+        }
     }
 
     /// Gets whether or not the animation is currently set to repeat when it reaches the end
@@ -180,8 +283,8 @@ impl Animation {
 
     /// Gets the duration that the current frame has been visible.
     ///
-    /// This can be used in combination with the [`frame_length`](Self::frame_length) method
-    /// in order to track the progress of the animation.
+    /// This can be used in combination with the [`current_frame_length`](Self::current_frame_length)
+    /// method in order to track the progress of the animation.
     pub fn current_frame_time(&self) -> Duration {
         self.timer
     }
@@ -194,7 +297,7 @@ impl Animation {
     ///
     /// The animation will not advance past the end of the current frame until the next call
     /// to [`advance`](Self::advance) or [`advance`](Self::advance_by). If a value is given
-    /// that is larger than [`frame_length`](Self::frame_length), this animation may
+    /// that is larger than [`current_frame_length`](Self::current_frame_length), this animation may
     /// skip frames.
     pub fn set_current_frame_time(&mut self, duration: Duration) {
         self.timer = duration;
@@ -212,3 +315,254 @@ impl Animation {
         self.current_frame < self.frames.len() - 1
     }
 }
+
+/// The length that will be used for frames that do not specify a delay, matching the
+/// behaviour of most web browsers.
+const DEFAULT_FRAME_LENGTH: Duration = Duration::from_millis(100);
+
+/// The decoded frames of an animated image, along with the length of each frame.
+///
+/// Animated GIF files are supported when the `texture_gif` feature is enabled, and animated
+/// PNG (APNG) files are supported when the `texture_png` feature is enabled. Any other image
+/// format supported by [`ImageData`] will be loaded as a single frame.
+///
+/// Each frame is stored at the full size of the image, with any partial updates from the
+/// original file already applied. Frames that do not specify a delay will last for
+/// 100 milliseconds.
+///
+/// To play the image back, convert it into an [`Animation`] via
+/// [`to_animation`](Self::to_animation), which will pack the frames into a single texture.
+#[derive(Debug, Clone)]
+pub struct AnimatedImage {
+    frames: Vec<ImageData>,
+    frame_lengths: Vec<Duration>,
+}
+
+impl AnimatedImage {
+    /// Loads an animated image from the given file.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::FailedToLoadAsset`] will be returned if the file could not be loaded.
+    /// * [`TetraError::InvalidTexture`] will be returned if the image data was invalid.
+    pub fn new<P>(path: P) -> Result<AnimatedImage>
+    where
+        P: AsRef<Path>,
+    {
+        AnimatedImage::from_encoded(&fs::read(path)?)
+    }
+
+    /// Decodes an animated image from a slice of data.
+    ///
+    /// This is useful in combination with [`include_bytes`](std::include_bytes), as it
+    /// allows you to include your image data directly in the binary.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::InvalidTexture`] will be returned if the image data was invalid.
+    pub fn from_encoded(data: &[u8]) -> Result<AnimatedImage> {
+        let format = image::guess_format(data).map_err(TetraError::InvalidTexture)?;
+
+        let frames = match format {
+            #[cfg(feature = "texture_gif")]
+            ImageFormat::Gif => GifDecoder::new(Cursor::new(data))
+                .and_then(|decoder| decoder.into_frames().collect_frames())
+                .map_err(TetraError::InvalidTexture)?,
+
+            #[cfg(feature = "texture_png")]
+            ImageFormat::Png => {
+                let decoder =
+                    PngDecoder::new(Cursor::new(data)).map_err(TetraError::InvalidTexture)?;
+
+                if decoder.is_apng() {
+                    decoder
+                        .apng()
+                        .into_frames()
+                        .collect_frames()
+                        .map_err(TetraError::InvalidTexture)?
+                } else {
+                    vec![single_frame(data)?]
+                }
+            }
+
+            _ => vec![single_frame(data)?],
+        };
+
+        if frames.is_empty() {
+            return Err(TetraError::InvalidTexture(ImageError::Decoding(
+                DecodingError::new(ImageFormatHint::Exact(format), "image has no frames"),
+            )));
+        }
+
+        let mut image = AnimatedImage {
+            frames: Vec::with_capacity(frames.len()),
+            frame_lengths: Vec::with_capacity(frames.len()),
+        };
+
+        for frame in frames {
+            let length = match Duration::from(frame.delay()) {
+                length if length.is_zero() => DEFAULT_FRAME_LENGTH,
+                length => length,
+            };
+
+            let buffer = frame.into_buffer();
+
+            image.frames.push(ImageData::from_data(
+                buffer.width() as i32,
+                buffer.height() as i32,
+                TextureFormat::Rgba8,
+                buffer.into_raw(),
+            )?);
+
+            image.frame_lengths.push(length);
+        }
+
+        Ok(image)
+    }
+
+    /// Returns the frames of the image.
+    pub fn frames(&self) -> &[ImageData] {
+        &self.frames
+    }
+
+    /// Returns the amount of time that each frame of the image lasts for.
+    pub fn frame_lengths(&self) -> &[Duration] {
+        &self.frame_lengths
+    }
+
+    /// Packs the frames of the image into a single atlas.
+    ///
+    /// The frames are laid out in a grid that is as close to square as possible. The
+    /// returned rectangles are the location of each frame within the atlas, in order.
+    pub fn to_atlas(&self) -> (ImageData, Vec<Rectangle>) {
+        let (frame_width, frame_height) = self.frames[0].size();
+
+        let columns = (self.frames.len() as f32).sqrt().ceil() as usize;
+        let rows = self.frames.len().div_ceil(columns);
+
+        let atlas_width = frame_width as usize * columns;
+        let atlas_height = frame_height as usize * rows;
+
+        let mut atlas = ImageData::from_data(
+            atlas_width as i32,
+            atlas_height as i32,
+            TextureFormat::Rgba8,
+            vec![0; atlas_width * atlas_height * TextureFormat::Rgba8.stride()],
+        )
+        .expect("atlas buffer should be the correct size");
+
+        let mut regions = Vec::with_capacity(self.frames.len());
+
+        let row_length = frame_width as usize * TextureFormat::Rgba8.stride();
+
+        for (i, frame) in self.frames.iter().enumerate() {
+            let x = (i % columns) * frame_width as usize;
+            let y = (i / columns) * frame_height as usize;
+
+            for (row, data) in frame.as_bytes().chunks_exact(row_length).enumerate() {
+                let start = (x + (y + row) * atlas_width) * TextureFormat::Rgba8.stride();
+
+                atlas.as_mut_bytes()[start..start + row_length].copy_from_slice(data);
+            }
+
+            regions.push(Rectangle::new(
+                x as f32,
+                y as f32,
+                frame_width as f32,
+                frame_height as f32,
+            ));
+        }
+
+        (atlas, regions)
+    }
+
+    /// Creates a new looping [`Animation`] from the image, packing the frames into
+    /// a single texture.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`] will be returned if the underlying graphics API encounters an error.
+    pub fn to_animation(&self, ctx: &mut Context) -> Result<Animation> {
+        let (atlas, regions) = self.to_atlas();
+        let texture = atlas.to_texture(ctx)?;
+
+        Ok(Animation::with_frame_lengths(
+            texture,
+            regions,
+            self.frame_lengths.clone(),
+        ))
+    }
+}
+
+fn single_frame(data: &[u8]) -> Result<Frame> {
+    let image = image::load_from_memory(data)
+        .map_err(TetraError::InvalidTexture)?
+        .into_rgba8();
+
+    Ok(Frame::new(image))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(value: u8) -> ImageData {
+        ImageData::from_data(1, 1, TextureFormat::Rgba8, vec![value; 4]).unwrap()
+    }
+
+    #[test]
+    fn atlas_layout() {
+        let image = AnimatedImage {
+            frames: vec![frame(1), frame(2), frame(3)],
+            frame_lengths: vec![DEFAULT_FRAME_LENGTH; 3],
+        };
+
+        let (atlas, regions) = image.to_atlas();
+
+        assert_eq!(atlas.size(), (2, 2));
+        assert_eq!(
+            &atlas.as_bytes()[..12],
+            &[1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3]
+        );
+        assert_eq!(regions[2], Rectangle::new(0.0, 1.0, 1.0, 1.0));
+    }
+
+    #[test]
+    #[cfg(feature = "texture_gif")]
+    fn decode_gif_frame_lengths() {
+        use image::codecs::gif::GifEncoder;
+        use image::{Delay, RgbaImage};
+
+        let mut data = Vec::new();
+
+        {
+            let mut encoder = GifEncoder::new(&mut data);
+
+            encoder
+                .encode_frames(vec![
+                    Frame::from_parts(
+                        RgbaImage::from_pixel(2, 2, image::Rgba([255, 0, 0, 255])),
+                        0,
+                        0,
+                        Delay::from_numer_denom_ms(50, 1),
+                    ),
+                    Frame::from_parts(
+                        RgbaImage::from_pixel(2, 2, image::Rgba([0, 0, 255, 255])),
+                        0,
+                        0,
+                        Delay::from_numer_denom_ms(0, 1),
+                    ),
+                ])
+                .unwrap();
+        }
+
+        let image = AnimatedImage::from_encoded(&data).unwrap();
+
+        assert_eq!(image.frames().len(), 2);
+        assert_eq!(
+            image.frame_lengths(),
+            &[Duration::from_millis(50), DEFAULT_FRAME_LENGTH]
+        );
+        assert_eq!(&image.frames()[1].as_bytes()[..4], &[0, 0, 255, 255]);
+    }
+}
@@ -1,6 +1,9 @@
+use std::time::Duration;
+
 use super::Rectangle;
 use crate::input;
 use crate::math::{Mat4, Vec2, Vec3};
+use crate::time;
 use crate::window;
 use crate::Context;
 
@@ -13,6 +16,21 @@ use crate::Context;
 /// The camera's matrix is cached internally as an optimization. After adjusting parameters
 /// on the camera, you can call the `update` method to recalculate the matrix.
 ///
+/// # Behaviours
+///
+/// The camera also has some optional built-in behaviours, which are driven by calling
+/// [`advance`](Self::advance) (or [`advance_by`](Self::advance_by)) once per frame:
+///
+/// * Following a target, with optional [smoothing](Self::follow_smoothing) and
+///   a [deadzone](Self::deadzone) - see [`follow`](Self::follow).
+/// * Screen shake, based on an amount of 'trauma' that decays over time - see
+///   [`add_trauma`](Self::add_trauma). The shake is only applied to the camera's matrix,
+///   so it does not affect the logical [`position`](Self::position) or [`rotation`](Self::rotation).
+///
+/// The camera's position can also be clamped to the edges of the world by setting
+/// [`bounds`](Self::bounds), and [`zoom_at`](Self::zoom_at) can be used to zoom in on
+/// a point (e.g. the mouse cursor).
+///
 /// # Examples
 ///
 /// The [`camera`](https://github.com/17cupsofcoffee/tetra/blob/main/examples/camera.rs)
@@ -53,7 +71,47 @@ pub struct Camera {
     /// (e.g. the screen, or a [`Canvas`](crate::graphics::Canvas)).
     pub viewport_height: f32,
 
+    /// How smoothly the camera moves towards its follow target.
+    ///
+    /// This is roughly the number of seconds that it takes for the camera to move two
+    /// thirds of the way towards the target. A value of zero (the default) will make
+    /// the camera snap to the target immediately.
+    pub follow_smoothing: f32,
+
+    /// An area around the center of the camera, in which the follow target can move
+    /// without the camera moving.
+    ///
+    /// The rectangle is relative to the camera's position - for example, a rectangle of
+    /// `Rectangle::new(-32.0, -16.0, 64.0, 32.0)` will allow the target to move 32 units
+    /// horizontally and 16 units vertically in each direction. Defaults to [`None`].
+    pub deadzone: Option<Rectangle>,
+
+    /// The area of the world that the camera is allowed to show.
+    ///
+    /// If this is set, calling [`update`](Self::update) will move the camera's position
+    /// so that the [`visible_rect`](Self::visible_rect) stays inside the bounds. If the
+    /// bounds are smaller than the visible area, the camera will be centered on them
+    /// instead. Defaults to [`None`].
+    pub bounds: Option<Rectangle>,
+
+    /// The maximum distance that screen shake can move the camera, in world units.
+    pub max_shake_offset: Vec2<f32>,
+
+    /// The maximum amount that screen shake can rotate the camera, in radians.
+    pub max_shake_rotation: f32,
+
+    /// How quickly the camera moves while shaking.
+    pub shake_frequency: f32,
+
+    /// How much trauma is removed from the camera each second.
+    pub trauma_decay: f32,
+
     matrix: Mat4<f32>,
+    follow_target: Option<Vec2<f32>>,
+    trauma: f32,
+    shake_time: f32,
+    shake_offset: Vec2<f32>,
+    shake_rotation: f32,
 }
 
 impl Camera {
@@ -69,7 +127,20 @@ impl Camera {
             viewport_width,
             viewport_height,
 
+            follow_smoothing: 0.0,
+            deadzone: None,
+            bounds: None,
+            max_shake_offset: Vec2::new(16.0, 16.0),
+            max_shake_rotation: 0.1,
+            shake_frequency: 15.0,
+            trauma_decay: 1.0,
+
             matrix: Mat4::translation_2d(Vec2::new(viewport_width / 2.0, viewport_height / 2.0)),
+            follow_target: None,
+            trauma: 0.0,
+            shake_time: 0.0,
+            shake_offset: Vec2::zero(),
+            shake_rotation: 0.0,
         }
     }
 
@@ -100,9 +171,14 @@ impl Camera {
 
     /// Recalculates the transformation matrix, based on the data currently contained
     /// within the camera.
+    ///
+    /// If [`bounds`](Self::bounds) are set, the camera's position will be clamped to
+    /// them before the matrix is calculated.
     pub fn update(&mut self) {
-        self.matrix = Mat4::translation_2d(-self.position);
-        self.matrix.rotate_z(self.rotation);
+        self.clamp_to_bounds();
+
+        self.matrix = Mat4::translation_2d(-self.view_position());
+        self.matrix.rotate_z(self.view_rotation());
         self.matrix
             .scale_3d(Vec3::new(self.scale.x, self.scale.y, 1.0));
         self.matrix.translate_2d(Vec2::new(
@@ -123,23 +199,154 @@ impl Camera {
         self.matrix
     }
 
+    /// Sets a target for the camera to follow.
+    ///
+    /// The camera will move towards the target when [`advance`](Self::advance) or
+    /// [`advance_by`](Self::advance_by) is called, taking into account the
+    /// [`follow_smoothing`](Self::follow_smoothing) and [`deadzone`](Self::deadzone)
+    /// settings. If the target is moving, you should call this method every frame to
+    /// update its position.
+    pub fn follow(&mut self, target: Vec2<f32>) {
+        self.follow_target = Some(target);
+    }
+
+    /// Stops the camera from following its target.
+    pub fn stop_following(&mut self) {
+        self.follow_target = None;
+    }
+
+    /// Returns the target that the camera is currently following, if any.
+    pub fn follow_target(&self) -> Option<Vec2<f32>> {
+        self.follow_target
+    }
+
+    /// Adds trauma to the camera, causing it to shake.
+    ///
+    /// Trauma is a value between zero and one, which decays over time (at the rate
+    /// specified by [`trauma_decay`](Self::trauma_decay)). The intensity of the shake
+    /// is proportional to the square of the trauma, so small amounts will cause
+    /// subtle movement, and larger amounts will cause much more violent shaking.
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.set_trauma(self.trauma + amount);
+    }
+
+    /// Returns the camera's current trauma.
+    pub fn trauma(&self) -> f32 {
+        self.trauma
+    }
+
+    /// Sets the camera's current trauma.
+    ///
+    /// The value will be clamped between zero and one.
+    pub fn set_trauma(&mut self, trauma: f32) {
+        self.trauma = trauma.clamp(0.0, 1.0);
+    }
+
+    /// Returns the offset currently being applied to the camera's position by screen shake.
+    pub fn shake_offset(&self) -> Vec2<f32> {
+        self.shake_offset
+    }
+
+    /// Returns the rotation currently being applied to the camera by screen shake.
+    pub fn shake_rotation(&self) -> f32 {
+        self.shake_rotation
+    }
+
+    /// Advances the camera's behaviours, and then recalculates the transformation matrix.
+    ///
+    /// This method uses the current [delta time](crate::time::get_delta_time)
+    /// to calculate how much time has passed.
+    pub fn advance(&mut self, ctx: &Context) {
+        self.advance_by(time::get_delta_time(ctx));
+    }
+
+    /// Advances the camera's behaviours by a specified amount of time, and then
+    /// recalculates the transformation matrix.
+    pub fn advance_by(&mut self, duration: Duration) {
+        let delta = duration.as_secs_f32();
+
+        if let Some(target) = self.follow_target {
+            let mut destination = target;
+
+            if let Some(deadzone) = self.deadzone {
+                let relative = target - self.position;
+
+                destination.x = self.position.x
+                    + (relative.x - deadzone.right()).max(0.0)
+                    + (relative.x - deadzone.x).min(0.0);
+
+                destination.y = self.position.y
+                    + (relative.y - deadzone.bottom()).max(0.0)
+                    + (relative.y - deadzone.y).min(0.0);
+            }
+
+            if self.follow_smoothing > 0.0 {
+                let t = 1.0 - (-delta / self.follow_smoothing).exp();
+                self.position += (destination - self.position) * t;
+            } else {
+                self.position = destination;
+            }
+        }
+
+        self.set_trauma(self.trauma - self.trauma_decay * delta);
+
+        if self.trauma > 0.0 {
+            self.shake_time += delta * self.shake_frequency;
+
+            let shake = self.trauma * self.trauma;
+
+            self.shake_offset = Vec2::new(
+                self.max_shake_offset.x * shake * noise(self.shake_time, 0.0),
+                self.max_shake_offset.y * shake * noise(self.shake_time, 1.0),
+            );
+
+            self.shake_rotation = self.max_shake_rotation * shake * noise(self.shake_time, 2.0);
+        } else {
+            self.shake_time = 0.0;
+            self.shake_offset = Vec2::zero();
+            self.shake_rotation = 0.0;
+        }
+
+        self.update();
+    }
+
+    /// Sets the camera's scale, while keeping the world position under the given point
+    /// (in camera co-ordinates, e.g. the mouse position) in the same place.
+    ///
+    /// This can be used to zoom in on the mouse cursor, rather than the center of
+    /// the screen.
+    pub fn zoom_at(&mut self, point: Vec2<f32>, scale: Vec2<f32>) {
+        let before = self.project(point);
+        self.scale = scale;
+        let after = self.project(point);
+
+        self.position += before - after;
+        self.update();
+    }
+
     /// Projects a point from world co-ordinates to camera co-ordinates.
+    ///
+    /// This takes into account any screen shake that is currently being applied, so that
+    /// the result matches what is currently being displayed.
     pub fn project(&self, point: Vec2<f32>) -> Vec2<f32> {
         let mut proj = Vec2::new(
             (point.x - self.viewport_width / 2.0) / self.scale.x,
             (point.y - self.viewport_height / 2.0) / self.scale.y,
         );
 
-        proj.rotate_z(-self.rotation);
-        proj += self.position;
+        proj.rotate_z(-self.view_rotation());
+        proj += self.view_position();
 
         proj
     }
 
     /// Projects a point from camera co-ordinates to world co-ordinates.
+    ///
+    /// This takes into account any screen shake that is currently being applied, so that
+    /// the result matches what is currently being displayed.
     pub fn unproject(&self, point: Vec2<f32>) -> Vec2<f32> {
-        let mut unproj = point - self.position;
-        unproj.rotate_z(self.rotation);
+        let mut unproj = point - self.view_position();
+        unproj.rotate_z(self.view_rotation());
 
         unproj.x = unproj.x * self.scale.x + self.viewport_width / 2.0;
         unproj.y = unproj.y * self.scale.y + self.viewport_height / 2.0;
@@ -180,7 +387,8 @@ impl Camera {
     /// contains the full camera viewport.
     ///
     /// Note that this method does not take into account any other transformations being
-    /// made to the view (e.g. screen scaling).
+    /// made to the view (e.g. screen scaling), or any screen shake that is currently
+    /// being applied.
     pub fn visible_rect(&self) -> Rectangle {
         let viewport_width = self.viewport_width / self.scale.x;
         let viewport_height = self.viewport_height / self.scale.y;
@@ -226,6 +434,42 @@ impl Camera {
             }
         }
     }
+
+    fn view_position(&self) -> Vec2<f32> {
+        self.position + self.shake_offset
+    }
+
+    fn view_rotation(&self) -> f32 {
+        self.rotation + self.shake_rotation
+    }
+
+    fn clamp_to_bounds(&mut self) {
+        if let Some(bounds) = self.bounds {
+            let visible = self.visible_rect();
+
+            self.position.x = clamp_axis(self.position.x, visible.width, bounds.x, bounds.width);
+            self.position.y = clamp_axis(self.position.y, visible.height, bounds.y, bounds.height);
+        }
+    }
+}
+
+fn clamp_axis(position: f32, visible_size: f32, bounds_start: f32, bounds_size: f32) -> f32 {
+    if visible_size >= bounds_size {
+        bounds_start + bounds_size / 2.0
+    } else {
+        let half = visible_size / 2.0;
+        position.clamp(bounds_start + half, bounds_start + bounds_size - half)
+    }
+}
+
+/// Smooth noise in the range of -1 to 1, made by layering sine waves with
+/// unrelated frequencies. The seed selects a different set of phases.
+fn noise(time: f32, seed: f32) -> f32 {
+    let a = (time + seed * 17.3).sin();
+    let b = (time * 2.17 + seed * 31.7).sin();
+    let c = (time * 4.31 + seed * 7.9).sin();
+
+    (a * 0.5 + b * 0.3 + c * 0.2).clamp(-1.0, 1.0)
 }
 
 #[cfg(test)]
@@ -323,4 +567,69 @@ mod tests {
         assert!(rect.width - 150.0 < 0.001);
         assert!(rect.height - 400.0 < 0.001);
     }
+
+    #[test]
+    fn zoom_at_keeps_point_fixed() {
+        let mut camera = Camera::new(800.0, 600.0);
+        camera.position = Vec2::new(50.0, -20.0);
+        camera.rotation = 0.3;
+
+        let point = Vec2::new(100.0, 150.0);
+        let before = camera.project(point);
+
+        camera.zoom_at(point, Vec2::new(3.0, 3.0));
+
+        let after = camera.project(point);
+
+        assert_eq!(camera.scale, Vec2::new(3.0, 3.0));
+        assert!((before - after).magnitude() < 0.001);
+    }
+
+    #[test]
+    fn bounds_clamping() {
+        let mut camera = Camera::new(200.0, 100.0);
+        camera.bounds = Some(Rectangle::new(0.0, 0.0, 1000.0, 50.0));
+        camera.position = Vec2::new(-500.0, 500.0);
+
+        camera.update();
+
+        // The bounds are shorter than the viewport, so the camera is centered vertically.
+        assert_eq!(camera.position, Vec2::new(100.0, 25.0));
+    }
+
+    #[test]
+    fn follow_with_deadzone() {
+        let mut camera = Camera::new(800.0, 600.0);
+        camera.deadzone = Some(Rectangle::new(-32.0, -16.0, 64.0, 32.0));
+
+        camera.follow(Vec2::new(20.0, 10.0));
+        camera.advance_by(Duration::from_millis(16));
+
+        assert_eq!(camera.position, Vec2::zero());
+
+        camera.follow(Vec2::new(100.0, -40.0));
+        camera.advance_by(Duration::from_millis(16));
+
+        assert_eq!(camera.position, Vec2::new(68.0, -24.0));
+    }
+
+    #[test]
+    fn shake_does_not_move_logical_position() {
+        let mut camera = Camera::new(800.0, 600.0);
+        camera.trauma_decay = 0.5;
+
+        camera.add_trauma(2.0);
+        assert_eq!(camera.trauma(), 1.0);
+
+        camera.advance_by(Duration::from_millis(500));
+
+        assert_eq!(camera.position, Vec2::zero());
+        assert!((camera.trauma() - 0.75).abs() < 0.001);
+        assert_ne!(camera.shake_offset(), Vec2::zero());
+
+        camera.advance_by(Duration::from_secs(2));
+
+        assert_eq!(camera.trauma(), 0.0);
+        assert_eq!(camera.shake_offset(), Vec2::zero());
+    }
 }
@@ -1,3 +1,4 @@
+use std::cell::RefCell;
 use std::rc::Rc;
 
 use crate::error::Result;
@@ -88,8 +89,9 @@ impl CanvasBuilder {
         Ok(Canvas {
             handle: Rc::new(attachments.canvas),
             texture: Texture::from_raw(attachments.color, ctx.graphics.default_filter_mode),
-            stencil_buffer: attachments.depth_stencil.map(Rc::new),
+            stencil_buffer: Rc::new(RefCell::new(attachments.depth_stencil)),
             multisample: attachments.multisample_color.map(Rc::new),
+            samples: attachments.samples,
         })
     }
 }
@@ -125,8 +127,9 @@ impl CanvasBuilder {
 pub struct Canvas {
     pub(crate) handle: Rc<RawCanvas>,
     pub(crate) texture: Texture,
-    pub(crate) stencil_buffer: Option<Rc<RawRenderbuffer>>,
+    pub(crate) stencil_buffer: Rc<RefCell<Option<RawRenderbuffer>>>,
     pub(crate) multisample: Option<Rc<RawRenderbuffer>>,
+    pub(crate) samples: u8,
 }
 
 impl Canvas {
@@ -158,6 +161,33 @@ impl Canvas {
         self.texture.draw(ctx, params)
     }
 
+    /// Returns whether the canvas has a stencil buffer attached.
+    ///
+    /// Stencil buffers can be requested up front via
+    /// [`CanvasBuilder::stencil_buffer`], and will also be attached automatically
+    /// when using the [`mask`](crate::graphics::mask) API.
+    pub fn has_stencil_buffer(&self) -> bool {
+        self.stencil_buffer.borrow().is_some()
+    }
+
+    /// Attaches a stencil buffer to the canvas, if it does not already have one.
+    pub(crate) fn ensure_stencil_buffer(&self, ctx: &mut Context) -> Result {
+        let mut stencil_buffer = self.stencil_buffer.borrow_mut();
+
+        if stencil_buffer.is_none() {
+            let (width, height) = self.size();
+
+            *stencil_buffer = Some(ctx.device.attach_depth_stencil_buffer(
+                &self.handle,
+                width,
+                height,
+                self.samples,
+            )?);
+        }
+
+        Ok(())
+    }
+
     /// Returns the width of the canvas.
     pub fn width(&self) -> i32 {
         self.texture.width()
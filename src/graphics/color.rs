@@ -52,24 +52,127 @@ impl Color {
     }
 
     /// Creates a new `Color`, with the specified RGB integer (0-255) values and the alpha set to 255.
-    pub fn rgb8(r: u8, g: u8, b: u8) -> Color {
-        let r = f32::from(r) / 255.0;
-        let g = f32::from(g) / 255.0;
-        let b = f32::from(b) / 255.0;
+    pub const fn rgb8(r: u8, g: u8, b: u8) -> Color {
+        let r = r as f32 / 255.0;
+        let g = g as f32 / 255.0;
+        let b = b as f32 / 255.0;
 
         Color { r, g, b, a: 1.0 }
     }
 
     /// Creates a new `Color`, with the specified RGBA (0-255) integer values.
-    pub fn rgba8(r: u8, g: u8, b: u8, a: u8) -> Color {
-        let r = f32::from(r) / 255.0;
-        let g = f32::from(g) / 255.0;
-        let b = f32::from(b) / 255.0;
-        let a = f32::from(a) / 255.0;
+    pub const fn rgba8(r: u8, g: u8, b: u8, a: u8) -> Color {
+        let r = r as f32 / 255.0;
+        let g = g as f32 / 255.0;
+        let b = b as f32 / 255.0;
+        let a = a as f32 / 255.0;
 
         Color { r, g, b, a }
     }
 
+    /// Creates a new `Color` with the RGB components all set to the same value, and the alpha
+    /// set to 1.0.
+    pub const fn gray(value: f32) -> Color {
+        Color::rgb(value, value, value)
+    }
+
+    /// Creates a new `Color` from HSV (hue, saturation, value) components, with the alpha
+    /// set to 1.0.
+    ///
+    /// The hue is specified in degrees, and will wrap around if it is outside of the range
+    /// `0.0` to `360.0`. The saturation and value should be between `0.0` and `1.0`.
+    pub fn hsv(h: f32, s: f32, v: f32) -> Color {
+        Color::hsva(h, s, v, 1.0)
+    }
+
+    /// Creates a new `Color` from HSV (hue, saturation, value) components and an alpha value.
+    ///
+    /// See [`Color::hsv`] for the expected ranges of the components.
+    pub fn hsva(h: f32, s: f32, v: f32, a: f32) -> Color {
+        let chroma = v * s;
+        let (r, g, b) = hue_to_rgb(h, chroma);
+        let m = v - chroma;
+
+        Color::rgba(r + m, g + m, b + m, a)
+    }
+
+    /// Creates a new `Color` from HSL (hue, saturation, lightness) components, with the alpha
+    /// set to 1.0.
+    ///
+    /// The hue is specified in degrees, and will wrap around if it is outside of the range
+    /// `0.0` to `360.0`. The saturation and lightness should be between `0.0` and `1.0`.
+    pub fn hsl(h: f32, s: f32, l: f32) -> Color {
+        Color::hsla(h, s, l, 1.0)
+    }
+
+    /// Creates a new `Color` from HSL (hue, saturation, lightness) components and an alpha value.
+    ///
+    /// See [`Color::hsl`] for the expected ranges of the components.
+    pub fn hsla(h: f32, s: f32, l: f32, a: f32) -> Color {
+        let chroma = (1.0 - (2.0 * l - 1.0).abs()) * s;
+        let (r, g, b) = hue_to_rgb(h, chroma);
+        let m = l - chroma / 2.0;
+
+        Color::rgba(r + m, g + m, b + m, a)
+    }
+
+    /// Creates a new `Color` from [HSLuv](https://www.hsluv.org/) components, with the alpha
+    /// set to 1.0.
+    ///
+    /// HSLuv is a perceptually uniform alternative to HSL - colors with the same lightness
+    /// will appear equally bright, regardless of their hue. This makes it a good fit for
+    /// generating palettes.
+    ///
+    /// The hue is specified in degrees, and will wrap around if it is outside of the range
+    /// `0.0` to `360.0`. The saturation and lightness should be between `0.0` and `1.0`.
+    pub fn hsluv(h: f32, s: f32, l: f32) -> Color {
+        Color::hsluva(h, s, l, 1.0)
+    }
+
+    /// Creates a new `Color` from [HSLuv](https://www.hsluv.org/) components and an alpha value.
+    ///
+    /// See [`Color::hsluv`] for the expected ranges of the components.
+    pub fn hsluva(h: f32, s: f32, l: f32, a: f32) -> Color {
+        let [r, g, b] = hsluv::to_rgb([h as f64, s as f64 * 100.0, l as f64 * 100.0]);
+
+        Color::rgba(r as f32, g as f32, b as f32, a)
+    }
+
+    /// Creates a new `Color` from [OKLab](https://bottosson.github.io/posts/oklab/) components,
+    /// with the alpha set to 1.0.
+    ///
+    /// OKLab is a perceptually uniform color space, which makes it well suited to blending
+    /// between colors. The lightness should be between `0.0` and `1.0`, and the `a` and `b`
+    /// components will generally be between `-0.4` and `0.4`.
+    ///
+    /// Colors that cannot be represented in sRGB will not be clamped - call
+    /// [`clamp`](Self::clamp) on the result if this is needed.
+    pub fn oklab(l: f32, a: f32, b: f32) -> Color {
+        Color::oklaba(l, a, b, 1.0)
+    }
+
+    /// Creates a new `Color` from [OKLab](https://bottosson.github.io/posts/oklab/) components
+    /// and an alpha value.
+    ///
+    /// See [`Color::oklab`] for the expected ranges of the components.
+    pub fn oklaba(l: f32, a: f32, b: f32, alpha: f32) -> Color {
+        let l_ = l + 0.396_337_78 * a + 0.215_803_76 * b;
+        let m_ = l - 0.105_561_346 * a - 0.063_854_17 * b;
+        let s_ = l - 0.089_484_18 * a - 1.291_485_5 * b;
+
+        let l = l_ * l_ * l_;
+        let m = m_ * m_ * m_;
+        let s = s_ * s_ * s_;
+
+        Color::rgba(
+            4.076_741_7 * l - 3.307_711_6 * m + 0.230_969_94 * s,
+            -1.268_438 * l + 2.609_757_4 * m - 0.341_319_38 * s,
+            -0.004_196_086_3 * l - 0.703_418_6 * m + 1.707_614_7 * s,
+            alpha,
+        )
+        .to_srgb()
+    }
+
     /// Creates a new `Color` using a hexidecimal color code, panicking if the input is
     /// invalid.
     ///
@@ -167,6 +270,209 @@ impl Color {
         }
     }
 
+    /// Returns the hue (in degrees), saturation and value of the color.
+    ///
+    /// The alpha component is ignored.
+    pub fn to_hsv(self) -> (f32, f32, f32) {
+        let max = self.r.max(self.g).max(self.b);
+        let min = self.r.min(self.g).min(self.b);
+        let chroma = max - min;
+
+        let saturation = if max > 0.0 { chroma / max } else { 0.0 };
+
+        (self.hue(max, chroma), saturation, max)
+    }
+
+    /// Returns the hue (in degrees), saturation and lightness of the color.
+    ///
+    /// The alpha component is ignored.
+    pub fn to_hsl(self) -> (f32, f32, f32) {
+        let max = self.r.max(self.g).max(self.b);
+        let min = self.r.min(self.g).min(self.b);
+        let chroma = max - min;
+
+        let lightness = (max + min) / 2.0;
+
+        let saturation = if chroma > 0.0 {
+            chroma / (1.0 - (2.0 * lightness - 1.0).abs())
+        } else {
+            0.0
+        };
+
+        (self.hue(max, chroma), saturation, lightness)
+    }
+
+    /// Returns the [HSLuv](https://www.hsluv.org/) hue (in degrees), saturation and lightness
+    /// of the color.
+    ///
+    /// The alpha component is ignored.
+    pub fn to_hsluv(self) -> (f32, f32, f32) {
+        let [h, s, l] = hsluv::from_rgb([self.r as f64, self.g as f64, self.b as f64]);
+
+        (h as f32, s as f32 / 100.0, l as f32 / 100.0)
+    }
+
+    /// Returns the [OKLab](https://bottosson.github.io/posts/oklab/) lightness, `a` and `b`
+    /// components of the color.
+    ///
+    /// The alpha component is ignored.
+    pub fn to_oklab(self) -> (f32, f32, f32) {
+        let Color { r, g, b, .. } = self.to_linear();
+
+        let l = 0.412_221_46 * r + 0.536_332_55 * g + 0.051_445_995 * b;
+        let m = 0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b;
+        let s = 0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b;
+
+        let l_ = l.cbrt();
+        let m_ = m.cbrt();
+        let s_ = s.cbrt();
+
+        (
+            0.210_454_26 * l_ + 0.793_617_8 * m_ - 0.004_072_047 * s_,
+            1.977_998_5 * l_ - 2.428_592_2 * m_ + 0.450_593_7 * s_,
+            0.025_904_037 * l_ + 0.782_771_77 * m_ - 0.808_675_77 * s_,
+        )
+    }
+
+    /// Converts the color from sRGB to linear RGB.
+    ///
+    /// Colors in Tetra are usually assumed to be sRGB (i.e. gamma-encoded), but lighting
+    /// and blending calculations are more accurate when carried out in linear space.
+    /// The alpha component is left unchanged.
+    pub fn to_linear(self) -> Color {
+        Color {
+            r: srgb_to_linear(self.r),
+            g: srgb_to_linear(self.g),
+            b: srgb_to_linear(self.b),
+            a: self.a,
+        }
+    }
+
+    /// Converts the color from linear RGB to sRGB.
+    ///
+    /// This is the inverse of [`to_linear`](Self::to_linear).
+    pub fn to_srgb(self) -> Color {
+        Color {
+            r: linear_to_srgb(self.r),
+            g: linear_to_srgb(self.g),
+            b: linear_to_srgb(self.b),
+            a: self.a,
+        }
+    }
+
+    /// Linearly interpolates between two colors, component by component.
+    ///
+    /// A `t` value of `0.0` will return `self`, and a value of `1.0` will return `other`.
+    /// To interpolate in a different color space, use [`mix`](Self::mix).
+    pub fn lerp(self, other: Color, t: f32) -> Color {
+        Color {
+            r: self.r + (other.r - self.r) * t,
+            g: self.g + (other.g - self.g) * t,
+            b: self.b + (other.b - self.b) * t,
+            a: self.a + (other.a - self.a) * t,
+        }
+    }
+
+    /// Interpolates between two colors in the specified color space.
+    ///
+    /// A `t` value of `0.0` will return `self`, and a value of `1.0` will return `other`.
+    /// Hues will be interpolated in whichever direction is shortest, and the alpha
+    /// component is always interpolated linearly.
+    pub fn mix(self, other: Color, t: f32, space: ColorSpace) -> Color {
+        let alpha = self.a + (other.a - self.a) * t;
+
+        let lerp = |a: f32, b: f32| a + (b - a) * t;
+        let lerp_hue = |a: f32, b: f32| a + wrap_degrees(b - a) * t;
+
+        let mixed = match space {
+            ColorSpace::Srgb => self.lerp(other, t),
+            ColorSpace::Linear => self.to_linear().lerp(other.to_linear(), t).to_srgb(),
+            ColorSpace::Hsv => {
+                let (h1, s1, v1) = self.to_hsv();
+                let (h2, s2, v2) = other.to_hsv();
+                Color::hsv(lerp_hue(h1, h2), lerp(s1, s2), lerp(v1, v2))
+            }
+            ColorSpace::Hsl => {
+                let (h1, s1, l1) = self.to_hsl();
+                let (h2, s2, l2) = other.to_hsl();
+                Color::hsl(lerp_hue(h1, h2), lerp(s1, s2), lerp(l1, l2))
+            }
+            ColorSpace::Hsluv => {
+                let (h1, s1, l1) = self.to_hsluv();
+                let (h2, s2, l2) = other.to_hsluv();
+                Color::hsluv(lerp_hue(h1, h2), lerp(s1, s2), lerp(l1, l2))
+            }
+            ColorSpace::Oklab => {
+                let (l1, a1, b1) = self.to_oklab();
+                let (l2, a2, b2) = other.to_oklab();
+                Color::oklab(lerp(l1, l2), lerp(a1, a2), lerp(b1, b2))
+            }
+        };
+
+        mixed.with_alpha(alpha)
+    }
+
+    /// Returns the color with its hue rotated by the specified number of degrees.
+    ///
+    /// The saturation and lightness (as defined by [HSLuv](https://www.hsluv.org/)) are
+    /// preserved, so the result will appear as bright as the original color.
+    pub fn rotate_hue(self, degrees: f32) -> Color {
+        let (h, s, l) = self.to_hsluv();
+
+        Color::hsluva(h + degrees, s, l, self.a)
+    }
+
+    /// Returns `count` colors evenly spaced along a gradient.
+    ///
+    /// The gradient passes through each of the specified colors in turn, and is interpolated
+    /// in the given color space. This can be used to build lookup tables for gradient maps,
+    /// or to generate a set of shades between two colors.
+    ///
+    /// If `colors` is empty, an empty `Vec` will be returned.
+    pub fn gradient(colors: &[Color], count: usize, space: ColorSpace) -> Vec<Color> {
+        match colors {
+            [] => Vec::new(),
+            [color] => vec![*color; count],
+            _ => (0..count)
+                .map(|i| {
+                    let t = if count > 1 {
+                        i as f32 / (count - 1) as f32
+                    } else {
+                        0.0
+                    };
+
+                    let position = t * (colors.len() - 1) as f32;
+                    let index = usize::min(position as usize, colors.len() - 2);
+
+                    colors[index].mix(colors[index + 1], position - index as f32, space)
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns `count` colors with hues evenly spaced around the color wheel, starting
+    /// with this color.
+    ///
+    /// For example, a count of `2` will give the complementary color, and a count of `3`
+    /// will give a triadic color scheme. Hues are rotated via [`rotate_hue`](Self::rotate_hue).
+    pub fn hue_palette(self, count: usize) -> Vec<Color> {
+        (0..count)
+            .map(|i| self.rotate_hue(360.0 * i as f32 / count as f32))
+            .collect()
+    }
+
+    fn hue(self, max: f32, chroma: f32) -> f32 {
+        if chroma <= 0.0 {
+            0.0
+        } else if max == self.r {
+            60.0 * ((self.g - self.b) / chroma).rem_euclid(6.0)
+        } else if max == self.g {
+            60.0 * ((self.b - self.r) / chroma + 2.0)
+        } else {
+            60.0 * ((self.r - self.g) / chroma + 4.0)
+        }
+    }
+
     // These constants should remain at the bottom of the impl block to keep
     // the docs readable - don't want to have to scroll through a load of colors
     // to get to the methods!
@@ -191,6 +497,233 @@ fn clamp_f32(val: f32) -> f32 {
     f32::min(f32::max(0.0, val), 1.0)
 }
 
+/// A color space that colors can be interpolated in.
+///
+/// Different color spaces will give different results when [mixing](Color::mix)
+/// colors - for example, blending between red and green in sRGB will pass through
+/// a muddy brown, whereas blending in HSV will pass through yellow.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ColorSpace {
+    /// The standard (gamma-encoded) RGB color space.
+    Srgb,
+
+    /// Linear RGB.
+    Linear,
+
+    /// Hue, saturation and value.
+    Hsv,
+
+    /// Hue, saturation and lightness.
+    Hsl,
+
+    /// [HSLuv](https://www.hsluv.org/), a perceptually uniform version of HSL.
+    Hsluv,
+
+    /// [OKLab](https://bottosson.github.io/posts/oklab/), a perceptually uniform color space.
+    Oklab,
+}
+
+/// Converts a hue (in degrees) and chroma to RGB components, without the lightness offset.
+fn hue_to_rgb(h: f32, chroma: f32) -> (f32, f32, f32) {
+    let sector = h.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (sector.rem_euclid(2.0) - 1.0).abs());
+
+    match sector as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    }
+}
+
+/// Wraps an angle in degrees into the range -180 to 180.
+fn wrap_degrees(degrees: f32) -> f32 {
+    (degrees + 180.0).rem_euclid(360.0) - 180.0
+}
+
+fn srgb_to_linear(val: f32) -> f32 {
+    if val <= 0.040_45 {
+        val / 12.92
+    } else {
+        ((val + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(val: f32) -> f32 {
+    if val <= 0.003_130_8 {
+        val * 12.92
+    } else {
+        1.055 * val.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Conversions between sRGB and HSLuv, based on the reference implementation.
+///
+/// These are carried out in double precision, as the intermediate values are
+/// quite large.
+mod hsluv {
+    use std::f64::consts::TAU;
+
+    const M: [[f64; 3]; 3] = [
+        [
+            3.240_969_941_904_521,
+            -1.537_383_177_570_093,
+            -0.498_610_760_293,
+        ],
+        [
+            -0.969_243_636_280_87,
+            1.875_967_501_507_72,
+            0.041_555_057_407_175,
+        ],
+        [
+            0.055_630_079_696_993,
+            -0.203_976_958_888_97,
+            1.056_971_514_242_878,
+        ],
+    ];
+
+    const M_INV: [[f64; 3]; 3] = [
+        [
+            0.412_390_799_265_95,
+            0.357_584_339_383_87,
+            0.180_480_788_401_83,
+        ],
+        [
+            0.212_639_005_871_51,
+            0.715_168_678_767_75,
+            0.072_192_315_360_733,
+        ],
+        [
+            0.019_330_818_715_591,
+            0.119_194_779_794_62,
+            0.950_532_152_249_66,
+        ],
+    ];
+
+    const REF_U: f64 = 0.197_830_006_642_83;
+    const REF_V: f64 = 0.468_319_994_938_79;
+    const KAPPA: f64 = 903.296_296_296_296_3;
+    const EPSILON: f64 = 0.008_856_451_679_035_631;
+
+    pub(super) fn to_rgb([h, s, l]: [f64; 3]) -> [f64; 3] {
+        // HSLuv -> LCh
+        let c = if (0.000_000_01..=99.999_999_9).contains(&l) {
+            max_chroma(l, h) / 100.0 * s
+        } else {
+            0.0
+        };
+
+        // LCh -> Luv
+        let h_rad = h.rem_euclid(360.0) / 360.0 * TAU;
+        let u = c * h_rad.cos();
+        let v = c * h_rad.sin();
+
+        // Luv -> XYZ
+        let xyz = if l == 0.0 {
+            [0.0; 3]
+        } else {
+            let var_u = u / (13.0 * l) + REF_U;
+            let var_v = v / (13.0 * l) + REF_V;
+            let y = l_to_y(l);
+            let x = -(9.0 * y * var_u) / ((var_u - 4.0) * var_v - var_u * var_v);
+            let z = (9.0 * y - 15.0 * var_v * y - var_v * x) / (3.0 * var_v);
+            [x, y, z]
+        };
+
+        // XYZ -> sRGB
+        M.map(|row| super::linear_to_srgb(dot(row, xyz) as f32) as f64)
+    }
+
+    pub(super) fn from_rgb(rgb: [f64; 3]) -> [f64; 3] {
+        // sRGB -> XYZ
+        let linear = rgb.map(|c| super::srgb_to_linear(c as f32) as f64);
+        let [x, y, z] = M_INV.map(|row| dot(row, linear));
+
+        // XYZ -> Luv
+        let l = y_to_l(y);
+        let divider = x + 15.0 * y + 3.0 * z;
+
+        let (u, v) = if l == 0.0 || divider == 0.0 {
+            (0.0, 0.0)
+        } else {
+            let var_u = 4.0 * x / divider;
+            let var_v = 9.0 * y / divider;
+            (13.0 * l * (var_u - REF_U), 13.0 * l * (var_v - REF_V))
+        };
+
+        // Luv -> LCh
+        let c = (u * u + v * v).sqrt();
+
+        let h = if c < 0.000_000_01 {
+            0.0
+        } else {
+            v.atan2(u).to_degrees().rem_euclid(360.0)
+        };
+
+        // LCh -> HSLuv
+        let s = if (0.000_000_01..=99.999_999_9).contains(&l) {
+            c / max_chroma(l, h) * 100.0
+        } else {
+            0.0
+        };
+
+        [h, s, l]
+    }
+
+    /// Returns the maximum chroma that can be represented in sRGB for the given
+    /// lightness and hue.
+    fn max_chroma(l: f64, h: f64) -> f64 {
+        let h_rad = h.rem_euclid(360.0) / 360.0 * TAU;
+
+        let sub1 = (l + 16.0).powi(3) / 1_560_896.0;
+        let sub2 = if sub1 > EPSILON { sub1 } else { l / KAPPA };
+
+        let mut min = f64::MAX;
+
+        for [m1, m2, m3] in M {
+            for t in [0.0, 1.0] {
+                let top1 = (284_517.0 * m1 - 94_839.0 * m3) * sub2;
+                let top2 = (838_422.0 * m3 + 769_860.0 * m2 + 731_718.0 * m1) * l * sub2
+                    - 769_860.0 * t * l;
+                let bottom = (632_260.0 * m3 - 126_452.0 * m2) * sub2 + 126_452.0 * t;
+
+                let slope = top1 / bottom;
+                let intercept = top2 / bottom;
+
+                let length = intercept / (h_rad.sin() - slope * h_rad.cos());
+
+                if length >= 0.0 {
+                    min = min.min(length);
+                }
+            }
+        }
+
+        min
+    }
+
+    fn l_to_y(l: f64) -> f64 {
+        if l <= 8.0 {
+            l / KAPPA
+        } else {
+            ((l + 16.0) / 116.0).powi(3)
+        }
+    }
+
+    fn y_to_l(y: f64) -> f64 {
+        if y <= EPSILON {
+            y * KAPPA
+        } else {
+            116.0 * y.cbrt() - 16.0
+        }
+    }
+
+    fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+        a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+    }
+}
+
 impl From<Color> for Vec4<f32> {
     fn from(color: Color) -> Vec4<f32> {
         Vec4::new(color.r, color.g, color.b, color.a)
@@ -412,7 +945,7 @@ impl DivAssign<f32> for Color {
 
 #[cfg(test)]
 mod tests {
-    use super::Color;
+    use super::{Color, ColorSpace};
 
     #[test]
     fn rgb8_creation() {
@@ -444,6 +977,70 @@ mod tests {
         assert!(Color::try_hex("ZZZZZZ").is_err());
     }
 
+    #[test]
+    fn hsv_and_hsl_conversions() {
+        assert!(similar_color(
+            Color::rgb(0.0, 1.0, 0.0),
+            Color::hsv(120.0, 1.0, 1.0)
+        ));
+        assert!(similar_color(
+            Color::rgb(0.0, 0.0, 1.0),
+            Color::hsl(-120.0, 1.0, 0.5)
+        ));
+
+        let color = Color::rgb(0.2, 0.4, 0.6);
+
+        let (h, s, v) = color.to_hsv();
+        assert!(similar_color(color, Color::hsv(h, s, v)));
+
+        let (h, s, l) = color.to_hsl();
+        assert!(similar_color(color, Color::hsl(h, s, l)));
+    }
+
+    #[test]
+    fn hsluv_conversions() {
+        // Reference values from hsluv.org.
+        let (h, s, l) = Color::RED.to_hsluv();
+
+        assert!((h - 12.177).abs() < 0.01);
+        assert!((s - 1.0).abs() < 0.001);
+        assert!((l - 0.532_37).abs() < 0.001);
+
+        let color = Color::rgb(0.2, 0.4, 0.6);
+        let (h, s, l) = color.to_hsluv();
+        assert!(similar_color(color, Color::hsluv(h, s, l)));
+    }
+
+    #[test]
+    fn oklab_conversions() {
+        let (l, a, b) = Color::WHITE.to_oklab();
+
+        assert!((l - 1.0).abs() < 0.001);
+        assert!(a.abs() < 0.001);
+        assert!(b.abs() < 0.001);
+
+        let color = Color::rgb(0.2, 0.4, 0.6);
+        let (l, a, b) = color.to_oklab();
+        assert!(similar_color(color, Color::oklab(l, a, b)));
+    }
+
+    #[test]
+    fn mixing() {
+        assert_eq!(
+            Color::rgba(0.5, 0.5, 0.0, 0.5),
+            Color::RED.lerp(Color::GREEN.with_alpha(0.0), 0.5)
+        );
+
+        // Mixing in HSV should take the shortest path around the color wheel.
+        assert!(similar_color(
+            Color::hsv(0.0, 1.0, 1.0),
+            Color::hsv(330.0, 1.0, 1.0).mix(Color::hsv(30.0, 1.0, 1.0), 0.5, ColorSpace::Hsv)
+        ));
+
+        let gradient = Color::gradient(&[Color::BLACK, Color::WHITE], 3, ColorSpace::Srgb);
+        assert_eq!(gradient, [Color::BLACK, Color::gray(0.5), Color::WHITE]);
+    }
+
     #[test]
     fn to_premultiplied() {
         assert_eq!(
@@ -549,4 +1146,11 @@ mod tests {
             && (a.b - b.b).abs() < std::f32::EPSILON
             && (a.a - b.a).abs() < std::f32::EPSILON
     }
+
+    fn similar_color(a: Color, b: Color) -> bool {
+        (a.r - b.r).abs() < 0.0001
+            && (a.g - b.g).abs() < 0.0001
+            && (a.b - b.b).abs() < 0.0001
+            && (a.a - b.a).abs() < 0.0001
+    }
 }
@@ -1,4 +1,4 @@
-use crate::graphics::Color;
+use crate::graphics::{Color, Rectangle};
 use crate::math::{Mat4, Vec2, Vec3};
 
 /// Parameters that can be manipulated when drawing an object.
@@ -12,7 +12,8 @@ pub struct DrawParams {
 
     /// The scale that the graphic should be drawn at. Defaults to `(1.0, 1.0)`.
     ///
-    /// This can be set to a negative value to flip the graphic around the origin.
+    /// This can be set to a negative value to flip the graphic around the origin, but
+    /// [`flip_x`](Self::flip_x) and [`flip_y`](Self::flip_y) are usually clearer.
     pub scale: Vec2<f32>,
 
     /// The origin of the graphic. Defaults to `(0.0, 0.0)` (the top left).
@@ -22,9 +23,45 @@ pub struct DrawParams {
     /// relative to the center of the image.
     pub origin: Vec2<f32>,
 
+    /// The origin of the graphic, relative to its size. Defaults to `(0.0, 0.0)` (the top left).
+    ///
+    /// For example, setting this to `(0.5, 0.5)` will make transformations relative to the
+    /// center of the graphic, and setting it to `(0.5, 1.0)` will make them relative to the
+    /// bottom center, regardless of how big the graphic is. This is added to the
+    /// [`origin`](Self::origin), so the two can be combined.
+    ///
+    /// The size of the graphic is determined by the type being drawn (e.g. the size of the
+    /// texture region, or the bounds of the text). Types that do not have a size, such as
+    /// meshes, will ignore this value.
+    pub pivot: Vec2<f32>,
+
     /// The rotation of the graphic, in radians. Defaults to `0.0`.
     pub rotation: f32,
 
+    /// The skew of the graphic, in radians. Defaults to `(0.0, 0.0)`.
+    ///
+    /// The X value shears the graphic horizontally (so that lower parts of the graphic are
+    /// shifted to the right), and the Y value shears the graphic vertically (so that parts
+    /// further to the right are shifted down). Skewing is applied relative to the origin,
+    /// after scaling and before rotation.
+    pub skew: Vec2<f32>,
+
+    /// Whether the graphic should be flipped horizontally. Defaults to `false`.
+    ///
+    /// Unlike using a negative [`scale`](Self::scale), the graphic is mirrored in place,
+    /// so the origin, pivot and position behave exactly the same as if it was not flipped.
+    /// Types that do not have a size, such as meshes, will be flipped around their origin
+    /// instead.
+    pub flip_x: bool,
+
+    /// Whether the graphic should be flipped vertically. Defaults to `false`.
+    ///
+    /// Unlike using a negative [`scale`](Self::scale), the graphic is mirrored in place,
+    /// so the origin, pivot and position behave exactly the same as if it was not flipped.
+    /// Types that do not have a size, such as meshes, will be flipped around their origin
+    /// instead.
+    pub flip_y: bool,
+
     /// A color to multiply the graphic by. Defaults to [`Color::WHITE`].
     pub color: Color,
 }
@@ -53,12 +90,39 @@ impl DrawParams {
         self
     }
 
+    /// Sets the origin of the graphic, relative to its size.
+    pub fn pivot(mut self, pivot: Vec2<f32>) -> DrawParams {
+        self.pivot = pivot;
+        self
+    }
+
     /// Sets the rotation of the graphic, in radians.
     pub fn rotation(mut self, rotation: f32) -> DrawParams {
         self.rotation = rotation;
         self
     }
 
+    /// Sets the skew of the graphic, in radians.
+    ///
+    /// The X skew shears the graphic horizontally, and the Y skew shears it vertically. If
+    /// the product of their tangents is greater than one, the graphic will be mirrored.
+    pub fn skew(mut self, skew: Vec2<f32>) -> DrawParams {
+        self.skew = skew;
+        self
+    }
+
+    /// Sets whether the graphic should be flipped horizontally.
+    pub fn flip_x(mut self, flip_x: bool) -> DrawParams {
+        self.flip_x = flip_x;
+        self
+    }
+
+    /// Sets whether the graphic should be flipped vertically.
+    pub fn flip_y(mut self, flip_y: bool) -> DrawParams {
+        self.flip_y = flip_y;
+        self
+    }
+
     /// Sets the color to multiply the graphic by.
     pub fn color(mut self, color: Color) -> DrawParams {
         self.color = color;
@@ -68,14 +132,93 @@ impl DrawParams {
     /// Creates a new transformation matrix equivalent to this set of params.
     ///
     /// This method does not take into account `color`, as it cannot
-    /// be represented via a matrix.
+    /// be represented via a matrix. It also does not take into account
+    /// `pivot`, and flips around the origin rather than in place, as both
+    /// depend on the size of the graphic - use
+    /// [`to_matrix_with_bounds`](Self::to_matrix_with_bounds) if you need
+    /// the exact transformation that will be used when drawing.
     pub fn to_matrix(&self) -> Mat4<f32> {
         let mut matrix = Mat4::translation_2d(-self.origin);
-        matrix.scale_3d(Vec3::from(self.scale));
+        matrix.scale_3d(Vec3::from(self.flipped_scale()));
+
+        if self.skew != Vec2::zero() {
+            let mut skew = Mat4::identity();
+            skew.cols.y.x = self.skew.x.tan();
+            skew.cols.x.y = self.skew.y.tan();
+
+            matrix = skew * matrix;
+        }
+
         matrix.rotate_z(self.rotation);
         matrix.translate_2d(self.position);
         matrix
     }
+
+    /// Creates a new transformation matrix equivalent to this set of params, applying
+    /// the `pivot` and flipping relative to the given bounds.
+    ///
+    /// This method does not take into account `color`, as it cannot
+    /// be represented via a matrix.
+    pub fn to_matrix_with_bounds(&self, bounds: Rectangle) -> Mat4<f32> {
+        self.resolve(bounds).to_matrix()
+    }
+
+    /// Returns a copy of the params with the pivot converted into an absolute origin,
+    /// and the flipping converted into a negative scale, based on the bounds of the
+    /// graphic being drawn.
+    pub(crate) fn resolve(&self, bounds: Rectangle) -> DrawParams {
+        let mut params = self.clone();
+
+        if self.pivot != Vec2::zero() {
+            params.origin += Vec2::new(
+                bounds.x + self.pivot.x * bounds.width,
+                bounds.y + self.pivot.y * bounds.height,
+            );
+
+            params.pivot = Vec2::zero();
+        }
+
+        // Mirroring around the center of the bounds and then applying the origin is
+        // equivalent to applying a negative scale around the origin's mirror image.
+        if self.flip_x {
+            params.origin.x = 2.0 * bounds.x + bounds.width - params.origin.x;
+            params.scale.x = -params.scale.x;
+            params.flip_x = false;
+        }
+
+        if self.flip_y {
+            params.origin.y = 2.0 * bounds.y + bounds.height - params.origin.y;
+            params.scale.y = -params.scale.y;
+            params.flip_y = false;
+        }
+
+        params
+    }
+
+    /// Returns the scale, with any flipping around the origin applied.
+    pub(crate) fn flipped_scale(&self) -> Vec2<f32> {
+        Vec2::new(
+            if self.flip_x {
+                -self.scale.x
+            } else {
+                self.scale.x
+            },
+            if self.flip_y {
+                -self.scale.y
+            } else {
+                self.scale.y
+            },
+        )
+    }
+
+    /// Returns whether the transformation mirrors the graphic, which reverses the winding
+    /// order of its vertices.
+    pub(crate) fn is_mirrored(&self) -> bool {
+        let scale = self.flipped_scale();
+        let skew = 1.0 - self.skew.x.tan() * self.skew.y.tan();
+
+        scale.x * scale.y * skew < 0.0
+    }
 }
 
 impl Default for DrawParams {
@@ -84,7 +227,11 @@ impl Default for DrawParams {
             position: Vec2::new(0.0, 0.0),
             scale: Vec2::new(1.0, 1.0),
             origin: Vec2::new(0.0, 0.0),
+            pivot: Vec2::new(0.0, 0.0),
             rotation: 0.0,
+            skew: Vec2::new(0.0, 0.0),
+            flip_x: false,
+            flip_y: false,
             color: Color::WHITE,
         }
     }
@@ -104,3 +251,110 @@ impl From<DrawParams> for Mat4<f32> {
         params.to_matrix()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transform(matrix: Mat4<f32>, point: Vec2<f32>) -> Vec2<f32> {
+        let result = matrix * point.with_z(0.0).with_w(1.0);
+        Vec2::new(result.x, result.y)
+    }
+
+    #[test]
+    fn flip_in_place() {
+        let bounds = Rectangle::new(0.0, 0.0, 16.0, 8.0);
+        let params = DrawParams::new()
+            .position(Vec2::new(100.0, 100.0))
+            .flip_x(true);
+
+        let matrix = params.to_matrix_with_bounds(bounds);
+
+        // The graphic still covers the same area, but the contents are mirrored.
+        assert_eq!(
+            transform(matrix, Vec2::new(0.0, 0.0)),
+            Vec2::new(116.0, 100.0)
+        );
+        assert_eq!(
+            transform(matrix, Vec2::new(16.0, 8.0)),
+            Vec2::new(100.0, 108.0)
+        );
+
+        // Without bounds, the graphic is flipped around the origin.
+        assert_eq!(
+            params.to_matrix(),
+            params.flip_x(false).scale(Vec2::new(-1.0, 1.0)).to_matrix()
+        );
+    }
+
+    #[test]
+    fn flip_preserves_origin() {
+        let bounds = Rectangle::new(0.0, 0.0, 16.0, 8.0);
+        let params = DrawParams::new()
+            .position(Vec2::new(100.0, 100.0))
+            .origin(Vec2::new(4.0, 2.0))
+            .flip_y(true);
+
+        let matrix = params.to_matrix_with_bounds(bounds);
+
+        // The top left of the flipped graphic is in the same place as it would be unflipped.
+        assert_eq!(
+            transform(matrix, Vec2::new(0.0, 8.0)),
+            Vec2::new(96.0, 98.0)
+        );
+    }
+
+    #[test]
+    fn skew_shears_around_origin() {
+        let matrix = DrawParams::new()
+            .skew(Vec2::new(std::f32::consts::FRAC_PI_4, 0.0))
+            .to_matrix();
+
+        let point = transform(matrix, Vec2::new(0.0, 10.0));
+
+        assert!((point.x - 10.0).abs() < 0.0001);
+        assert!((point.y - 10.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn mirroring_matches_matrix() {
+        let cases = [
+            (DrawParams::new(), false),
+            (DrawParams::new().flip_x(true), true),
+            (DrawParams::new().flip_x(true).flip_y(true), false),
+            (DrawParams::new().scale(Vec2::new(1.0, -2.0)), true),
+            (DrawParams::new().skew(Vec2::new(0.5, 0.5)), false),
+            (DrawParams::new().skew(Vec2::new(1.0, 1.0)), true),
+            (
+                DrawParams::new().skew(Vec2::new(1.0, 1.0)).flip_y(true),
+                false,
+            ),
+        ];
+
+        for (params, mirrored) in cases {
+            let m = params.clone().rotation(1.0).to_matrix();
+            let determinant = m.cols.x.x * m.cols.y.y - m.cols.y.x * m.cols.x.y;
+
+            assert_eq!(params.is_mirrored(), mirrored);
+            assert_eq!(determinant < 0.0, mirrored);
+        }
+    }
+
+    #[test]
+    fn pivot_is_relative_to_bounds() {
+        let bounds = Rectangle::new(0.0, 0.0, 16.0, 32.0);
+        let params = DrawParams::new()
+            .origin(Vec2::new(1.0, 0.0))
+            .pivot(Vec2::new(0.5, 1.0));
+
+        let resolved = params.resolve(bounds);
+
+        assert_eq!(resolved.origin, Vec2::new(9.0, 32.0));
+        assert_eq!(resolved.pivot, Vec2::zero());
+
+        assert_eq!(
+            transform(params.to_matrix_with_bounds(bounds), Vec2::new(9.0, 32.0)),
+            Vec2::zero()
+        );
+    }
+}
@@ -1,10 +1,14 @@
+use std::borrow::Cow;
+use std::io::Cursor;
 use std::path::Path;
 
 use half::f16;
+use image::error::{ImageFormatHint, UnsupportedError};
+use image::{ColorType, ImageError, ImageOutputFormat};
 
 use crate::error::{Result, TetraError};
 use crate::fs;
-use crate::graphics::{Color, Rectangle, Texture, TextureFormat};
+use crate::graphics::{Color, FilterMode, Rectangle, Texture, TextureFormat};
 use crate::math::Vec2;
 use crate::Context;
 
@@ -31,6 +35,9 @@ use crate::Context;
 /// | ICO | `texture_ico` | No |
 /// | PNM | `texture_pnm` | No |
 /// | DDS/DXT | `texture_dds` | No |
+///
+/// Images can also be encoded to PNG, JPEG, BMP or TGA via the [`save`](ImageData::save)
+/// and [`encode`](ImageData::encode) methods, using the same feature flags.
 #[derive(Debug, Clone)]
 pub struct ImageData {
     data: Vec<u8>,
@@ -292,6 +299,408 @@ impl ImageData {
         }
     }
 
+    /// Draws another image on top of this one, with its top-left corner at the
+    /// specified position.
+    ///
+    /// The source image will be alpha blended onto this image. Any parts of the source
+    /// image that fall outside of this image's bounds will be ignored.
+    ///
+    /// The images do not need to have the same [`TextureFormat`] - the source data will be
+    /// converted as needed, in the same way as [`set_pixel_color`](Self::set_pixel_color).
+    pub fn blit(&mut self, source: &ImageData, position: Vec2<i32>) {
+        let x_start = position.x.max(0);
+        let y_start = position.y.max(0);
+        let x_end = (position.x + source.width as i32).min(self.width as i32);
+        let y_end = (position.y + source.height as i32).min(self.height as i32);
+
+        let dst_stride = self.format.stride();
+        let src_stride = source.format.stride();
+
+        for y in y_start..y_end {
+            for x in x_start..x_end {
+                let src_idx = ((x - position.x) as usize
+                    + (y - position.y) as usize * source.width)
+                    * src_stride;
+
+                let dst_idx = (x as usize + y as usize * self.width) * dst_stride;
+
+                let src = read_color(source.format, &source.data[src_idx..src_idx + src_stride]);
+                let target = &mut self.data[dst_idx..dst_idx + dst_stride];
+
+                if src.a <= 0.0 {
+                    continue;
+                }
+
+                if src.a >= 1.0 {
+                    write_color(self.format, src, target);
+                    continue;
+                }
+
+                let dst = read_color(self.format, target);
+                let dst_a = dst.a * (1.0 - src.a);
+                let out_a = src.a + dst_a;
+
+                let blended = Color::rgba(
+                    (src.r * src.a + dst.r * dst_a) / out_a,
+                    (src.g * src.a + dst.g * dst_a) / out_a,
+                    (src.b * src.a + dst.b * dst_a) / out_a,
+                    out_a,
+                );
+
+                write_color(self.format, blended, target);
+            }
+        }
+    }
+
+    /// Sets every pixel in the specified region to a color.
+    ///
+    /// Unlike [`blit`](Self::blit), this does not perform any blending - the existing
+    /// pixels will be overwritten. Any parts of the region that fall outside of the
+    /// image's bounds will be ignored.
+    pub fn fill_rect(&mut self, region: Rectangle<i32>, color: Color) {
+        let x_start = region.x.clamp(0, self.width as i32) as usize;
+        let y_start = region.y.clamp(0, self.height as i32) as usize;
+        let x_end = (region.x + region.width).clamp(0, self.width as i32) as usize;
+        let y_end = (region.y + region.height).clamp(0, self.height as i32) as usize;
+
+        let stride = self.format.stride();
+
+        let mut pixel = vec![0; stride];
+        write_color(self.format, color, &mut pixel);
+
+        for y in y_start..y_end {
+            let row_start = (x_start + y * self.width) * stride;
+            let row_end = (x_end + y * self.width) * stride;
+
+            for target in self.data[row_start..row_end].chunks_exact_mut(stride) {
+                target.copy_from_slice(&pixel);
+            }
+        }
+    }
+
+    /// Flips the image horizontally.
+    pub fn flip_x(&mut self) {
+        let width = self.width;
+        *self = self.remap(self.width, self.height, |x, y| (width - x - 1, y));
+    }
+
+    /// Flips the image vertically.
+    pub fn flip_y(&mut self) {
+        let height = self.height;
+        *self = self.remap(self.width, self.height, |x, y| (x, height - y - 1));
+    }
+
+    /// Rotates the image by 90 degrees clockwise.
+    ///
+    /// This will swap the width and height of the image.
+    pub fn rotate_90(&mut self) {
+        let height = self.height;
+        *self = self.remap(self.height, self.width, |x, y| (y, height - x - 1));
+    }
+
+    /// Rotates the image by 180 degrees.
+    pub fn rotate_180(&mut self) {
+        let (width, height) = (self.width, self.height);
+        *self = self.remap(width, height, |x, y| (width - x - 1, height - y - 1));
+    }
+
+    /// Rotates the image by 270 degrees clockwise (or 90 degrees counter-clockwise).
+    ///
+    /// This will swap the width and height of the image.
+    pub fn rotate_270(&mut self) {
+        let width = self.width;
+        *self = self.remap(self.height, self.width, |x, y| (width - y - 1, x));
+    }
+
+    /// Creates a new `ImageData` by resizing this image.
+    ///
+    /// The filter mode determines how the pixels will be sampled:
+    ///
+    /// * [`FilterMode::Nearest`] will preserve hard edges, which is usually what you
+    /// want for pixel art.
+    /// * [`FilterMode::Linear`] will use bilinear interpolation. Colors are blended with
+    /// their alpha taken into account, so transparent pixels will not cause dark fringes
+    /// around the edges of the image.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either dimension is negative.
+    pub fn resize(&self, width: i32, height: i32, filter: FilterMode) -> ImageData {
+        assert!(width >= 0 && height >= 0, "size must not be negative");
+
+        let width = width as usize;
+        let height = height as usize;
+
+        if self.width == 0 || self.height == 0 {
+            return ImageData {
+                data: vec![0; width * height * self.format.stride()],
+                width,
+                height,
+                format: self.format,
+            };
+        }
+
+        let scale_x = self.width as f32 / width as f32;
+        let scale_y = self.height as f32 / height as f32;
+
+        match filter {
+            FilterMode::Nearest => {
+                let (src_width, src_height) = (self.width, self.height);
+
+                self.remap(width, height, |x, y| {
+                    (
+                        ((x as f32 + 0.5) * scale_x).min(src_width as f32 - 1.0) as usize,
+                        ((y as f32 + 0.5) * scale_y).min(src_height as f32 - 1.0) as usize,
+                    )
+                })
+            }
+
+            FilterMode::Linear => {
+                let stride = self.format.stride();
+                let mut data = vec![0; width * height * stride];
+
+                let max_x = self.width as f32 - 1.0;
+                let max_y = self.height as f32 - 1.0;
+
+                for (i, target) in data.chunks_exact_mut(stride).enumerate() {
+                    let src_x = ((i % width) as f32 + 0.5) * scale_x - 0.5;
+                    let src_y = ((i / width) as f32 + 0.5) * scale_y - 0.5;
+
+                    let x0 = src_x.floor().clamp(0.0, max_x);
+                    let y0 = src_y.floor().clamp(0.0, max_y);
+                    let x1 = (x0 + 1.0).min(max_x);
+                    let y1 = (y0 + 1.0).min(max_y);
+
+                    let tx = (src_x - x0).clamp(0.0, 1.0);
+                    let ty = (src_y - y0).clamp(0.0, 1.0);
+
+                    let samples = [
+                        (x0, y0, (1.0 - tx) * (1.0 - ty)),
+                        (x1, y0, tx * (1.0 - ty)),
+                        (x0, y1, (1.0 - tx) * ty),
+                        (x1, y1, tx * ty),
+                    ];
+
+                    let mut sum = [0.0; 4];
+
+                    for (x, y, weight) in samples {
+                        let idx = (x as usize + y as usize * self.width) * stride;
+                        let color = read_color(self.format, &self.data[idx..idx + stride]);
+                        let alpha = color.a * weight;
+
+                        sum[0] += color.r * alpha;
+                        sum[1] += color.g * alpha;
+                        sum[2] += color.b * alpha;
+                        sum[3] += alpha;
+                    }
+
+                    let color = if sum[3] > 0.0 {
+                        Color::rgba(sum[0] / sum[3], sum[1] / sum[3], sum[2] / sum[3], sum[3])
+                    } else {
+                        Color::rgba(0.0, 0.0, 0.0, 0.0)
+                    };
+
+                    write_color(self.format, color, target);
+                }
+
+                ImageData {
+                    data,
+                    width,
+                    height,
+                    format: self.format,
+                }
+            }
+        }
+    }
+
+    /// Extends each edge of the image outwards by the specified number of pixels,
+    /// repeating the pixels along the edge.
+    ///
+    /// This is useful when packing sprites into an atlas, as it prevents colors from
+    /// neighbouring sprites bleeding in when drawing with linear filtering or at
+    /// non-integer positions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `amount` is negative.
+    pub fn extrude(&mut self, amount: i32) {
+        assert!(amount >= 0, "amount must not be negative");
+
+        if self.width == 0 || self.height == 0 {
+            return;
+        }
+
+        let amount = amount as usize;
+        let max_x = self.width - 1;
+        let max_y = self.height - 1;
+
+        *self = self.remap(self.width + amount * 2, self.height + amount * 2, |x, y| {
+            (
+                x.saturating_sub(amount).min(max_x),
+                y.saturating_sub(amount).min(max_y),
+            )
+        });
+    }
+
+    /// Removes any fully transparent rows and columns from the edges of the image.
+    ///
+    /// The returned vector is the position of the top-left corner of the trimmed image,
+    /// relative to the original image. This can be used to keep sprites aligned after
+    /// their transparent margins have been removed.
+    ///
+    /// If the image is fully transparent, it will be trimmed to a size of zero. Images
+    /// whose [`TextureFormat`] does not have an alpha channel will not be changed.
+    pub fn trim(&mut self) -> Vec2<i32> {
+        let stride = self.format.stride();
+
+        let mut min_x = self.width;
+        let mut min_y = self.height;
+        let mut max_x = 0;
+        let mut max_y = 0;
+
+        for (i, pixel) in self.data.chunks_exact(stride).enumerate() {
+            if read_color(self.format, pixel).a > 0.0 {
+                let x = i % self.width;
+                let y = i / self.width;
+
+                min_x = min_x.min(x);
+                min_y = min_y.min(y);
+                max_x = max_x.max(x + 1);
+                max_y = max_y.max(y + 1);
+            }
+        }
+
+        if min_x >= max_x || min_y >= max_y {
+            self.data.clear();
+            self.width = 0;
+            self.height = 0;
+
+            return Vec2::zero();
+        }
+
+        *self = self.region(Rectangle::new(
+            min_x as i32,
+            min_y as i32,
+            (max_x - min_x) as i32,
+            (max_y - min_y) as i32,
+        ));
+
+        Vec2::new(min_x as i32, min_y as i32)
+    }
+
+    /// Creates a new image of the given size, copying each pixel from the position
+    /// in this image returned by the closure.
+    fn remap<F>(&self, width: usize, height: usize, mut func: F) -> ImageData
+    where
+        F: FnMut(usize, usize) -> (usize, usize),
+    {
+        let stride = self.format.stride();
+        let mut data = Vec::with_capacity(width * height * stride);
+
+        for y in 0..height {
+            for x in 0..width {
+                let (src_x, src_y) = func(x, y);
+                let idx = (src_x + src_y * self.width) * stride;
+
+                data.extend_from_slice(&self.data[idx..idx + stride]);
+            }
+        }
+
+        ImageData {
+            data,
+            width,
+            height,
+            format: self.format,
+        }
+    }
+
+    /// Encodes the image data into the specified file format.
+    ///
+    /// Data in formats that cannot be stored directly will be converted:
+    ///
+    /// * [`TextureFormat::R8`] data will be saved as grayscale.
+    /// * [`TextureFormat::Rg8`] data will be saved as RGB, with the blue channel set to zero.
+    /// * [`TextureFormat::Rgba16F`] data will be clamped and converted to 8 bits per channel.
+    /// * JPEG does not support transparency, so the alpha channel will be discarded.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::InvalidTexture`] will be returned if the image could not be encoded.
+    pub fn encode(&self, format: ImageFormat) -> Result<Vec<u8>> {
+        let (data, color_type) = self.to_encodable(format.supports_alpha());
+
+        let mut output = Cursor::new(Vec::new());
+
+        image::write_buffer_with_format(
+            &mut output,
+            &data,
+            self.width as u32,
+            self.height as u32,
+            color_type,
+            format.to_output_format(),
+        )
+        .map_err(TetraError::InvalidTexture)?;
+
+        Ok(output.into_inner())
+    }
+
+    /// Saves the image data to the given file.
+    ///
+    /// The format will be determined based on the file extension - see [`ImageFormat::from_path`]
+    /// for the supported extensions. If you need more control over the format, use
+    /// [`encode`](Self::encode) instead.
+    ///
+    /// This can be combined with [`Canvas::get_data`](super::Canvas::get_data) in order to
+    /// save screenshots.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::InvalidTexture`] will be returned if the file extension was not recognized,
+    /// or if the image could not be encoded.
+    /// * [`TetraError::FailedToSaveAsset`] will be returned if the file could not be written.
+    pub fn save<P>(&self, path: P) -> Result
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+
+        let format = ImageFormat::from_path(path).ok_or_else(|| {
+            TetraError::InvalidTexture(ImageError::Unsupported(UnsupportedError::from(
+                ImageFormatHint::from(path),
+            )))
+        })?;
+
+        fs::write(path, &self.encode(format)?)
+    }
+
+    fn to_encodable(&self, alpha: bool) -> (Cow<'_, [u8]>, ColorType) {
+        match (self.format, alpha) {
+            (TextureFormat::Rgba8, true) => (Cow::Borrowed(&self.data), ColorType::Rgba8),
+            (TextureFormat::R8, _) => (Cow::Borrowed(&self.data), ColorType::L8),
+            (_, true) => (
+                Cow::Owned(
+                    self.data
+                        .chunks_exact(self.format.stride())
+                        .flat_map(|pixel| <[u8; 4]>::from(read_color(self.format, pixel)))
+                        .collect(),
+                ),
+                ColorType::Rgba8,
+            ),
+            (_, false) => (
+                Cow::Owned(
+                    self.data
+                        .chunks_exact(self.format.stride())
+                        .flat_map(|pixel| {
+                            let [r, g, b, _] = <[u8; 4]>::from(read_color(self.format, pixel));
+                            [r, g, b]
+                        })
+                        .collect(),
+                ),
+                ColorType::Rgb8,
+            ),
+        }
+    }
+
     /// Multiplies the RGB components of each pixel by the alpha component.
     ///
     /// This can be useful when working with
@@ -304,6 +713,81 @@ impl ImageData {
     }
 }
 
+/// File formats that [`ImageData`] can be encoded to.
+///
+/// Each format is only available if the corresponding Cargo feature is enabled (see the
+/// [`ImageData`] docs for details).
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    /// PNG.
+    #[cfg(feature = "texture_png")]
+    Png,
+
+    /// JPEG, with the specified quality (from 1 to 100).
+    #[cfg(feature = "texture_jpeg")]
+    Jpeg {
+        /// The quality of the encoded image, from 1 to 100.
+        quality: u8,
+    },
+
+    /// BMP.
+    #[cfg(feature = "texture_bmp")]
+    Bmp,
+
+    /// TGA.
+    #[cfg(feature = "texture_tga")]
+    Tga,
+}
+
+impl ImageFormat {
+    /// Determines the format of an image based on the extension of a file path.
+    ///
+    /// The recognized extensions are `png`, `jpg`/`jpeg` (which will use a quality
+    /// of 90), `bmp` and `tga`. Returns [`None`] if the extension is not recognized,
+    /// or the corresponding feature is not enabled.
+    pub fn from_path<P>(path: P) -> Option<ImageFormat>
+    where
+        P: AsRef<Path>,
+    {
+        let extension = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+
+        match extension.as_str() {
+            #[cfg(feature = "texture_png")]
+            "png" => Some(ImageFormat::Png),
+            #[cfg(feature = "texture_jpeg")]
+            "jpg" | "jpeg" => Some(ImageFormat::Jpeg { quality: 90 }),
+            #[cfg(feature = "texture_bmp")]
+            "bmp" => Some(ImageFormat::Bmp),
+            #[cfg(feature = "texture_tga")]
+            "tga" => Some(ImageFormat::Tga),
+            _ => None,
+        }
+    }
+
+    fn supports_alpha(self) -> bool {
+        match self {
+            #[cfg(feature = "texture_jpeg")]
+            ImageFormat::Jpeg { .. } => false,
+            #[allow(unreachable_patterns)]
+            _ => true,
+        }
+    }
+
+    fn to_output_format(self) -> ImageOutputFormat {
+        match self {
+            #[cfg(feature = "texture_png")]
+            ImageFormat::Png => ImageOutputFormat::Png,
+            #[cfg(feature = "texture_jpeg")]
+            ImageFormat::Jpeg { quality } => ImageOutputFormat::Jpeg(quality),
+            #[cfg(feature = "texture_bmp")]
+            ImageFormat::Bmp => ImageOutputFormat::Bmp,
+            #[cfg(feature = "texture_tga")]
+            ImageFormat::Tga => ImageOutputFormat::Tga,
+        }
+    }
+}
+
 fn read_color(format: TextureFormat, data: &[u8]) -> Color {
     match format {
         TextureFormat::Rgba8 => Color::rgba8(data[0], data[1], data[2], data[3]),
@@ -848,4 +1332,131 @@ mod tests {
             bytemuck::cast_slice(&output),
         );
     }
+
+    #[test]
+    fn blit_blends_and_clips() {
+        let mut target = ImageData::from_data(2, 2, TextureFormat::Rgba8, vec![255; 16]).unwrap();
+
+        let source = ImageData::from_data(
+            2,
+            1,
+            TextureFormat::Rgba8,
+            vec![
+                0, 0, 0, 0, // Pixel 1
+                255, 0, 0, 255, // Pixel 2
+            ],
+        )
+        .unwrap();
+
+        target.blit(&source, Vec2::new(0, 1));
+        target.blit(&source, Vec2::new(-1, 0));
+
+        assert_eq!(
+            target.as_bytes(),
+            &[
+                255, 0, 0, 255, // Pixel 1
+                255, 255, 255, 255, // Pixel 2
+                255, 255, 255, 255, // Pixel 3
+                255, 0, 0, 255, // Pixel 4
+            ]
+        );
+    }
+
+    #[test]
+    fn fill_rect_clips() {
+        let mut image = ImageData::from_data(3, 2, TextureFormat::R8, vec![0; 6]).unwrap();
+
+        image.fill_rect(Rectangle::new(1, -1, 5, 2), Color::WHITE);
+
+        assert_eq!(image.as_bytes(), &[0, 255, 255, 0, 0, 0]);
+    }
+
+    #[test]
+    fn flip_and_rotate() {
+        // 1 2 3
+        // 4 5 6
+        let input = ImageData::from_data(3, 2, TextureFormat::R8, vec![1, 2, 3, 4, 5, 6]).unwrap();
+
+        let mut image = input.clone();
+        image.flip_x();
+        assert_eq!(image.as_bytes(), &[3, 2, 1, 6, 5, 4]);
+
+        let mut image = input.clone();
+        image.flip_y();
+        assert_eq!(image.as_bytes(), &[4, 5, 6, 1, 2, 3]);
+
+        let mut image = input.clone();
+        image.rotate_90();
+        assert_eq!(image.size(), (2, 3));
+        assert_eq!(image.as_bytes(), &[4, 1, 5, 2, 6, 3]);
+
+        let mut image = input.clone();
+        image.rotate_180();
+        assert_eq!(image.as_bytes(), &[6, 5, 4, 3, 2, 1]);
+
+        let mut image = input;
+        image.rotate_270();
+        assert_eq!(image.size(), (2, 3));
+        assert_eq!(image.as_bytes(), &[3, 6, 2, 5, 1, 4]);
+    }
+
+    #[test]
+    fn resize_nearest_and_linear() {
+        let image = ImageData::from_data(2, 1, TextureFormat::R8, vec![0, 255]).unwrap();
+
+        let nearest = image.resize(4, 2, FilterMode::Nearest);
+        assert_eq!(nearest.as_bytes(), &[0, 0, 255, 255, 0, 0, 255, 255]);
+
+        let linear = image.resize(3, 1, FilterMode::Linear);
+        assert_eq!(linear.as_bytes()[0], 0);
+        assert!((126..=128).contains(&linear.as_bytes()[1]));
+        assert_eq!(linear.as_bytes()[2], 255);
+    }
+
+    #[test]
+    fn extrude_repeats_edges() {
+        let mut image = ImageData::from_data(2, 1, TextureFormat::R8, vec![1, 2]).unwrap();
+
+        image.extrude(1);
+
+        assert_eq!(image.size(), (4, 3));
+        assert_eq!(image.as_bytes(), &[1, 1, 2, 2, 1, 1, 2, 2, 1, 1, 2, 2]);
+    }
+
+    #[test]
+    fn trim_returns_offset() {
+        let mut data = vec![0; 4 * 4 * 4];
+        data[(1 + 2 * 4) * 4 + 3] = 255;
+        data[(2 + 2 * 4) * 4 + 3] = 255;
+
+        let mut image = ImageData::from_data(4, 4, TextureFormat::Rgba8, data).unwrap();
+
+        assert_eq!(image.trim(), Vec2::new(1, 2));
+        assert_eq!(image.size(), (2, 1));
+
+        let mut empty = ImageData::from_data(2, 2, TextureFormat::Rgba8, vec![0; 16]).unwrap();
+
+        assert_eq!(empty.trim(), Vec2::zero());
+        assert_eq!(empty.size(), (0, 0));
+    }
+
+    #[test]
+    #[cfg(feature = "texture_png")]
+    fn encode_png_roundtrip() {
+        let image = ImageData::from_data(2, 1, TextureFormat::Rg8, vec![255, 0, 0, 128]).unwrap();
+
+        let encoded = image.encode(ImageFormat::Png).unwrap();
+        let decoded = ImageData::from_encoded(&encoded).unwrap();
+
+        assert_eq!(decoded.format(), TextureFormat::Rgba8);
+        assert_eq!(decoded.as_bytes(), &[255, 0, 0, 255, 0, 128, 0, 255]);
+    }
+
+    #[test]
+    #[cfg(feature = "texture_png")]
+    fn format_from_path() {
+        assert_eq!(ImageFormat::from_path("test.PNG"), Some(ImageFormat::Png));
+        assert_eq!(ImageFormat::from_path("test.txt"), None);
+        assert_eq!(ImageFormat::from_path("test"), None);
+    }
 }
@@ -0,0 +1,627 @@
+//! Functions and types relating to 2D lighting and shadows.
+//!
+//! Lighting works by rendering all of the lights in a scene into a [`LightMap`], which
+//! is then multiplied over the top of the rendered scene. Areas that no light reaches
+//! are left at the light map's ambient color.
+
+use std::f32::consts::TAU;
+
+use crate::error::Result;
+use crate::graphics::mesh::Vertex;
+use crate::graphics::{
+    self, BlendState, Canvas, Color, DrawParams, Rectangle, StencilAction, StencilState,
+    StencilTest,
+};
+use crate::math::Vec2;
+use crate::Context;
+
+use super::shapes::push_triangles;
+
+/// The number of rings used to approximate a light's falloff curve.
+const LIGHT_RINGS: usize = 8;
+
+/// The number of segments used for a full circle of light.
+const LIGHT_SEGMENTS: usize = 48;
+
+/// The shape of the area lit by a [`Light`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LightKind {
+    /// The light shines equally in all directions.
+    Point,
+
+    /// The light shines in a cone.
+    Spot {
+        /// The direction that the cone points in, in radians.
+        direction: f32,
+
+        /// The angle between the center of the cone and its edge, in radians.
+        angle: f32,
+
+        /// The angle over which the light fades out at the edges of the cone, in radians.
+        ///
+        /// If this is zero, the cone will have a hard edge.
+        softness: f32,
+    },
+}
+
+/// A light source.
+///
+/// Lights are positioned in the same co-ordinate space as the rest of your scene, so
+/// they will be affected by the transform matrix that is active when the
+/// [`LightMap`] is rendered.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Light {
+    /// The position of the light.
+    pub position: Vec2<f32>,
+
+    /// The color of the light.
+    pub color: Color,
+
+    /// The distance at which the light fades out completely.
+    pub radius: f32,
+
+    /// The brightness of the light at its center.
+    pub intensity: f32,
+
+    /// The exponent of the light's falloff curve.
+    ///
+    /// A value of `1.0` gives a linear falloff, higher values concentrate the light
+    /// around its center, and lower values spread it out towards the edge.
+    pub falloff: f32,
+
+    /// The shape of the lit area.
+    pub kind: LightKind,
+
+    /// Whether the light is blocked by [`Occluder`]s.
+    pub casts_shadows: bool,
+}
+
+impl Light {
+    /// Creates a new white point light.
+    pub fn point(position: Vec2<f32>, radius: f32) -> Light {
+        Light {
+            position,
+            color: Color::WHITE,
+            radius,
+            intensity: 1.0,
+            falloff: 1.0,
+            kind: LightKind::Point,
+            casts_shadows: true,
+        }
+    }
+
+    /// Creates a new white spot light, with a soft edge.
+    ///
+    /// The direction and the angle (between the center of the cone and its edge)
+    /// are specified in radians.
+    pub fn spot(position: Vec2<f32>, radius: f32, direction: f32, angle: f32) -> Light {
+        Light {
+            kind: LightKind::Spot {
+                direction,
+                angle,
+                softness: angle * 0.25,
+            },
+            ..Light::point(position, radius)
+        }
+    }
+
+    /// Sets the position of the light.
+    pub fn position(mut self, position: Vec2<f32>) -> Light {
+        self.position = position;
+        self
+    }
+
+    /// Sets the color of the light.
+    pub fn color(mut self, color: Color) -> Light {
+        self.color = color;
+        self
+    }
+
+    /// Sets the distance at which the light fades out completely.
+    pub fn radius(mut self, radius: f32) -> Light {
+        self.radius = radius;
+        self
+    }
+
+    /// Sets the brightness of the light at its center.
+    pub fn intensity(mut self, intensity: f32) -> Light {
+        self.intensity = intensity;
+        self
+    }
+
+    /// Sets the exponent of the light's falloff curve.
+    pub fn falloff(mut self, falloff: f32) -> Light {
+        self.falloff = falloff;
+        self
+    }
+
+    /// Sets the shape of the lit area.
+    pub fn kind(mut self, kind: LightKind) -> Light {
+        self.kind = kind;
+        self
+    }
+
+    /// Sets whether the light is blocked by [`Occluder`]s.
+    pub fn casts_shadows(mut self, casts_shadows: bool) -> Light {
+        self.casts_shadows = casts_shadows;
+        self
+    }
+
+    /// Returns how much of the light reaches a point at the given offset from its
+    /// position, between `0.0` and `1.0`.
+    fn attenuation(&self, offset: Vec2<f32>) -> f32 {
+        if self.radius <= 0.0 {
+            return 0.0;
+        }
+
+        let distance = offset.magnitude() / self.radius;
+        let radial = (1.0 - distance).max(0.0).powf(self.falloff.max(0.0));
+
+        match self.kind {
+            LightKind::Point => radial,
+            LightKind::Spot {
+                direction,
+                angle,
+                softness,
+            } => {
+                if offset == Vec2::zero() {
+                    return radial;
+                }
+
+                let delta = angle_between(offset.y.atan2(offset.x), direction).abs();
+
+                let angular = if softness > 0.0 {
+                    ((angle - delta) / softness).clamp(0.0, 1.0)
+                } else if delta <= angle {
+                    1.0
+                } else {
+                    0.0
+                };
+
+                radial * angular
+            }
+        }
+    }
+}
+
+/// A shape that blocks light.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Occluder {
+    points: Vec<Vec2<f32>>,
+}
+
+impl Occluder {
+    /// Creates an occluder from the outline of a polygon.
+    ///
+    /// The points can be wound in either direction, and the polygon does not have to be convex.
+    pub fn polygon(points: &[Vec2<f32>]) -> Occluder {
+        Occluder {
+            points: points.to_vec(),
+        }
+    }
+
+    /// Creates an occluder from a rectangle.
+    pub fn rectangle(rectangle: Rectangle) -> Occluder {
+        Occluder {
+            points: vec![
+                rectangle.top_left(),
+                rectangle.top_right(),
+                rectangle.bottom_right(),
+                rectangle.bottom_left(),
+            ],
+        }
+    }
+
+    /// Returns the points that make up the outline of the occluder.
+    pub fn points(&self) -> &[Vec2<f32>] {
+        &self.points
+    }
+}
+
+impl From<Rectangle> for Occluder {
+    fn from(rectangle: Rectangle) -> Self {
+        Occluder::rectangle(rectangle)
+    }
+}
+
+/// How shadows should be rendered.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShadowMode {
+    /// Shadows will have sharp edges.
+    Hard,
+
+    /// Shadows will have soft edges, simulating a light source with a physical size.
+    ///
+    /// This is achieved by rendering each light multiple times from slightly different
+    /// positions, so the cost of rendering scales with the number of samples.
+    Soft {
+        /// The radius of the simulated light source.
+        radius: f32,
+
+        /// The number of times each light will be rendered.
+        samples: u8,
+    },
+}
+
+/// A render target that lights are drawn into.
+///
+/// Each frame, call [`render`](Self::render) to draw the lights and shadows for the
+/// scene, and then call [`draw`](Self::draw) after drawing the scene itself to
+/// apply the lighting. The light map is multiplied with the contents of the
+/// screen, so it is usually the same size as the screen (or the canvas you are
+/// rendering to).
+///
+/// # Performance
+///
+/// Creating a `LightMap` creates a [`Canvas`] with a stencil buffer, so you
+/// should try to reuse light maps rather than creating them every frame.
+/// The geometry for lights and shadows is generated on the CPU and drawn
+/// via the sprite batch.
+#[derive(Debug, Clone)]
+pub struct LightMap {
+    canvas: Canvas,
+    ambient: Color,
+    shadow_mode: ShadowMode,
+
+    light_vertices: Vec<Vertex>,
+    light_indices: Vec<u32>,
+    shadow_vertices: Vec<Vertex>,
+    shadow_indices: Vec<u32>,
+}
+
+impl LightMap {
+    /// Creates a new light map.
+    ///
+    /// The ambient light will be black by default, so any areas that are not lit
+    /// will be completely dark.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if
+    /// the underlying graphics API encounters an error.
+    pub fn new(ctx: &mut Context, width: i32, height: i32) -> Result<LightMap> {
+        let canvas = Canvas::builder(width, height)
+            .stencil_buffer(true)
+            .build(ctx)?;
+
+        Ok(LightMap {
+            canvas,
+            ambient: Color::BLACK,
+            shadow_mode: ShadowMode::Hard,
+
+            light_vertices: Vec::new(),
+            light_indices: Vec::new(),
+            shadow_vertices: Vec::new(),
+            shadow_indices: Vec::new(),
+        })
+    }
+
+    /// Returns the color of the light that reaches every part of the scene.
+    pub fn ambient(&self) -> Color {
+        self.ambient
+    }
+
+    /// Sets the color of the light that reaches every part of the scene.
+    pub fn set_ambient(&mut self, ambient: Color) {
+        self.ambient = ambient;
+    }
+
+    /// Returns how shadows are rendered.
+    pub fn shadow_mode(&self) -> ShadowMode {
+        self.shadow_mode
+    }
+
+    /// Sets how shadows are rendered.
+    pub fn set_shadow_mode(&mut self, shadow_mode: ShadowMode) {
+        self.shadow_mode = shadow_mode;
+    }
+
+    /// Returns a reference to the canvas that the lights are rendered to.
+    pub fn canvas(&self) -> &Canvas {
+        &self.canvas
+    }
+
+    /// Renders the lights into the light map, replacing its previous contents.
+    ///
+    /// The current transform matrix will be applied to the lights and occluders,
+    /// so a [`Camera`](super::Camera) can be used in the same way as when
+    /// drawing the rest of the scene.
+    ///
+    /// The active canvas and blend state will be restored afterwards, but the
+    /// stencil state will be reset to [`StencilState::disabled`].
+    pub fn render(&mut self, ctx: &mut Context, lights: &[Light], occluders: &[Occluder]) {
+        let previous_canvas = ctx.graphics.canvas.clone();
+        let previous_blend_state = ctx.graphics.blend_state;
+
+        graphics::set_canvas(ctx, &self.canvas);
+        graphics::clear(ctx, self.ambient);
+        graphics::set_blend_state(ctx, BlendState::add(false));
+
+        let mut used_stencil = false;
+
+        for light in lights {
+            let shadowed = light.casts_shadows && !occluders.is_empty();
+
+            let (samples, source_radius) = match self.shadow_mode {
+                ShadowMode::Soft { radius, samples } if shadowed => (samples.max(1), radius),
+                _ => (1, 0.0),
+            };
+
+            build_light(
+                light,
+                1.0 / samples as f32,
+                &mut self.light_vertices,
+                &mut self.light_indices,
+            );
+
+            for sample in 0..samples {
+                if shadowed {
+                    let origin = light.position + sample_offset(sample, samples, source_radius);
+
+                    build_shadows(
+                        origin,
+                        light.radius + source_radius,
+                        occluders,
+                        &mut self.shadow_vertices,
+                        &mut self.shadow_indices,
+                    );
+
+                    graphics::clear_stencil(ctx, 0);
+                    graphics::set_stencil_state(
+                        ctx,
+                        StencilState::write(StencilAction::Replace, 1),
+                    );
+                    graphics::set_color_mask(ctx, false, false, false, false);
+
+                    push_triangles(ctx, &self.shadow_vertices, &self.shadow_indices);
+
+                    graphics::set_stencil_state(ctx, StencilState::read(StencilTest::EqualTo, 0));
+                    graphics::set_color_mask(ctx, true, true, true, true);
+
+                    used_stencil = true;
+                } else if used_stencil {
+                    graphics::set_stencil_state(ctx, StencilState::disabled());
+                    used_stencil = false;
+                }
+
+                push_triangles(ctx, &self.light_vertices, &self.light_indices);
+            }
+        }
+
+        if used_stencil {
+            graphics::set_stencil_state(ctx, StencilState::disabled());
+        }
+
+        graphics::set_blend_state(ctx, previous_blend_state);
+        graphics::set_canvas_ex(ctx, previous_canvas.as_ref());
+    }
+
+    /// Applies the light map to the current render target, by multiplying it with the
+    /// colors that have already been drawn.
+    ///
+    /// The active blend state will be restored afterwards.
+    pub fn draw<P>(&self, ctx: &mut Context, params: P)
+    where
+        P: Into<DrawParams>,
+    {
+        let previous_blend_state = ctx.graphics.blend_state;
+
+        graphics::set_blend_state(ctx, BlendState::multiply());
+        self.canvas.draw(ctx, params);
+        graphics::set_blend_state(ctx, previous_blend_state);
+    }
+}
+
+/// Generates the geometry for a light, as a fan of rings with the falloff curve baked
+/// into the vertex colors.
+fn build_light(light: &Light, weight: f32, vertices: &mut Vec<Vertex>, indices: &mut Vec<u32>) {
+    vertices.clear();
+    indices.clear();
+
+    let (start, sweep, closed) = match light.kind {
+        LightKind::Point => (0.0, TAU, true),
+        LightKind::Spot {
+            direction, angle, ..
+        } => {
+            let angle = angle.clamp(0.0, TAU / 2.0);
+            (direction - angle, angle * 2.0, false)
+        }
+    };
+
+    let segments = ((LIGHT_SEGMENTS as f32 * sweep / TAU).ceil() as usize).max(1);
+    let columns = if closed { segments } else { segments + 1 };
+
+    let color_at = |offset: Vec2<f32>| {
+        let amount = light.attenuation(offset) * light.intensity * weight;
+
+        Color::rgba(
+            light.color.r * amount,
+            light.color.g * amount,
+            light.color.b * amount,
+            1.0,
+        )
+    };
+
+    vertices.push(Vertex::new(
+        light.position,
+        Vec2::zero(),
+        color_at(Vec2::zero()),
+    ));
+
+    for ring in 1..=LIGHT_RINGS {
+        let distance = light.radius * ring as f32 / LIGHT_RINGS as f32;
+
+        for column in 0..columns {
+            let theta = start + sweep * column as f32 / segments as f32;
+            let offset = Vec2::new(theta.cos(), theta.sin()) * distance;
+
+            vertices.push(Vertex::new(
+                light.position + offset,
+                Vec2::zero(),
+                color_at(offset),
+            ));
+        }
+    }
+
+    let index = |ring: usize, column: usize| (1 + (ring - 1) * columns + column % columns) as u32;
+
+    for column in 0..segments {
+        indices.extend_from_slice(&[0, index(1, column), index(1, column + 1)]);
+
+        for ring in 2..=LIGHT_RINGS {
+            let inner_a = index(ring - 1, column);
+            let inner_b = index(ring - 1, column + 1);
+            let outer_a = index(ring, column);
+            let outer_b = index(ring, column + 1);
+
+            indices.extend_from_slice(&[inner_a, outer_a, outer_b, inner_a, outer_b, inner_b]);
+        }
+    }
+}
+
+/// Generates the geometry for the shadows cast by the occluders, as seen from the
+/// given origin.
+///
+/// Each edge of each occluder is extruded away from the origin, far enough that the
+/// shadow covers everything within `reach` of the origin.
+fn build_shadows(
+    origin: Vec2<f32>,
+    reach: f32,
+    occluders: &[Occluder],
+    vertices: &mut Vec<Vertex>,
+    indices: &mut Vec<u32>,
+) {
+    vertices.clear();
+    indices.clear();
+
+    // Doubling the distance guarantees that the far edge of the shadow stays
+    // outside of the lit area, even when an edge subtends a wide angle.
+    let extrude = reach.max(0.0) * 2.0;
+
+    for occluder in occluders {
+        let points = occluder.points();
+
+        if points.len() < 2 {
+            continue;
+        }
+
+        for (i, &a) in points.iter().enumerate() {
+            let b = points[(i + 1) % points.len()];
+
+            let to_a = a - origin;
+            let to_b = b - origin;
+
+            let dir_a = to_a.try_normalized();
+            let dir_b = to_b.try_normalized();
+
+            let (dir_a, dir_b) = match (dir_a, dir_b) {
+                (Some(dir_a), Some(dir_b)) => (dir_a, dir_b),
+                _ => continue,
+            };
+
+            // The shadow is split at the bisector, so that neither half spans
+            // more than 90 degrees.
+            let bisector = match (dir_a + dir_b).try_normalized() {
+                Some(bisector) => bisector,
+                None => continue,
+            };
+
+            let far_a = a + dir_a * extrude;
+            let far_b = b + dir_b * extrude;
+            let far_mid = origin + bisector * (to_a.magnitude().max(to_b.magnitude()) + extrude);
+
+            let base = vertices.len() as u32;
+
+            for position in [a, b, far_b, far_a, far_mid] {
+                vertices.push(Vertex::new(position, Vec2::zero(), Color::WHITE));
+            }
+
+            indices.extend_from_slice(&[
+                base,
+                base + 1,
+                base + 2,
+                base,
+                base + 2,
+                base + 3,
+                base + 3,
+                base + 2,
+                base + 4,
+            ]);
+        }
+    }
+}
+
+/// Returns an offset within a circle of the given radius, spreading the samples evenly
+/// using a golden angle spiral.
+fn sample_offset(sample: u8, samples: u8, radius: f32) -> Vec2<f32> {
+    if samples <= 1 || radius <= 0.0 {
+        return Vec2::zero();
+    }
+
+    const GOLDEN_ANGLE: f32 = 2.399_963;
+
+    let distance = radius * ((sample as f32 + 0.5) / samples as f32).sqrt();
+    let theta = sample as f32 * GOLDEN_ANGLE;
+
+    Vec2::new(theta.cos(), theta.sin()) * distance
+}
+
+/// Returns the signed difference between two angles, wrapped to the range -PI to PI.
+fn angle_between(a: f32, b: f32) -> f32 {
+    let difference = (a - b).rem_euclid(TAU);
+
+    if difference > TAU / 2.0 {
+        difference - TAU
+    } else {
+        difference
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spot_light_attenuation() {
+        let light = Light::spot(Vec2::zero(), 100.0, 0.0, 0.5).kind(LightKind::Spot {
+            direction: 0.0,
+            angle: 0.5,
+            softness: 0.0,
+        });
+
+        assert_eq!(light.attenuation(Vec2::new(50.0, 0.0)), 0.5);
+        assert_eq!(light.attenuation(Vec2::new(-50.0, 0.0)), 0.0);
+        assert_eq!(light.attenuation(Vec2::new(150.0, 0.0)), 0.0);
+    }
+
+    #[test]
+    fn shadows_extend_past_light_radius() {
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+
+        // A wide edge right next to the light is the worst case.
+        let occluder = Occluder::polygon(&[Vec2::new(-100.0, 1.0), Vec2::new(100.0, 1.0)]);
+
+        build_shadows(Vec2::zero(), 50.0, &[occluder], &mut vertices, &mut indices);
+
+        assert!(!indices.is_empty());
+
+        // The point straight behind the edge, at the edge of the light, should be
+        // covered by one of the shadow triangles.
+        let target = Vec2::new(0.0, 50.0);
+
+        let covered = indices.chunks_exact(3).any(|t| {
+            let a = vertices[t[0] as usize].position;
+            let b = vertices[t[1] as usize].position;
+            let c = vertices[t[2] as usize].position;
+
+            let d1 = (b - a).x * (target - a).y - (b - a).y * (target - a).x;
+            let d2 = (c - b).x * (target - b).y - (c - b).y * (target - b).x;
+            let d3 = (a - c).x * (target - c).y - (a - c).y * (target - c).x;
+
+            (d1 >= 0.0 && d2 >= 0.0 && d3 >= 0.0) || (d1 <= 0.0 && d2 <= 0.0 && d3 <= 0.0)
+        });
+
+        assert!(covered);
+    }
+}
@@ -0,0 +1,296 @@
+//! Functions and types relating to masking.
+//!
+//! Masks allow you to clip drawing to an arbitrary shape - for example, you could draw a
+//! circle into a mask, and then draw a map inside of it to create a circular minimap.
+//! Anything that can be drawn can be used as part of a mask, and the parts of it that are
+//! drawn will define the area that content is clipped to (transparent pixels are still
+//! included, so shapes and meshes usually work better than textures).
+//!
+//! Using a mask is a three step process:
+//!
+//! * Call [`begin`], and then draw the shape of the mask. This will not be visible on screen.
+//! * Call [`apply`], and then draw the content that should be clipped by the mask.
+//! * Call [`end`] to stop masking.
+//!
+//! [`with_mask`] wraps all three steps up into a single function call.
+//!
+//! Masks can be nested, in which case the inner mask will also be clipped by the outer mask.
+//!
+//! Note that [`graphics::clear`](super::clear) is not affected by masks - to fill the
+//! masked area with a color, draw a rectangle instead.
+//!
+//! # Stencil Buffers
+//!
+//! Masks are implemented using the stencil buffer, so the target that is being drawn to must
+//! have one attached. Canvases will have a stencil buffer attached automatically when a mask
+//! is begun, but the main backbuffer must be created with one via
+//! [`ContextBuilder::stencil_buffer`](crate::ContextBuilder::stencil_buffer) - otherwise,
+//! beginning a mask without a canvas active will return an error.
+//!
+//! While a mask is active, the masking API takes control of the
+//! [stencil state](super::set_stencil_state) and [color mask](super::set_color_mask), so you
+//! should avoid changing them manually. They will be restored to their previous values once
+//! the outermost mask has ended. You should also avoid switching canvases while a mask is
+//! active, as the stencil buffer is not shared between targets.
+
+use crate::error::{Result, TetraError};
+use crate::graphics::{self, DrawParams, StencilAction, StencilState, StencilTest};
+use crate::math::Mat4;
+use crate::window;
+use crate::Context;
+
+/// The stencil buffer stores 8 bits per pixel, so this is the deepest that masks can be nested.
+const MAX_DEPTH: usize = 255;
+
+/// Determines which parts of the content will be visible when a mask is applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaskMode {
+    /// Only content that is inside of the mask will be visible.
+    Inside,
+
+    /// Only content that is outside of the mask will be visible.
+    ///
+    /// If the mask is nested, content will still be clipped by the outer masks.
+    ///
+    /// This mode requires an extra draw over the whole render target when the mask is
+    /// applied, so it is slightly slower than [`Inside`](MaskMode::Inside).
+    Outside,
+}
+
+/// Begins drawing a new mask.
+///
+/// Until [`apply`] is called, anything that is drawn will be added to the shape of the mask,
+/// rather than being displayed.
+///
+/// If another mask is already active, the new mask will be nested inside of it. Only the parts
+/// of the new mask that are visible through the outer mask will be used.
+///
+/// If a canvas is active and it does not have a stencil buffer, one will be attached to it.
+///
+/// # Errors
+///
+/// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if
+/// a stencil buffer could not be attached to the active canvas, or if no canvas is active
+/// and the backbuffer was created without a stencil buffer.
+///
+/// # Panics
+///
+/// Panics if the current mask has not been [applied](apply) yet, or if more than 255 masks
+/// are nested.
+pub fn begin(ctx: &mut Context) -> Result {
+    assert!(
+        ctx.graphics.masks.iter().all(Option::is_some),
+        "the current mask must be applied before a nested mask can begin"
+    );
+
+    assert!(
+        ctx.graphics.masks.len() < MAX_DEPTH,
+        "masks cannot be nested more than {} levels deep",
+        MAX_DEPTH
+    );
+
+    match ctx.graphics.canvas.clone() {
+        Some(canvas) => canvas.ensure_stencil_buffer(ctx)?,
+        None if !ctx.graphics.backbuffer_stencil => {
+            return Err(TetraError::PlatformError(
+                "masks require a stencil buffer - enable one via ContextBuilder::stencil_buffer"
+                    .into(),
+            ));
+        }
+        None => {}
+    }
+
+    let depth = ctx.graphics.masks.len() as u8;
+
+    if depth == 0 {
+        ctx.graphics.mask_base = Some((ctx.graphics.stencil_state, ctx.graphics.color_mask));
+    }
+
+    graphics::set_stencil_state(
+        ctx,
+        StencilState {
+            test: StencilTest::EqualTo,
+            ..StencilState::write(StencilAction::Increment, depth)
+        },
+    );
+
+    if depth == 0 {
+        graphics::clear_stencil(ctx, 0);
+    }
+
+    graphics::set_color_mask(ctx, false, false, false, false);
+
+    ctx.graphics.masks.push(None);
+
+    Ok(())
+}
+
+/// Finishes drawing the current mask, and applies it to subsequent drawing.
+///
+/// # Panics
+///
+/// Panics if no mask has been [begun](begin), or if the current mask has already
+/// been applied.
+pub fn apply(ctx: &mut Context, mode: MaskMode) {
+    let current = ctx
+        .graphics
+        .masks
+        .last_mut()
+        .expect("mask::begin should be called before mask::apply");
+
+    assert!(
+        current.is_none(),
+        "the current mask has already been applied"
+    );
+
+    *current = Some(mode);
+
+    if mode == MaskMode::Outside {
+        // At this point, the visible area of the outer masks contains `depth - 1` outside
+        // of the new mask, and `depth` inside of it. Inverting the bits that differ between
+        // those two values swaps them, so that the visible area always has a value of
+        // `depth`, no matter which mode was used. This keeps nesting and ending masks simple.
+        let depth = ctx.graphics.masks.len() as u8;
+
+        graphics::set_stencil_state(
+            ctx,
+            StencilState {
+                test: StencilTest::LessThanOrEqualTo,
+                write_mask: depth ^ (depth - 1),
+                ..StencilState::write(StencilAction::Invert, depth - 1)
+            },
+        );
+
+        cover_target(ctx);
+    }
+
+    apply_current(ctx);
+}
+
+/// Ends the current mask.
+///
+/// If the mask was nested, the outer mask will be re-applied. Otherwise, the stencil state
+/// and color mask will be restored to the values they had before the mask began.
+///
+/// # Panics
+///
+/// Panics if no mask has been [begun](begin).
+pub fn end(ctx: &mut Context) {
+    ctx.graphics
+        .masks
+        .pop()
+        .expect("mask::begin should be called before mask::end");
+
+    if ctx.graphics.masks.is_empty() {
+        let (stencil_state, [red, green, blue, alpha]) = ctx
+            .graphics
+            .mask_base
+            .take()
+            .expect("mask base state should be stored");
+
+        graphics::set_stencil_state(ctx, stencil_state);
+        graphics::set_color_mask(ctx, red, green, blue, alpha);
+    } else {
+        // Rather than redrawing the mask, we can remove it from the stencil buffer
+        // by decrementing every pixel that is at the mask's depth.
+        let depth = ctx.graphics.masks.len() as u8 + 1;
+
+        graphics::set_stencil_state(
+            ctx,
+            StencilState {
+                test: StencilTest::EqualTo,
+                ..StencilState::write(StencilAction::Decrement, depth)
+            },
+        );
+
+        graphics::set_color_mask(ctx, false, false, false, false);
+
+        cover_target(ctx);
+
+        apply_current(ctx);
+    }
+}
+
+/// Draws a mask and some content that is clipped by it, ending the mask afterwards.
+///
+/// This is a shortcut for calling [`begin`], `draw_mask`, [`apply`], `draw_content`
+/// and [`end`]. If either of the closures returns an error, the mask will still be
+/// ended before the error is returned.
+///
+/// # Errors
+///
+/// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned under
+/// the same conditions as [`begin`].
+/// * Any errors returned by the closures will be passed through.
+///
+/// # Panics
+///
+/// Panics under the same conditions as [`begin`].
+pub fn with_mask<M, C>(ctx: &mut Context, mode: MaskMode, draw_mask: M, draw_content: C) -> Result
+where
+    M: FnOnce(&mut Context) -> Result,
+    C: FnOnce(&mut Context) -> Result,
+{
+    begin(ctx)?;
+
+    let result = draw_mask(ctx).and_then(|_| {
+        apply(ctx, mode);
+        draw_content(ctx)
+    });
+
+    end(ctx);
+
+    result
+}
+
+/// Returns how many masks are currently active.
+pub fn depth(ctx: &Context) -> usize {
+    ctx.graphics.masks.len()
+}
+
+/// Sets up the stencil state so that only the area visible through the current mask
+/// will be drawn.
+fn apply_current(ctx: &mut Context) {
+    let depth = ctx.graphics.masks.len() as u8;
+
+    let [red, green, blue, alpha] = ctx
+        .graphics
+        .mask_base
+        .expect("mask base state should be stored")
+        .1;
+
+    graphics::set_stencil_state(ctx, StencilState::read(StencilTest::EqualTo, depth));
+    graphics::set_color_mask(ctx, red, green, blue, alpha);
+}
+
+/// Draws a quad over the entire render target, ignoring the current transform and shader.
+fn cover_target(ctx: &mut Context) {
+    let (width, height) = match &ctx.graphics.canvas {
+        Some(canvas) => canvas.size(),
+        None => window::get_size(ctx),
+    };
+
+    let transform = ctx.graphics.transform_matrix;
+    let shader = ctx.graphics.shader.clone();
+    let texture = ctx.graphics.default_texture.clone();
+
+    graphics::set_transform_matrix(ctx, Mat4::identity());
+    graphics::set_shader_ex(ctx, None);
+    graphics::set_texture(ctx, &texture);
+
+    graphics::push_quad(
+        ctx,
+        0.0,
+        0.0,
+        width as f32,
+        height as f32,
+        0.0,
+        0.0,
+        1.0,
+        1.0,
+        &DrawParams::new(),
+    );
+
+    graphics::set_shader_ex(ctx, shader.as_ref());
+    graphics::set_transform_matrix(ctx, transform);
+}
@@ -7,7 +7,14 @@
 //! read the docs for the various buffer/mesh types to understand their performance characteristics before
 //! using them.
 
+mod paint;
+mod path;
+#[cfg(feature = "svg")]
+mod svg;
+
 pub use lyon_tessellation::path::builder::BorderRadii;
+pub use paint::*;
+pub use path::*;
 
 use std::rc::Rc;
 
@@ -15,13 +22,13 @@ use bytemuck::{Pod, Zeroable};
 use lyon_tessellation::geom::euclid::{Point2D, Size2D};
 use lyon_tessellation::math::{Angle, Point, Rect, Vector};
 use lyon_tessellation::path::builder::{Build, PathBuilder};
-use lyon_tessellation::path::{Polygon, Winding};
+use lyon_tessellation::path::{Path as LyonPath, Polygon, Winding};
 use lyon_tessellation::{
     BuffersBuilder, FillOptions, FillTessellator, FillVertex, FillVertexConstructor, StrokeOptions,
     StrokeTessellator, StrokeVertex, StrokeVertexConstructor, VertexBuffers,
 };
 
-use crate::graphics::{self, Color, DrawParams, Rectangle, Texture};
+use crate::graphics::{self, Color, DrawParams, FlushReason, Rectangle, Texture};
 use crate::math::Vec2;
 use crate::platform::{RawIndexBuffer, RawVertexBuffer};
 use crate::Context;
@@ -66,6 +73,210 @@ impl Vertex {
 unsafe impl Pod for Vertex {}
 unsafe impl Zeroable for Vertex {}
 
+crate::vertex_layout!(Vertex {
+    position => ("a_position", Vec2),
+    uv => ("a_uv", Vec2),
+    color => ("a_color", Vec4),
+});
+
+/// The format of a vertex attribute.
+///
+/// All of the formats are converted to floating point values before they are passed
+/// to the shader. The `Normalized` variants map integer values onto the range
+/// `0.0..=1.0` (for unsigned types) or `-1.0..=1.0` (for signed types), while the
+/// others are converted as-is.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum VertexAttributeFormat {
+    /// A single [`f32`] (`float` in GLSL).
+    Float,
+
+    /// Two [`f32`]s (`vec2` in GLSL).
+    Vec2,
+
+    /// Three [`f32`]s (`vec3` in GLSL).
+    Vec3,
+
+    /// Four [`f32`]s (`vec4` in GLSL).
+    Vec4,
+
+    /// Four [`u8`]s (`vec4` in GLSL).
+    UnsignedByte4,
+
+    /// Four normalized [`u8`]s (`vec4` in GLSL).
+    UnsignedByte4Normalized,
+
+    /// Four [`i8`]s (`vec4` in GLSL).
+    Byte4,
+
+    /// Four normalized [`i8`]s (`vec4` in GLSL).
+    Byte4Normalized,
+
+    /// Two [`u16`]s (`vec2` in GLSL).
+    UnsignedShort2,
+
+    /// Two normalized [`u16`]s (`vec2` in GLSL).
+    UnsignedShort2Normalized,
+
+    /// Two [`i16`]s (`vec2` in GLSL).
+    Short2,
+
+    /// Two normalized [`i16`]s (`vec2` in GLSL).
+    Short2Normalized,
+}
+
+impl VertexAttributeFormat {
+    /// Returns the size of an attribute in this format, in bytes.
+    pub const fn size(self) -> usize {
+        match self {
+            VertexAttributeFormat::Float => 4,
+            VertexAttributeFormat::Vec2 => 8,
+            VertexAttributeFormat::Vec3 => 12,
+            VertexAttributeFormat::Vec4 => 16,
+            VertexAttributeFormat::UnsignedByte4
+            | VertexAttributeFormat::UnsignedByte4Normalized
+            | VertexAttributeFormat::Byte4
+            | VertexAttributeFormat::Byte4Normalized
+            | VertexAttributeFormat::UnsignedShort2
+            | VertexAttributeFormat::UnsignedShort2Normalized
+            | VertexAttributeFormat::Short2
+            | VertexAttributeFormat::Short2Normalized => 4,
+        }
+    }
+}
+
+/// A description of an individual attribute within a vertex.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct VertexAttribute {
+    /// The name of the attribute in the shader.
+    pub name: &'static str,
+
+    /// The format of the attribute's data.
+    pub format: VertexAttributeFormat,
+
+    /// The offset of the attribute from the start of the vertex, in bytes.
+    pub offset: usize,
+}
+
+impl VertexAttribute {
+    /// Creates a new vertex attribute.
+    pub const fn new(
+        name: &'static str,
+        format: VertexAttributeFormat,
+        offset: usize,
+    ) -> VertexAttribute {
+        VertexAttribute {
+            name,
+            format,
+            offset,
+        }
+    }
+}
+
+/// A type that can be used as vertex data.
+///
+/// Implementing this trait for your own types allows them to be stored in a [`VertexBuffer`],
+/// and passed to a custom [`Shader`](crate::graphics::Shader). Each attribute will be bound
+/// to the shader input with the same name - attributes that the shader does not use
+/// will be ignored.
+///
+/// The built-in [`Vertex`] type uses the `a_position`, `a_uv` and `a_color` attributes,
+/// which are also used by the default shader. If you want your custom vertex type
+/// to be drawable with the default shader, include attributes with those names.
+///
+/// The easiest way to implement this trait is via the [`vertex_layout!`](crate::vertex_layout)
+/// macro, which calculates the offsets of the attributes for you. If you implement it
+/// manually, make sure that the offsets match the layout of your type (which should
+/// be `#[repr(C)]`).
+///
+/// # Examples
+///
+/// ```
+/// # use bytemuck::{Pod, Zeroable};
+/// # use tetra::graphics::mesh::{VertexAttribute, VertexAttributeFormat, VertexLayout};
+/// #
+/// #[repr(C)]
+/// #[derive(Debug, Copy, Clone)]
+/// struct SwayVertex {
+///     position: [f32; 2],
+///     sway: f32,
+///     palette_index: [u8; 4],
+/// }
+///
+/// unsafe impl Pod for SwayVertex {}
+/// unsafe impl Zeroable for SwayVertex {}
+///
+/// impl VertexLayout for SwayVertex {
+///     const ATTRIBUTES: &'static [VertexAttribute] = &[
+///         VertexAttribute::new("a_position", VertexAttributeFormat::Vec2, 0),
+///         VertexAttribute::new("a_sway", VertexAttributeFormat::Float, 8),
+///         VertexAttribute::new("a_palette_index", VertexAttributeFormat::UnsignedByte4, 12),
+///     ];
+/// }
+/// ```
+pub trait VertexLayout: Pod {
+    /// The attributes that make up the vertex.
+    ///
+    /// Each attribute must fit within the size of the type, and they should not overlap.
+    const ATTRIBUTES: &'static [VertexAttribute];
+}
+
+/// Implements [`VertexLayout`](crate::graphics::mesh::VertexLayout) for a struct.
+///
+/// Each field that should be passed to the shader is mapped to the name of an attribute and
+/// a [`VertexAttributeFormat`](crate::graphics::mesh::VertexAttributeFormat). The offsets
+/// of the attributes are calculated from the fields via
+/// [`offset_of!`](https://doc.rust-lang.org/std/mem/macro.offset_of.html), so they will stay
+/// correct if the struct is rearranged. Fields that are not listed will not be bound.
+///
+/// The struct should be `#[repr(C)]`, and must implement [`Pod`](bytemuck::Pod).
+///
+/// # Examples
+///
+/// ```
+/// # use bytemuck::{Pod, Zeroable};
+/// # use tetra::graphics::mesh::{VertexAttributeFormat, VertexLayout};
+/// #
+/// #[repr(C)]
+/// #[derive(Debug, Copy, Clone)]
+/// struct SwayVertex {
+///     position: [f32; 2],
+///     sway: f32,
+///     palette_index: [u8; 4],
+/// }
+///
+/// unsafe impl Pod for SwayVertex {}
+/// unsafe impl Zeroable for SwayVertex {}
+///
+/// tetra::vertex_layout!(SwayVertex {
+///     position => ("a_position", Vec2),
+///     sway => ("a_sway", Float),
+///     palette_index => ("a_palette_index", UnsignedByte4),
+/// });
+///
+/// let attributes = SwayVertex::ATTRIBUTES;
+///
+/// assert_eq!(attributes[1].name, "a_sway");
+/// assert_eq!(attributes[1].format, VertexAttributeFormat::Float);
+/// assert_eq!(attributes[1].offset, 8);
+/// assert_eq!(attributes[2].offset, 12);
+/// ```
+#[macro_export]
+macro_rules! vertex_layout {
+    ($t:ty { $($field:ident => ($name:expr, $format:ident)),* $(,)? }) => {
+        impl $crate::graphics::mesh::VertexLayout for $t {
+            const ATTRIBUTES: &'static [$crate::graphics::mesh::VertexAttribute] = &[
+                $(
+                    $crate::graphics::mesh::VertexAttribute::new(
+                        $name,
+                        $crate::graphics::mesh::VertexAttributeFormat::$format,
+                        ::core::mem::offset_of!($t, $field),
+                    ),
+                )*
+            ];
+        }
+    };
+}
+
 /// The expected usage of a GPU buffer.
 ///
 /// The GPU may optionally use this to optimize data storage and access.
@@ -115,6 +326,11 @@ impl VertexWinding {
 /// handle to a GPU resource. However, this does mean that modifying a buffer (e.g.
 /// calling `set_data`) will also affect any clones that exist of it.
 ///
+/// # Custom Vertex Types
+///
+/// By default, vertex buffers store [`Vertex`] data, but any type that implements
+/// [`VertexLayout`] can be used instead. The layout of a buffer is fixed when it is
+/// created - all future calls to [`set_data`](Self::set_data) must use the same type.
 #[derive(Clone, Debug, PartialEq)]
 pub struct VertexBuffer {
     handle: Rc<RawVertexBuffer>,
@@ -130,7 +346,14 @@ impl VertexBuffer {
     ///
     /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the underlying
     /// graphics API encounters an error.
-    pub fn new(ctx: &mut Context, vertices: &[Vertex]) -> Result<VertexBuffer> {
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the vertex type's attributes lie outside of the vertex.
+    pub fn new<V>(ctx: &mut Context, vertices: &[V]) -> Result<VertexBuffer>
+    where
+        V: VertexLayout,
+    {
         VertexBuffer::with_usage(ctx, vertices, BufferUsage::Dynamic)
     }
 
@@ -142,14 +365,22 @@ impl VertexBuffer {
     ///
     /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the underlying
     /// graphics API encounters an error.
-    pub fn with_usage(
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the vertex type's attributes lie outside of the vertex.
+    pub fn with_usage<V>(
         ctx: &mut Context,
-        vertices: &[Vertex],
+        vertices: &[V],
         usage: BufferUsage,
-    ) -> Result<VertexBuffer> {
-        let buffer = ctx.device.new_vertex_buffer(vertices.len(), usage)?;
+    ) -> Result<VertexBuffer>
+    where
+        V: VertexLayout,
+    {
+        let buffer = ctx.device.new_vertex_buffer::<V>(vertices.len(), usage)?;
 
         ctx.device.set_vertex_buffer_data(&buffer, vertices, 0);
+        ctx.graphics.stats.buffer_uploads += 1;
 
         Ok(VertexBuffer {
             handle: Rc::new(buffer),
@@ -160,10 +391,15 @@ impl VertexBuffer {
     ///
     /// # Panics
     ///
-    /// Panics if the offset is out of bounds.
-    pub fn set_data(&self, ctx: &mut Context, vertices: &[Vertex], offset: usize) {
+    /// Panics if the offset is out of bounds, or if the vertex type does not
+    /// match the one that the buffer was created with.
+    pub fn set_data<V>(&self, ctx: &mut Context, vertices: &[V], offset: usize)
+    where
+        V: VertexLayout,
+    {
         ctx.device
             .set_vertex_buffer_data(&self.handle, vertices, offset);
+        ctx.graphics.stats.buffer_uploads += 1;
     }
 
     /// Creates a mesh using this buffer.
@@ -232,6 +468,7 @@ impl IndexBuffer {
         let buffer = ctx.device.new_index_buffer(indices.len(), usage)?;
 
         ctx.device.set_index_buffer_data(&buffer, indices, 0);
+        ctx.graphics.stats.buffer_uploads += 1;
 
         Ok(IndexBuffer {
             handle: Rc::new(buffer),
@@ -246,6 +483,7 @@ impl IndexBuffer {
     pub fn set_data(&self, ctx: &mut Context, indices: &[u32], offset: usize) {
         ctx.device
             .set_index_buffer_data(&self.handle, indices, offset);
+        ctx.graphics.stats.buffer_uploads += 1;
     }
 }
 
@@ -255,6 +493,12 @@ struct DrawRange {
     count: usize,
 }
 
+#[derive(Clone, Debug)]
+struct InstanceData {
+    buffer: VertexBuffer,
+    divisor: u32,
+}
+
 /// Ways of drawing a shape.
 #[derive(Copy, Clone, Debug)]
 pub enum ShapeStyle {
@@ -271,6 +515,8 @@ pub enum ShapeStyle {
 ///
 /// * A [`Texture`] that individual vertices can sample from.
 /// * An [`IndexBuffer`] that can be used to modify the order/subset of vertices that are drawn.
+/// * An instance buffer, which can be used to provide per-instance data when [drawing
+///   multiple instances](Mesh::draw_instanced) of the mesh.
 /// * A winding order, which determines which side of the geometry is front-facing.
 /// * A backface culling flag, which determines whether back-facing geometry should be drawn.
 /// * A draw range, which can be used to draw subsections of the mesh.
@@ -300,6 +546,7 @@ pub enum ShapeStyle {
 pub struct Mesh {
     vertex_buffer: VertexBuffer,
     index_buffer: Option<IndexBuffer>,
+    instance_data: Option<InstanceData>,
     texture: Option<Texture>,
     draw_range: Option<DrawRange>,
     winding: VertexWinding,
@@ -312,6 +559,7 @@ impl Mesh {
         Mesh {
             vertex_buffer,
             index_buffer: None,
+            instance_data: None,
             texture: None,
             draw_range: None,
             winding: VertexWinding::CounterClockwise,
@@ -324,6 +572,7 @@ impl Mesh {
         Mesh {
             vertex_buffer,
             index_buffer: Some(index_buffer),
+            instance_data: None,
             texture: None,
             winding: VertexWinding::CounterClockwise,
             draw_range: None,
@@ -343,10 +592,14 @@ impl Mesh {
     /// if one is enabled).
     ///
     /// You will need to use a custom [`Shader`](crate::graphics::Shader) in order to pass unique
-    /// properties to each instance. Currently, the easiest way of doing this is via uniform
-    /// arrays - however, there is a hardware-determined limit on how many uniform locations
-    /// an individual shader can use, so this may not work if you're rendering a large
-    /// number of objects.
+    /// properties to each instance. The most scalable way of doing this is to attach an
+    /// [instance buffer](Self::set_instance_buffer) to the mesh, but for small numbers of
+    /// instances, it may be simpler to use uniform arrays and `gl_InstanceID` instead
+    /// (bearing in mind that there is a hardware-determined limit on how many uniform
+    /// locations an individual shader can use).
+    ///
+    /// If an instance buffer is attached, the number of instances will be capped to
+    /// the number that the buffer has data for.
     ///
     /// This should usually only be used for complex meshes - instancing can be inefficient
     /// for simple geometry (e.g. quads). That said, as with all things performance-related,
@@ -355,7 +608,7 @@ impl Mesh {
     where
         P: Into<DrawParams>,
     {
-        graphics::flush(ctx);
+        graphics::flush_with_reason(ctx, FlushReason::MeshDrawn);
 
         let texture = self
             .texture
@@ -382,11 +635,12 @@ impl Mesh {
         ctx.device.cull_face(self.backface_culling);
 
         // Because canvas rendering is effectively done upside-down, the winding order is the opposite
-        // of what you'd expect in that case.
-        ctx.device.front_face(match &ctx.graphics.canvas {
-            None => self.winding,
-            Some(_) => self.winding.flipped(),
-        });
+        // of what you'd expect in that case. Mirroring the mesh via the params also reverses it.
+        ctx.device
+            .front_face(match (&ctx.graphics.canvas, params.is_mirrored()) {
+                (None, false) | (Some(_), true) => self.winding,
+                (None, true) | (Some(_), false) => self.winding.flipped(),
+            });
 
         let (start, count) = match (self.draw_range, &self.index_buffer) {
             (Some(d), _) => (d.start, d.count),
@@ -394,15 +648,28 @@ impl Mesh {
             (_, None) => (0, self.vertex_buffer.handle.count()),
         };
 
+        let instances = match &self.instance_data {
+            Some(i) if i.divisor > 0 => {
+                usize::min(instances, i.buffer.handle.count() * i.divisor as usize)
+            }
+            _ => instances,
+        };
+
         ctx.device.draw_instanced(
             &self.vertex_buffer.handle,
             self.index_buffer.as_ref().map(|i| &*i.handle),
+            self.instance_data
+                .as_ref()
+                .map(|i| (&*i.buffer.handle, i.divisor)),
             &texture.data.handle,
             &shader.data.handle,
             start,
             count,
             instances,
         );
+
+        ctx.graphics.stats.draw_calls += 1;
+        ctx.graphics.stats.vertices += count * instances;
     }
 
     /// Gets a reference to the vertex buffer contained within this mesh.
@@ -432,6 +699,41 @@ impl Mesh {
         self.index_buffer = None;
     }
 
+    /// Gets a reference to the instance buffer contained within this mesh.
+    ///
+    /// Returns [`None`] if this mesh does not currently have an instance buffer attached.
+    pub fn instance_buffer(&self) -> Option<&VertexBuffer> {
+        self.instance_data.as_ref().map(|i| &i.buffer)
+    }
+
+    /// Gets the attribute divisor of the instance buffer contained within this mesh.
+    ///
+    /// Returns [`None`] if this mesh does not currently have an instance buffer attached.
+    pub fn instance_divisor(&self) -> Option<u32> {
+        self.instance_data.as_ref().map(|i| i.divisor)
+    }
+
+    /// Sets the instance buffer that will be used when drawing the mesh.
+    ///
+    /// The attributes in an instance buffer advance once every `divisor` instances, rather
+    /// than once per vertex - a divisor of `1` gives each instance its own piece of data.
+    /// They are bound to the shader by name, in the same way as [vertex attributes](VertexLayout),
+    /// so they should not share names with the attributes in the vertex buffer.
+    ///
+    /// The buffer can be updated every frame via [`VertexBuffer::set_data`], allowing
+    /// large numbers of moving objects to be drawn with a single draw call.
+    pub fn set_instance_buffer(&mut self, instance_buffer: VertexBuffer, divisor: u32) {
+        self.instance_data = Some(InstanceData {
+            buffer: instance_buffer,
+            divisor,
+        });
+    }
+
+    /// Resets the mesh to no longer use an instance buffer.
+    pub fn reset_instance_buffer(&mut self) {
+        self.instance_data = None;
+    }
+
     /// Gets a reference to the texture contained within this mesh.
     ///
     /// Returns [`None`] if this mesh does not currently have an texture attatched.
@@ -623,6 +925,49 @@ impl Mesh {
     }
 }
 
+/// # SVG loading
+#[cfg(feature = "svg")]
+impl Mesh {
+    /// Creates a new mesh from an SVG document.
+    ///
+    /// This method requires the `svg` feature to be enabled. See [`GeometryBuilder::svg`]
+    /// for details of what is supported.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::InvalidSvg`](crate::TetraError::InvalidSvg) will be returned if the document
+    /// could not be parsed.
+    /// * [`TetraError::TessellationError`](crate::TetraError::TessellationError) will be returned if a shape
+    /// could not be turned into vertex data.
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the underlying
+    /// graphics API encounters an error.
+    pub fn from_svg(ctx: &mut Context, data: &str) -> Result<Mesh> {
+        GeometryBuilder::new().svg(data)?.build_mesh(ctx)
+    }
+
+    /// Creates a new mesh from an SVG file.
+    ///
+    /// This method requires the `svg` feature to be enabled. See [`GeometryBuilder::svg`]
+    /// for details of what is supported.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::FailedToLoadAsset`](crate::TetraError::FailedToLoadAsset) will be returned
+    /// if the file could not be loaded.
+    /// * [`TetraError::InvalidSvg`](crate::TetraError::InvalidSvg) will be returned if the document
+    /// could not be parsed.
+    /// * [`TetraError::TessellationError`](crate::TetraError::TessellationError) will be returned if a shape
+    /// could not be turned into vertex data.
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the underlying
+    /// graphics API encounters an error.
+    pub fn from_svg_file<P>(ctx: &mut Context, path: P) -> Result<Mesh>
+    where
+        P: AsRef<std::path::Path>,
+    {
+        GeometryBuilder::new().svg_file(path)?.build_mesh(ctx)
+    }
+}
+
 impl From<VertexBuffer> for Mesh {
     fn from(buffer: VertexBuffer) -> Self {
         Mesh::new(buffer)
@@ -674,7 +1019,7 @@ impl StrokeVertexConstructor<Vertex> for TetraVertexConstructor {
 #[derive(Debug, Clone)]
 pub struct GeometryBuilder {
     data: VertexBuffers<Vertex, u32>,
-    color: Color,
+    paint: Paint,
 }
 
 impl GeometryBuilder {
@@ -682,7 +1027,7 @@ impl GeometryBuilder {
     pub fn new() -> GeometryBuilder {
         GeometryBuilder {
             data: VertexBuffers::new(),
-            color: Color::WHITE,
+            paint: Paint::Color(Color::WHITE),
         }
     }
 
@@ -697,7 +1042,13 @@ impl GeometryBuilder {
         style: ShapeStyle,
         rectangle: Rectangle,
     ) -> Result<&mut GeometryBuilder> {
-        let mut builder = BuffersBuilder::new(&mut self.data, TetraVertexConstructor(self.color));
+        let vertex_start = self.data.vertices.len();
+        let index_start = self.data.indices.len();
+
+        let mut builder = BuffersBuilder::new(
+            &mut self.data,
+            TetraVertexConstructor(self.paint.base_color()),
+        );
 
         match style {
             ShapeStyle::Fill => {
@@ -717,6 +1068,8 @@ impl GeometryBuilder {
             }
         }
 
+        apply_paint(&self.paint, &mut self.data, vertex_start, index_start);
+
         Ok(self)
     }
 
@@ -732,7 +1085,13 @@ impl GeometryBuilder {
         rectangle: Rectangle,
         radii: BorderRadii,
     ) -> Result<&mut GeometryBuilder> {
-        let mut builder = BuffersBuilder::new(&mut self.data, TetraVertexConstructor(self.color));
+        let vertex_start = self.data.vertices.len();
+        let index_start = self.data.indices.len();
+
+        let mut builder = BuffersBuilder::new(
+            &mut self.data,
+            TetraVertexConstructor(self.paint.base_color()),
+        );
 
         match style {
             ShapeStyle::Fill => {
@@ -752,6 +1111,8 @@ impl GeometryBuilder {
             }
         }
 
+        apply_paint(&self.paint, &mut self.data, vertex_start, index_start);
+
         Ok(self)
     }
 
@@ -767,7 +1128,13 @@ impl GeometryBuilder {
         center: Vec2<f32>,
         radius: f32,
     ) -> Result<&mut GeometryBuilder> {
-        let mut builder = BuffersBuilder::new(&mut self.data, TetraVertexConstructor(self.color));
+        let vertex_start = self.data.vertices.len();
+        let index_start = self.data.indices.len();
+
+        let mut builder = BuffersBuilder::new(
+            &mut self.data,
+            TetraVertexConstructor(self.paint.base_color()),
+        );
 
         match style {
             ShapeStyle::Fill => {
@@ -799,6 +1166,8 @@ impl GeometryBuilder {
             }
         }
 
+        apply_paint(&self.paint, &mut self.data, vertex_start, index_start);
+
         Ok(self)
     }
 
@@ -814,7 +1183,13 @@ impl GeometryBuilder {
         center: Vec2<f32>,
         radii: Vec2<f32>,
     ) -> Result<&mut GeometryBuilder> {
-        let mut builder = BuffersBuilder::new(&mut self.data, TetraVertexConstructor(self.color));
+        let vertex_start = self.data.vertices.len();
+        let index_start = self.data.indices.len();
+
+        let mut builder = BuffersBuilder::new(
+            &mut self.data,
+            TetraVertexConstructor(self.paint.base_color()),
+        );
 
         match style {
             ShapeStyle::Fill => {
@@ -850,6 +1225,8 @@ impl GeometryBuilder {
             }
         }
 
+        apply_paint(&self.paint, &mut self.data, vertex_start, index_start);
+
         Ok(self)
     }
 
@@ -864,7 +1241,13 @@ impl GeometryBuilder {
         style: ShapeStyle,
         points: &[Vec2<f32>],
     ) -> Result<&mut GeometryBuilder> {
-        let mut builder = BuffersBuilder::new(&mut self.data, TetraVertexConstructor(self.color));
+        let vertex_start = self.data.vertices.len();
+        let index_start = self.data.indices.len();
+
+        let mut builder = BuffersBuilder::new(
+            &mut self.data,
+            TetraVertexConstructor(self.paint.base_color()),
+        );
 
         let points: Vec<Point> = points
             .iter()
@@ -896,6 +1279,8 @@ impl GeometryBuilder {
             }
         }
 
+        apply_paint(&self.paint, &mut self.data, vertex_start, index_start);
+
         Ok(self)
     }
 
@@ -910,7 +1295,13 @@ impl GeometryBuilder {
         stroke_width: f32,
         points: &[Vec2<f32>],
     ) -> Result<&mut GeometryBuilder> {
-        let mut builder = BuffersBuilder::new(&mut self.data, TetraVertexConstructor(self.color));
+        let vertex_start = self.data.vertices.len();
+        let index_start = self.data.indices.len();
+
+        let mut builder = BuffersBuilder::new(
+            &mut self.data,
+            TetraVertexConstructor(self.paint.base_color()),
+        );
 
         let points: Vec<Point> = points
             .iter()
@@ -929,16 +1320,137 @@ impl GeometryBuilder {
             .tessellate_polygon(polygon, &options, &mut builder)
             .map_err(TetraError::TessellationError)?;
 
+        apply_paint(&self.paint, &mut self.data, vertex_start, index_start);
+
+        Ok(self)
+    }
+
+    /// Adds a filled [`Path`].
+    ///
+    /// Any sub-paths that are left open will be closed automatically. Overlapping sub-paths
+    /// are filled using the even-odd rule, so a sub-path inside another will cut a hole in it.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::TessellationError`](crate::TetraError::TessellationError) will be returned if the shape
+    /// could not be turned into vertex data.
+    pub fn fill_path(&mut self, path: &Path) -> Result<&mut GeometryBuilder> {
+        let vertex_start = self.data.vertices.len();
+        let index_start = self.data.indices.len();
+
+        self.tessellate_fill(
+            &path.to_lyon(),
+            &FillOptions::default(),
+            self.paint.base_color(),
+        )?;
+
+        apply_paint(&self.paint, &mut self.data, vertex_start, index_start);
+
+        Ok(self)
+    }
+
+    /// Adds a stroked [`Path`].
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::TessellationError`](crate::TetraError::TessellationError) will be returned if the shape
+    /// could not be turned into vertex data.
+    pub fn stroke_path(&mut self, path: &Path, stroke: &Stroke) -> Result<&mut GeometryBuilder> {
+        let vertex_start = self.data.vertices.len();
+        let index_start = self.data.indices.len();
+
+        self.tessellate_stroke(
+            &stroke.apply_dashes(path.to_lyon()),
+            &stroke.to_lyon(),
+            self.paint.base_color(),
+        )?;
+
+        apply_paint(&self.paint, &mut self.data, vertex_start, index_start);
+
         Ok(self)
     }
 
+    /// Adds the shapes from an SVG document.
+    ///
+    /// This method requires the `svg` feature to be enabled.
+    ///
+    /// Only the subset of SVG that is commonly used for icons and simple illustrations is
+    /// supported:
+    ///
+    /// | Feature | Supported |
+    /// |-|-|
+    /// | Elements | `svg`, `g`, `a`, `switch`, `path`, `rect`, `circle`, `ellipse`, `line`, `polyline` and `polygon` |
+    /// | Gradients | `linearGradient` and `radialGradient`, including `gradientUnits`, `gradientTransform` and stops inherited via `href` |
+    /// | Painting | `fill`, `stroke`, `fill-rule`, `opacity`, `fill-opacity` and `stroke-opacity` |
+    /// | Strokes | `stroke-width`, `stroke-linecap`, `stroke-linejoin`, `stroke-miterlimit`, `stroke-dasharray` and `stroke-dashoffset` |
+    /// | Colors | Hex, `rgb()`/`rgba()` and the basic named colors (`currentColor` is treated as black) |
+    /// | Styling | Presentation attributes and inline `style` attributes (but not `<style>` sheets) |
+    /// | Other | `transform`, `viewBox`, `display` and `visibility` |
+    ///
+    /// Anything else (such as text, images, filters, clipping, masking and `<use>` references)
+    /// is ignored.
+    ///
+    /// The document's `viewBox` will be scaled to fit its `width` and `height`, if they are
+    /// set. Paths and shapes in the document will be painted using their own colors, rather
+    /// than the builder's current paint.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::InvalidSvg`](crate::TetraError::InvalidSvg) will be returned if the document
+    /// could not be parsed.
+    /// * [`TetraError::TessellationError`](crate::TetraError::TessellationError) will be returned if a shape
+    /// could not be turned into vertex data.
+    #[cfg(feature = "svg")]
+    pub fn svg(&mut self, data: &str) -> Result<&mut GeometryBuilder> {
+        svg::add_svg(self, data)?;
+        Ok(self)
+    }
+
+    /// Adds the shapes from an SVG file.
+    ///
+    /// This method requires the `svg` feature to be enabled. See [`svg`](GeometryBuilder::svg)
+    /// for details of what is supported.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::FailedToLoadAsset`](crate::TetraError::FailedToLoadAsset) will be returned
+    /// if the file could not be loaded.
+    /// * [`TetraError::InvalidSvg`](crate::TetraError::InvalidSvg) will be returned if the document
+    /// could not be parsed.
+    /// * [`TetraError::TessellationError`](crate::TetraError::TessellationError) will be returned if a shape
+    /// could not be turned into vertex data.
+    #[cfg(feature = "svg")]
+    pub fn svg_file<P>(&mut self, path: P) -> Result<&mut GeometryBuilder>
+    where
+        P: AsRef<std::path::Path>,
+    {
+        let data = crate::fs::read_to_string(path)?;
+        self.svg(&data)
+    }
+
     /// Sets the color that will be used for subsequent shapes.
     ///
     /// You can also use [`DrawParams::color`](super::DrawParams) to tint an entire mesh -
     /// this method only needs to be used if you want to display multiple colors in a
     /// single piece of geometry.
     pub fn set_color(&mut self, color: Color) -> &mut GeometryBuilder {
-        self.color = color;
+        self.paint = Paint::Color(color);
+        self
+    }
+
+    /// Sets the paint that will be used to fill subsequent shapes.
+    ///
+    /// This allows shapes to be filled with gradients, or to have texture co-ordinates
+    /// generated for them. Each shape is painted relative to its own bounding box.
+    ///
+    /// Gradients are applied by splitting the shape's triangles wherever they cross a
+    /// gradient stop, so shapes with complex gradients will generate more vertices
+    /// than shapes with flat colors.
+    pub fn set_paint<P>(&mut self, paint: P) -> &mut GeometryBuilder
+    where
+        P: Into<Paint>,
+    {
+        self.paint = paint.into();
         self
     }
 
@@ -995,6 +1507,35 @@ impl GeometryBuilder {
     }
 }
 
+impl GeometryBuilder {
+    fn tessellate_fill(&mut self, path: &LyonPath, options: &FillOptions, color: Color) -> Result {
+        let mut builder = BuffersBuilder::new(&mut self.data, TetraVertexConstructor(color));
+        let mut tessellator = FillTessellator::new();
+
+        tessellator
+            .tessellate_path(path, options, &mut builder)
+            .map_err(TetraError::TessellationError)?;
+
+        Ok(())
+    }
+
+    fn tessellate_stroke(
+        &mut self,
+        path: &LyonPath,
+        options: &StrokeOptions,
+        color: Color,
+    ) -> Result {
+        let mut builder = BuffersBuilder::new(&mut self.data, TetraVertexConstructor(color));
+        let mut tessellator = StrokeTessellator::new();
+
+        tessellator
+            .tessellate_path(path, options, &mut builder)
+            .map_err(TetraError::TessellationError)?;
+
+        Ok(())
+    }
+}
+
 impl Default for GeometryBuilder {
     fn default() -> Self {
         GeometryBuilder::new()
@@ -0,0 +1,482 @@
+use lyon_tessellation::VertexBuffers;
+
+use crate::graphics::mesh::Vertex;
+use crate::graphics::{self, Color, Rectangle};
+use crate::math::Vec2;
+
+/// The maximum difference between a radial gradient's true value and the linearly
+/// interpolated value along a triangle edge, before the triangle gets subdivided.
+const RADIAL_TOLERANCE: f32 = 0.01;
+
+/// The maximum number of times a triangle will be subdivided to fit a radial gradient.
+const MAX_SUBDIVISIONS: u32 = 12;
+
+/// A color at a specific point along a gradient.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GradientStop {
+    /// The position of the stop along the gradient, from `0.0` to `1.0`.
+    pub offset: f32,
+
+    /// The color of the gradient at this stop.
+    pub color: Color,
+}
+
+impl GradientStop {
+    /// Creates a new gradient stop.
+    pub fn new(offset: f32, color: Color) -> GradientStop {
+        GradientStop { offset, color }
+    }
+}
+
+/// How the shapes generated by a [`GeometryBuilder`](super::GeometryBuilder) should be filled.
+///
+/// All of the positions used by a paint are relative to the bounding box of each shape,
+/// with `(0.0, 0.0)` being the top left corner and `(1.0, 1.0)` being the bottom right.
+/// This means that the same paint can be reused for shapes of different sizes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Paint {
+    /// A single, flat color.
+    Color(Color),
+
+    /// A gradient that changes color along a line.
+    ///
+    /// Stops should be sorted by their offset. Points before the first stop or
+    /// after the last stop will use the color of the nearest stop.
+    LinearGradient {
+        /// The point where the gradient starts (offset `0.0`).
+        start: Vec2<f32>,
+
+        /// The point where the gradient ends (offset `1.0`).
+        end: Vec2<f32>,
+
+        /// The colors of the gradient.
+        stops: Vec<GradientStop>,
+    },
+
+    /// A gradient that changes color outwards from a center point.
+    ///
+    /// As the positions are relative to the shape's bounds, the gradient will be
+    /// stretched into an ellipse for shapes that aren't square.
+    ///
+    /// Stops should be sorted by their offset. Points before the first stop or
+    /// after the last stop will use the color of the nearest stop.
+    RadialGradient {
+        /// The center of the gradient (offset `0.0`).
+        center: Vec2<f32>,
+
+        /// The distance from the center at which the gradient ends (offset `1.0`).
+        radius: f32,
+
+        /// The colors of the gradient.
+        stops: Vec<GradientStop>,
+    },
+
+    /// Generates texture co-ordinates, so that a texture can be stretched across the shape.
+    ///
+    /// Note that the texture itself is not part of the geometry - it should be set on the
+    /// [`Mesh`](super::Mesh) that the geometry is used in.
+    Texture {
+        /// The region of the texture to map onto the shape, in normalized UV co-ordinates
+        /// (i.e. `Rectangle::new(0.0, 0.0, 1.0, 1.0)` is the whole texture).
+        region: Rectangle,
+
+        /// The color to tint the texture with.
+        tint: Color,
+    },
+}
+
+impl Paint {
+    /// Creates a gradient that goes from left to right across a shape.
+    pub fn horizontal_gradient(from: Color, to: Color) -> Paint {
+        Paint::LinearGradient {
+            start: Vec2::new(0.0, 0.5),
+            end: Vec2::new(1.0, 0.5),
+            stops: vec![GradientStop::new(0.0, from), GradientStop::new(1.0, to)],
+        }
+    }
+
+    /// Creates a gradient that goes from top to bottom across a shape.
+    pub fn vertical_gradient(from: Color, to: Color) -> Paint {
+        Paint::LinearGradient {
+            start: Vec2::new(0.5, 0.0),
+            end: Vec2::new(0.5, 1.0),
+            stops: vec![GradientStop::new(0.0, from), GradientStop::new(1.0, to)],
+        }
+    }
+
+    /// Creates a gradient that goes from the center of a shape out to its edges.
+    pub fn radial_gradient(inner: Color, outer: Color) -> Paint {
+        Paint::RadialGradient {
+            center: Vec2::new(0.5, 0.5),
+            radius: 0.5,
+            stops: vec![GradientStop::new(0.0, inner), GradientStop::new(1.0, outer)],
+        }
+    }
+
+    /// Creates a paint that stretches an entire texture across a shape.
+    pub fn texture() -> Paint {
+        Paint::Texture {
+            region: Rectangle::new(0.0, 0.0, 1.0, 1.0),
+            tint: Color::WHITE,
+        }
+    }
+
+    /// The color that lyon should assign to new vertices.
+    pub(crate) fn base_color(&self) -> Color {
+        match self {
+            Paint::Color(color) => *color,
+            Paint::Texture { tint, .. } => *tint,
+            _ => Color::WHITE,
+        }
+    }
+}
+
+impl From<Color> for Paint {
+    fn from(color: Color) -> Paint {
+        Paint::Color(color)
+    }
+}
+
+/// The bounding box of a shape, used to normalize positions.
+pub(crate) struct Bounds {
+    pub(crate) min: Vec2<f32>,
+    pub(crate) size: Vec2<f32>,
+}
+
+impl Bounds {
+    pub(crate) fn new(vertices: &[Vertex]) -> Bounds {
+        let mut min = Vec2::broadcast(f32::INFINITY);
+        let mut max = Vec2::broadcast(f32::NEG_INFINITY);
+
+        for vertex in vertices {
+            min = Vec2::partial_min(min, vertex.position);
+            max = Vec2::partial_max(max, vertex.position);
+        }
+
+        let size = max - min;
+
+        // Avoid dividing by zero for degenerate shapes (e.g. a horizontal line).
+        Bounds {
+            min,
+            size: Vec2::new(
+                if size.x > 0.0 { size.x } else { 1.0 },
+                if size.y > 0.0 { size.y } else { 1.0 },
+            ),
+        }
+    }
+
+    fn normalize(&self, position: Vec2<f32>) -> Vec2<f32> {
+        (position - self.min) / self.size
+    }
+}
+
+/// Applies a paint to the vertices of the most recently tessellated shape, which
+/// start at the given offsets.
+pub(crate) fn apply_paint(
+    paint: &Paint,
+    data: &mut VertexBuffers<Vertex, u32>,
+    vertex_start: usize,
+    index_start: usize,
+) {
+    if vertex_start >= data.vertices.len() {
+        return;
+    }
+
+    let bounds = Bounds::new(&data.vertices[vertex_start..]);
+
+    apply_paint_with_bounds(paint, data, vertex_start, index_start, &bounds);
+}
+
+/// Applies a paint to the vertices of the most recently tessellated shape, with its
+/// positions relative to the given bounds rather than the shape's own.
+pub(crate) fn apply_paint_with_bounds(
+    paint: &Paint,
+    data: &mut VertexBuffers<Vertex, u32>,
+    vertex_start: usize,
+    index_start: usize,
+    bounds: &Bounds,
+) {
+    match paint {
+        Paint::Color(_) => {}
+
+        Paint::Texture { region, tint } => {
+            for vertex in &mut data.vertices[vertex_start..] {
+                let normalized = bounds.normalize(vertex.position);
+
+                vertex.uv = Vec2::new(
+                    region.x + normalized.x * region.width,
+                    region.y + normalized.y * region.height,
+                );
+
+                vertex.color = *tint;
+            }
+        }
+
+        Paint::LinearGradient { start, end, stops } => {
+            let direction = *end - *start;
+            let length_squared = direction.dot(direction);
+
+            let offset = |position| {
+                if length_squared > 0.0 {
+                    (bounds.normalize(position) - *start).dot(direction) / length_squared
+                } else {
+                    0.0
+                }
+            };
+
+            // Linear gradients vary linearly across each triangle, so they only
+            // need splitting at the stops.
+            remesh(data, vertex_start, index_start, stops, offset, false);
+        }
+
+        Paint::RadialGradient {
+            center,
+            radius,
+            stops,
+        } => {
+            let offset = |position| {
+                if *radius > 0.0 {
+                    (bounds.normalize(position) - *center).magnitude() / *radius
+                } else {
+                    0.0
+                }
+            };
+
+            remesh(data, vertex_start, index_start, stops, offset, true);
+        }
+    }
+}
+
+/// Replaces the triangles of a shape with ones that are colored by a gradient.
+///
+/// Vertex colors are interpolated linearly by the GPU, so in order for the gradient
+/// to be accurate, each triangle is split wherever it crosses a stop. If the gradient
+/// doesn't vary linearly across the triangle (i.e. if it's radial), it gets subdivided
+/// first, until it is close enough.
+fn remesh<F>(
+    data: &mut VertexBuffers<Vertex, u32>,
+    vertex_start: usize,
+    index_start: usize,
+    stops: &[GradientStop],
+    offset: F,
+    subdivide: bool,
+) where
+    F: Fn(Vec2<f32>) -> f32,
+{
+    let triangles: Vec<[Vec2<f32>; 3]> = data.indices[index_start..]
+        .chunks_exact(3)
+        .map(|tri| {
+            [
+                data.vertices[tri[0] as usize].position,
+                data.vertices[tri[1] as usize].position,
+                data.vertices[tri[2] as usize].position,
+            ]
+        })
+        .collect();
+
+    data.vertices.truncate(vertex_start);
+    data.indices.truncate(index_start);
+
+    let mut pieces = Vec::new();
+
+    for triangle in triangles {
+        pieces.clear();
+
+        if subdivide {
+            subdivide_triangle(triangle, &offset, 0, &mut pieces);
+        } else {
+            pieces.push(triangle);
+        }
+
+        for piece in &pieces {
+            let points = [
+                (piece[0], offset(piece[0])),
+                (piece[1], offset(piece[1])),
+                (piece[2], offset(piece[2])),
+            ];
+
+            split_at_stops(&points, stops, data);
+        }
+    }
+}
+
+fn subdivide_triangle<F>(
+    triangle: [Vec2<f32>; 3],
+    offset: &F,
+    depth: u32,
+    output: &mut Vec<[Vec2<f32>; 3]>,
+) where
+    F: Fn(Vec2<f32>) -> f32,
+{
+    if depth < MAX_SUBDIVISIONS {
+        // Find the edge where linear interpolation is the least accurate.
+        let mut worst = (0, 0.0);
+
+        for i in 0..3 {
+            let a = triangle[i];
+            let b = triangle[(i + 1) % 3];
+            let error = (offset((a + b) / 2.0) - (offset(a) + offset(b)) / 2.0).abs();
+
+            if error > worst.1 {
+                worst = (i, error);
+            }
+        }
+
+        if worst.1 > RADIAL_TOLERANCE {
+            let (i, _) = worst;
+            let a = triangle[i];
+            let b = triangle[(i + 1) % 3];
+            let c = triangle[(i + 2) % 3];
+            let mid = (a + b) / 2.0;
+
+            subdivide_triangle([a, mid, c], offset, depth + 1, output);
+            subdivide_triangle([mid, b, c], offset, depth + 1, output);
+
+            return;
+        }
+    }
+
+    output.push(triangle);
+}
+
+/// Splits a triangle along the lines where it crosses each stop, and adds the
+/// resulting pieces to the buffers.
+fn split_at_stops(
+    triangle: &[(Vec2<f32>, f32); 3],
+    stops: &[GradientStop],
+    data: &mut VertexBuffers<Vertex, u32>,
+) {
+    let mut remaining = triangle.to_vec();
+
+    for stop in stops {
+        if remaining.iter().all(|(_, t)| *t >= stop.offset) {
+            continue;
+        }
+
+        if remaining.iter().all(|(_, t)| *t <= stop.offset) {
+            break;
+        }
+
+        let (below, above) = split_polygon(&remaining, stop.offset);
+        push_polygon(&below, stops, data);
+        remaining = above;
+    }
+
+    push_polygon(&remaining, stops, data);
+}
+
+/// Splits a convex polygon into the parts below and above a given offset.
+#[allow(clippy::type_complexity)]
+fn split_polygon(
+    polygon: &[(Vec2<f32>, f32)],
+    split: f32,
+) -> (Vec<(Vec2<f32>, f32)>, Vec<(Vec2<f32>, f32)>) {
+    let mut below = Vec::new();
+    let mut above = Vec::new();
+
+    for (i, &(position, t)) in polygon.iter().enumerate() {
+        let (next_position, next_t) = polygon[(i + 1) % polygon.len()];
+
+        if t <= split {
+            below.push((position, t));
+        }
+
+        if t >= split {
+            above.push((position, t));
+        }
+
+        if (t < split && next_t > split) || (t > split && next_t < split) {
+            let amount = (split - t) / (next_t - t);
+            let crossing = (position + (next_position - position) * amount, split);
+
+            below.push(crossing);
+            above.push(crossing);
+        }
+    }
+
+    (below, above)
+}
+
+fn push_polygon(
+    polygon: &[(Vec2<f32>, f32)],
+    stops: &[GradientStop],
+    data: &mut VertexBuffers<Vertex, u32>,
+) {
+    if polygon.len() < 3 {
+        return;
+    }
+
+    let first = data.vertices.len() as u32;
+
+    for &(position, t) in polygon {
+        data.vertices.push(Vertex::new(
+            position,
+            Vec2::zero(),
+            gradient_color(stops, t),
+        ));
+    }
+
+    // The pieces are always convex, so they can be triangulated as a fan.
+    for i in 1..polygon.len() as u32 - 1 {
+        data.indices
+            .extend_from_slice(&[first, first + i, first + i + 1]);
+    }
+}
+
+/// Returns the color of a gradient at the given offset.
+fn gradient_color(stops: &[GradientStop], t: f32) -> Color {
+    graphics::interpolate_keyframes(
+        stops,
+        t,
+        Color::WHITE,
+        |stop| (stop.offset, stop.color),
+        Color::lerp,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stops() -> Vec<GradientStop> {
+        vec![
+            GradientStop::new(0.0, Color::BLACK),
+            GradientStop::new(0.5, Color::RED),
+            GradientStop::new(1.0, Color::WHITE),
+        ]
+    }
+
+    #[test]
+    fn gradient_colors() {
+        let stops = stops();
+
+        assert_eq!(gradient_color(&stops, -1.0), Color::BLACK);
+        assert_eq!(gradient_color(&stops, 0.25), Color::rgb(0.5, 0.0, 0.0));
+        assert_eq!(gradient_color(&stops, 0.5), Color::RED);
+        assert_eq!(gradient_color(&stops, 0.75), Color::rgb(1.0, 0.5, 0.5));
+        assert_eq!(gradient_color(&stops, 2.0), Color::WHITE);
+        assert_eq!(gradient_color(&[], 0.5), Color::WHITE);
+    }
+
+    #[test]
+    fn triangles_are_split_at_stops() {
+        let mut data = VertexBuffers::new();
+
+        let triangle = [
+            (Vec2::new(0.0, 0.0), 0.0),
+            (Vec2::new(1.0, 0.0), 1.0),
+            (Vec2::new(0.0, 1.0), 0.0),
+        ];
+
+        split_at_stops(&triangle, &stops(), &mut data);
+
+        // A quad below the middle stop, and a triangle above it.
+        assert_eq!(data.vertices.len(), 7);
+        assert_eq!(data.indices.len(), 9);
+
+        assert!(data
+            .vertices
+            .iter()
+            .any(|v| v.position == Vec2::new(0.5, 0.0) && v.color == Color::RED));
+    }
+}
@@ -0,0 +1,527 @@
+use lyon_tessellation::math::{Angle, Point, Vector};
+use lyon_tessellation::path::iterator::PathIterator;
+use lyon_tessellation::path::{Path as LyonPath, PathEvent};
+use lyon_tessellation::{LineCap as LyonLineCap, LineJoin as LyonLineJoin, StrokeOptions};
+
+use crate::math::Vec2;
+
+/// The tolerance used when flattening curves into line segments for dashing.
+const FLATTENING_TOLERANCE: f32 = 0.1;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PathCommand {
+    MoveTo(Vec2<f32>),
+    LineTo(Vec2<f32>),
+    QuadraticBezierTo(Vec2<f32>, Vec2<f32>),
+    CubicBezierTo(Vec2<f32>, Vec2<f32>, Vec2<f32>),
+    Arc {
+        center: Vec2<f32>,
+        radius: f32,
+        start_angle: f32,
+        sweep_angle: f32,
+    },
+    Close,
+}
+
+/// A vector path, made up of lines and curves.
+///
+/// Paths can be filled or stroked via [`GeometryBuilder::fill_path`](super::GeometryBuilder::fill_path)
+/// and [`GeometryBuilder::stroke_path`](super::GeometryBuilder::stroke_path).
+///
+/// A path can contain multiple sub-paths - calling [`move_to`](Path::move_to) will start a new one.
+/// If a drawing method is called without a preceding `move_to`, the sub-path will start at the
+/// point that the method is drawing to.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Path {
+    commands: Vec<PathCommand>,
+}
+
+impl Path {
+    /// Creates a new empty path.
+    pub fn new() -> Path {
+        Path {
+            commands: Vec::new(),
+        }
+    }
+
+    /// Starts a new sub-path at the given point.
+    pub fn move_to(&mut self, to: Vec2<f32>) -> &mut Path {
+        self.commands.push(PathCommand::MoveTo(to));
+        self
+    }
+
+    /// Adds a straight line from the current point.
+    pub fn line_to(&mut self, to: Vec2<f32>) -> &mut Path {
+        self.commands.push(PathCommand::LineTo(to));
+        self
+    }
+
+    /// Adds a quadratic bezier curve from the current point.
+    pub fn quadratic_bezier_to(&mut self, ctrl: Vec2<f32>, to: Vec2<f32>) -> &mut Path {
+        self.commands.push(PathCommand::QuadraticBezierTo(ctrl, to));
+        self
+    }
+
+    /// Adds a cubic bezier curve from the current point.
+    pub fn cubic_bezier_to(
+        &mut self,
+        ctrl1: Vec2<f32>,
+        ctrl2: Vec2<f32>,
+        to: Vec2<f32>,
+    ) -> &mut Path {
+        self.commands
+            .push(PathCommand::CubicBezierTo(ctrl1, ctrl2, to));
+        self
+    }
+
+    /// Adds a circular arc.
+    ///
+    /// The angles are in radians, with `0.0` pointing right and positive values sweeping
+    /// clockwise (as the Y axis points down). If there is a current point, a line will be
+    /// added from it to the start of the arc.
+    pub fn arc(
+        &mut self,
+        center: Vec2<f32>,
+        radius: f32,
+        start_angle: f32,
+        sweep_angle: f32,
+    ) -> &mut Path {
+        self.commands.push(PathCommand::Arc {
+            center,
+            radius,
+            start_angle,
+            sweep_angle,
+        });
+        self
+    }
+
+    /// Closes the current sub-path, adding a line back to its starting point.
+    pub fn close(&mut self) -> &mut Path {
+        self.commands.push(PathCommand::Close);
+        self
+    }
+
+    /// Returns `true` if nothing has been added to the path.
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Clears the path.
+    pub fn clear(&mut self) -> &mut Path {
+        self.commands.clear();
+        self
+    }
+
+    pub(crate) fn to_lyon(&self) -> LyonPath {
+        let mut builder = LyonPath::builder().with_svg();
+        let mut has_current = false;
+
+        for command in &self.commands {
+            match *command {
+                PathCommand::MoveTo(to) => {
+                    builder.move_to(to_point(to));
+                    has_current = true;
+                }
+
+                PathCommand::LineTo(to) => {
+                    builder.line_to(to_point(to));
+                    has_current = true;
+                }
+
+                PathCommand::QuadraticBezierTo(ctrl, to) => {
+                    builder.quadratic_bezier_to(to_point(ctrl), to_point(to));
+                    has_current = true;
+                }
+
+                PathCommand::CubicBezierTo(ctrl1, ctrl2, to) => {
+                    builder.cubic_bezier_to(to_point(ctrl1), to_point(ctrl2), to_point(to));
+                    has_current = true;
+                }
+
+                PathCommand::Arc {
+                    center,
+                    radius,
+                    start_angle,
+                    sweep_angle,
+                } => {
+                    let start = center + Vec2::new(start_angle.cos(), start_angle.sin()) * radius;
+
+                    if has_current {
+                        builder.line_to(to_point(start));
+                    } else {
+                        builder.move_to(to_point(start));
+                    }
+
+                    builder.arc(
+                        to_point(center),
+                        Vector::new(radius, radius),
+                        Angle::radians(sweep_angle),
+                        Angle::radians(0.0),
+                    );
+
+                    has_current = true;
+                }
+
+                PathCommand::Close => {
+                    builder.close();
+                    has_current = false;
+                }
+            }
+        }
+
+        builder.build()
+    }
+}
+
+fn to_point(point: Vec2<f32>) -> Point {
+    Point::new(point.x, point.y)
+}
+
+/// How the corners of a stroked path should be drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineJoin {
+    /// The outer edges of the lines are extended until they meet at a sharp point.
+    ///
+    /// If this would extend further than the [miter limit](Stroke::miter_limit), a bevel
+    /// is used instead.
+    Miter,
+
+    /// The same as `Miter`, but if the miter limit is exceeded, the point is clipped
+    /// instead of falling back to a bevel.
+    MiterClip,
+
+    /// The corner is rounded off.
+    Round,
+
+    /// The corner is cut off flat.
+    Bevel,
+}
+
+/// How the ends of a stroked path should be drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineCap {
+    /// The stroke ends exactly at the end of the path.
+    Butt,
+
+    /// The stroke extends past the end of the path by half of the stroke width.
+    Square,
+
+    /// The stroke ends in a semicircle.
+    Round,
+}
+
+/// Options for stroking a [`Path`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stroke {
+    /// The width of the stroke. Defaults to `1.0`.
+    pub width: f32,
+
+    /// How the corners of the path should be drawn. Defaults to [`LineJoin::Miter`].
+    pub line_join: LineJoin,
+
+    /// How the start of each sub-path (and each dash) should be drawn.
+    /// Defaults to [`LineCap::Butt`].
+    pub start_cap: LineCap,
+
+    /// How the end of each sub-path (and each dash) should be drawn.
+    /// Defaults to [`LineCap::Butt`].
+    pub end_cap: LineCap,
+
+    /// The limit on the ratio of a miter join's length to the stroke width.
+    /// Defaults to `4.0`, and values less than `1.0` will be treated as `1.0`.
+    pub miter_limit: f32,
+
+    /// Alternating lengths of dashes and gaps. Defaults to empty (a solid line).
+    ///
+    /// If an odd number of lengths is given, the pattern is repeated to make it even,
+    /// as in SVG.
+    pub dash_pattern: Vec<f32>,
+
+    /// How far into the dash pattern the stroke starts. Defaults to `0.0`.
+    pub dash_offset: f32,
+}
+
+impl Stroke {
+    /// Creates a new solid stroke with the given width.
+    pub fn new(width: f32) -> Stroke {
+        Stroke {
+            width,
+            ..Stroke::default()
+        }
+    }
+
+    /// Sets the width of the stroke.
+    pub fn width(mut self, width: f32) -> Stroke {
+        self.width = width;
+        self
+    }
+
+    /// Sets how the corners of the path should be drawn.
+    pub fn line_join(mut self, line_join: LineJoin) -> Stroke {
+        self.line_join = line_join;
+        self
+    }
+
+    /// Sets how both ends of each sub-path should be drawn.
+    pub fn line_cap(mut self, line_cap: LineCap) -> Stroke {
+        self.start_cap = line_cap;
+        self.end_cap = line_cap;
+        self
+    }
+
+    /// Sets how the start of each sub-path should be drawn.
+    pub fn start_cap(mut self, start_cap: LineCap) -> Stroke {
+        self.start_cap = start_cap;
+        self
+    }
+
+    /// Sets how the end of each sub-path should be drawn.
+    pub fn end_cap(mut self, end_cap: LineCap) -> Stroke {
+        self.end_cap = end_cap;
+        self
+    }
+
+    /// Sets the miter limit.
+    pub fn miter_limit(mut self, miter_limit: f32) -> Stroke {
+        self.miter_limit = miter_limit;
+        self
+    }
+
+    /// Sets the dash pattern.
+    pub fn dashes(mut self, pattern: &[f32], offset: f32) -> Stroke {
+        self.dash_pattern = pattern.to_vec();
+        self.dash_offset = offset;
+        self
+    }
+
+    pub(crate) fn to_lyon(&self) -> StrokeOptions {
+        StrokeOptions::default()
+            .with_line_width(self.width)
+            .with_line_join(match self.line_join {
+                LineJoin::Miter => LyonLineJoin::Miter,
+                LineJoin::MiterClip => LyonLineJoin::MiterClip,
+                LineJoin::Round => LyonLineJoin::Round,
+                LineJoin::Bevel => LyonLineJoin::Bevel,
+            })
+            .with_start_cap(to_lyon_cap(self.start_cap))
+            .with_end_cap(to_lyon_cap(self.end_cap))
+            .with_miter_limit(self.miter_limit.max(StrokeOptions::MINIMUM_MITER_LIMIT))
+    }
+
+    /// Splits a path into dashes, if a dash pattern is set.
+    pub(crate) fn apply_dashes(&self, path: LyonPath) -> LyonPath {
+        let mut pattern = self.dash_pattern.clone();
+
+        if pattern.len() % 2 == 1 {
+            pattern = pattern.repeat(2);
+        }
+
+        let total: f32 = pattern.iter().sum();
+
+        if pattern.is_empty() || pattern.iter().any(|l| *l < 0.0) || total <= 0.0 {
+            return path;
+        }
+
+        let mut dasher = Dasher::new(&pattern, self.dash_offset.rem_euclid(total));
+
+        for event in path.iter().flattened(FLATTENING_TOLERANCE) {
+            match event {
+                PathEvent::Begin { .. } => dasher.reset(),
+                PathEvent::Line { from, to } => dasher.line(from, to),
+                PathEvent::End {
+                    last, first, close, ..
+                } => {
+                    if close {
+                        dasher.line(last, first);
+                    }
+
+                    dasher.end_dash();
+                }
+                _ => {}
+            }
+        }
+
+        dasher.builder.build()
+    }
+}
+
+impl Default for Stroke {
+    fn default() -> Stroke {
+        Stroke {
+            width: 1.0,
+            line_join: LineJoin::Miter,
+            start_cap: LineCap::Butt,
+            end_cap: LineCap::Butt,
+            miter_limit: StrokeOptions::DEFAULT_MITER_LIMIT,
+            dash_pattern: Vec::new(),
+            dash_offset: 0.0,
+        }
+    }
+}
+
+fn to_lyon_cap(cap: LineCap) -> LyonLineCap {
+    match cap {
+        LineCap::Butt => LyonLineCap::Butt,
+        LineCap::Square => LyonLineCap::Square,
+        LineCap::Round => LyonLineCap::Round,
+    }
+}
+
+/// Walks along a flattened path, emitting a new sub-path for each dash.
+struct Dasher<'a> {
+    builder: lyon_tessellation::path::path::Builder,
+    pattern: &'a [f32],
+    offset: f32,
+
+    index: usize,
+    remaining: f32,
+    on: bool,
+    drawing: bool,
+}
+
+impl<'a> Dasher<'a> {
+    fn new(pattern: &'a [f32], offset: f32) -> Dasher<'a> {
+        let mut dasher = Dasher {
+            builder: LyonPath::builder(),
+            pattern,
+            offset,
+
+            index: 0,
+            remaining: 0.0,
+            on: true,
+            drawing: false,
+        };
+
+        dasher.reset();
+        dasher
+    }
+
+    /// Restarts the pattern, as each sub-path is dashed separately.
+    fn reset(&mut self) {
+        self.end_dash();
+
+        self.index = 0;
+        self.remaining = self.pattern[0];
+        self.on = true;
+
+        let mut offset = self.offset;
+
+        while offset > 0.0 {
+            if offset < self.remaining {
+                self.remaining -= offset;
+                break;
+            }
+
+            offset -= self.remaining;
+            self.advance();
+        }
+    }
+
+    fn advance(&mut self) {
+        self.index = (self.index + 1) % self.pattern.len();
+        self.remaining = self.pattern[self.index];
+        self.on = !self.on;
+    }
+
+    fn line(&mut self, from: Point, to: Point) {
+        let length = (to - from).length();
+
+        if length <= 0.0 {
+            return;
+        }
+
+        let direction = (to - from) / length;
+        let mut position = 0.0;
+
+        while position < length {
+            let step = self.remaining.min(length - position);
+            let on = self.on;
+
+            if on {
+                if !self.drawing {
+                    self.builder.begin(from + direction * position);
+                    self.drawing = true;
+                }
+
+                self.builder.line_to(from + direction * (position + step));
+            }
+
+            position += step;
+            self.remaining -= step;
+
+            if self.remaining <= 0.0 {
+                if on {
+                    self.end_dash();
+                }
+
+                self.advance();
+            }
+        }
+    }
+
+    fn end_dash(&mut self) {
+        if self.drawing {
+            self.builder.end(false);
+            self.drawing = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count_sub_paths(path: &LyonPath) -> usize {
+        path.iter()
+            .filter(|e| matches!(e, PathEvent::Begin { .. }))
+            .count()
+    }
+
+    #[test]
+    fn dashed_line() {
+        let mut path = Path::new();
+        path.move_to(Vec2::new(0.0, 0.0))
+            .line_to(Vec2::new(100.0, 0.0));
+
+        let dashed = Stroke::new(1.0)
+            .dashes(&[10.0, 5.0], 0.0)
+            .apply_dashes(path.to_lyon());
+
+        // 0-10, 15-25, ..., 90-100
+        assert_eq!(count_sub_paths(&dashed), 7);
+    }
+
+    #[test]
+    fn dashed_line_with_offset() {
+        let mut path = Path::new();
+        path.move_to(Vec2::new(0.0, 0.0))
+            .line_to(Vec2::new(100.0, 0.0));
+
+        let dashed = Stroke::new(1.0)
+            .dashes(&[10.0, 5.0], 10.0)
+            .apply_dashes(path.to_lyon());
+
+        // 5-15, 20-30, ..., 95-100
+        assert_eq!(count_sub_paths(&dashed), 7);
+
+        match dashed.iter().next() {
+            Some(PathEvent::Begin { at }) => assert_eq!(at, Point::new(5.0, 0.0)),
+            _ => panic!("expected the path to begin with a dash"),
+        }
+    }
+
+    #[test]
+    fn invalid_dash_patterns_are_ignored() {
+        let mut path = Path::new();
+        path.move_to(Vec2::new(0.0, 0.0))
+            .line_to(Vec2::new(100.0, 0.0));
+
+        for pattern in &[&[][..], &[0.0, 0.0], &[-1.0, 5.0]] {
+            let dashed = Stroke::new(1.0)
+                .dashes(pattern, 0.0)
+                .apply_dashes(path.to_lyon());
+
+            assert_eq!(count_sub_paths(&dashed), 1);
+        }
+    }
+}
@@ -0,0 +1,1457 @@
+//! Loading of SVG documents into shape geometry.
+//!
+//! Only the subset of SVG that is commonly used for icons and simple illustrations is
+//! supported: paths, basic shapes, solid fills and strokes, linear/radial gradients and
+//! groups with transforms. Text, images, filters, clipping, masking and `<use>` references
+//! are ignored.
+
+use hashbrown::HashMap;
+use lyon_tessellation::math::{point, rect, vector, Angle, Point};
+use lyon_tessellation::path::builder::{BorderRadii, PathBuilder, SvgPathBuilder};
+use lyon_tessellation::path::{ArcFlags, FillRule, Path as LyonPath, Polygon, Winding};
+use lyon_tessellation::FillOptions;
+use roxmltree::{Document, Node, ParsingOptions};
+
+use crate::error::{Result, TetraError};
+use crate::graphics::Color;
+use crate::math::Vec2;
+
+use super::{
+    apply_paint_with_bounds, Bounds, GeometryBuilder, GradientStop, LineCap, LineJoin, Paint,
+    Stroke,
+};
+
+/// The maximum length of a chain of gradients referencing each other's stops.
+const MAX_GRADIENT_REFERENCES: usize = 8;
+
+/// The maximum depth that elements can be nested to in an SVG document.
+const MAX_ELEMENT_DEPTH: usize = 256;
+
+/// Adds the shapes from an SVG document to a geometry builder.
+pub(crate) fn add_svg(builder: &mut GeometryBuilder, data: &str) -> Result {
+    let root = parse_document(data)?;
+
+    if root.name != "svg" {
+        return Err(TetraError::InvalidSvg(format!(
+            "expected root element to be <svg>, found <{}>",
+            root.name
+        )));
+    }
+
+    let (viewport, transform) = root_viewport(&root);
+
+    let mut gradients = HashMap::new();
+    collect_gradients(&root, &mut gradients);
+    resolve_gradient_references(&mut gradients);
+
+    let mut renderer = Renderer {
+        builder,
+        gradients,
+        viewport,
+    };
+
+    let mut style = Style::default();
+
+    if !style.apply_element(&root) {
+        return Ok(());
+    }
+
+    renderer.render_children(&root, &style, &transform)
+}
+
+/// Works out the size of the root viewport, and the transform that maps the `viewBox`
+/// onto it.
+fn root_viewport(root: &Element) -> (Vec2<f32>, Transform) {
+    let view_box = root
+        .attr("viewBox")
+        .map(parse_numbers)
+        .filter(|v| v.len() == 4 && v[2] > 0.0 && v[3] > 0.0);
+
+    let absolute = |name| {
+        root.attr(name)
+            .and_then(parse_length)
+            .filter(|l| !l.percent && l.value > 0.0)
+            .map(|l| l.value)
+    };
+
+    match view_box {
+        Some(vb) => {
+            let width = absolute("width").unwrap_or(vb[2]);
+            let height = absolute("height").unwrap_or(vb[3]);
+
+            // This matches the default `preserveAspectRatio` of `xMidYMid meet`.
+            let scale = f32::min(width / vb[2], height / vb[3]);
+
+            let transform = Transform {
+                a: scale,
+                b: 0.0,
+                c: 0.0,
+                d: scale,
+                e: (width - vb[2] * scale) / 2.0 - vb[0] * scale,
+                f: (height - vb[3] * scale) / 2.0 - vb[1] * scale,
+            };
+
+            (Vec2::new(vb[2], vb[3]), transform)
+        }
+
+        None => {
+            let width = absolute("width").unwrap_or(100.0);
+            let height = absolute("height").unwrap_or(100.0);
+
+            (Vec2::new(width, height), Transform::IDENTITY)
+        }
+    }
+}
+
+struct Renderer<'a> {
+    builder: &'a mut GeometryBuilder,
+    gradients: HashMap<String, Gradient>,
+    viewport: Vec2<f32>,
+}
+
+impl<'a> Renderer<'a> {
+    fn render_children(
+        &mut self,
+        element: &Element,
+        style: &Style,
+        transform: &Transform,
+    ) -> Result {
+        for child in &element.children {
+            self.render(child, style, transform)?;
+        }
+
+        Ok(())
+    }
+
+    fn render(
+        &mut self,
+        element: &Element,
+        parent_style: &Style,
+        parent_transform: &Transform,
+    ) -> Result {
+        let name = element.name.as_str();
+
+        if !matches!(
+            name,
+            "svg"
+                | "g"
+                | "a"
+                | "switch"
+                | "path"
+                | "rect"
+                | "circle"
+                | "ellipse"
+                | "line"
+                | "polyline"
+                | "polygon"
+        ) {
+            return Ok(());
+        }
+
+        let mut style = parent_style.clone();
+
+        if !style.apply_element(element) {
+            return Ok(());
+        }
+
+        let transform = match element.attr("transform") {
+            Some(value) => parent_transform.multiply(&parse_transform(value)),
+            None => *parent_transform,
+        };
+
+        let number = |name| {
+            element
+                .attr(name)
+                .and_then(parse_length)
+                .map(|l| l.value)
+                .unwrap_or(0.0)
+        };
+
+        match name {
+            "path" => {
+                if let Some(d) = element.attr("d") {
+                    self.draw(&parse_path_data(d), &style, &transform, true)?;
+                }
+            }
+
+            "rect" => {
+                let (x, y) = (number("x"), number("y"));
+                let (width, height) = (number("width"), number("height"));
+
+                if width > 0.0 && height > 0.0 {
+                    let rx = element.attr("rx").and_then(parse_length).map(|l| l.value);
+                    let ry = element.attr("ry").and_then(parse_length).map(|l| l.value);
+
+                    // Elliptical corners aren't supported, so the smaller radius is used.
+                    let radius = match (rx, ry) {
+                        (Some(rx), Some(ry)) => rx.min(ry),
+                        (Some(r), None) | (None, Some(r)) => r,
+                        (None, None) => 0.0,
+                    }
+                    .min(width / 2.0)
+                    .min(height / 2.0);
+
+                    let mut builder = LyonPath::builder();
+                    let bounds = rect(x, y, width, height);
+
+                    if radius > 0.0 {
+                        builder.add_rounded_rectangle(
+                            &bounds,
+                            &BorderRadii::new(radius),
+                            Winding::Positive,
+                        );
+                    } else {
+                        builder.add_rectangle(&bounds, Winding::Positive);
+                    }
+
+                    self.draw(&builder.build(), &style, &transform, true)?;
+                }
+            }
+
+            "circle" => {
+                let radius = number("r");
+
+                if radius > 0.0 {
+                    let mut builder = LyonPath::builder();
+                    builder.add_circle(
+                        point(number("cx"), number("cy")),
+                        radius,
+                        Winding::Positive,
+                    );
+                    self.draw(&builder.build(), &style, &transform, true)?;
+                }
+            }
+
+            "ellipse" => {
+                let (rx, ry) = (number("rx"), number("ry"));
+
+                if rx > 0.0 && ry > 0.0 {
+                    let mut builder = LyonPath::builder();
+                    builder.add_ellipse(
+                        point(number("cx"), number("cy")),
+                        vector(rx, ry),
+                        Angle::radians(0.0),
+                        Winding::Positive,
+                    );
+                    self.draw(&builder.build(), &style, &transform, true)?;
+                }
+            }
+
+            "line" => {
+                let mut builder = LyonPath::builder();
+                builder.begin(point(number("x1"), number("y1")));
+                builder.line_to(point(number("x2"), number("y2")));
+                builder.end(false);
+
+                self.draw(&builder.build(), &style, &transform, false)?;
+            }
+
+            "polyline" | "polygon" => {
+                let numbers = element
+                    .attr("points")
+                    .map(parse_numbers)
+                    .unwrap_or_default();
+                let points: Vec<Point> =
+                    numbers.chunks_exact(2).map(|p| point(p[0], p[1])).collect();
+
+                if points.len() >= 2 {
+                    let mut builder = LyonPath::builder();
+                    builder.add_polygon(Polygon {
+                        points: &points,
+                        closed: name == "polygon",
+                    });
+                    self.draw(&builder.build(), &style, &transform, true)?;
+                }
+            }
+
+            _ => self.render_children(element, &style, &transform)?,
+        }
+
+        Ok(())
+    }
+
+    fn draw(
+        &mut self,
+        path: &LyonPath,
+        style: &Style,
+        transform: &Transform,
+        fill: bool,
+    ) -> Result {
+        if !style.visible {
+            return Ok(());
+        }
+
+        if fill {
+            if let Some(paint) = self.resolve_paint(&style.fill, style.fill_opacity * style.opacity)
+            {
+                let vertex_start = self.builder.data.vertices.len();
+                let index_start = self.builder.data.indices.len();
+
+                let options = FillOptions::default().with_fill_rule(style.fill_rule);
+                self.builder
+                    .tessellate_fill(path, &options, paint.base_color())?;
+
+                self.finish_shape(&paint, vertex_start, index_start, transform);
+            }
+        }
+
+        if style.stroke.width > 0.0 {
+            if let Some(paint) =
+                self.resolve_paint(&style.stroke_paint, style.stroke_opacity * style.opacity)
+            {
+                let vertex_start = self.builder.data.vertices.len();
+                let index_start = self.builder.data.indices.len();
+
+                self.builder.tessellate_stroke(
+                    &style.stroke.apply_dashes(path.clone()),
+                    &style.stroke.to_lyon(),
+                    paint.base_color(),
+                )?;
+
+                self.finish_shape(&paint, vertex_start, index_start, transform);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Applies gradients and transforms to a newly tessellated shape.
+    ///
+    /// Both of these are applied after tessellation, so that gradients and strokes are
+    /// calculated in the shape's local space, as the SVG spec requires.
+    fn finish_shape(
+        &mut self,
+        paint: &ShapePaint,
+        vertex_start: usize,
+        index_start: usize,
+        transform: &Transform,
+    ) {
+        if vertex_start >= self.builder.data.vertices.len() {
+            return;
+        }
+
+        if let ShapePaint::Gradient(paint, user_space) = paint {
+            let bounds = if *user_space {
+                Bounds {
+                    min: Vec2::zero(),
+                    size: Vec2::one(),
+                }
+            } else {
+                Bounds::new(&self.builder.data.vertices[vertex_start..])
+            };
+
+            apply_paint_with_bounds(
+                paint,
+                &mut self.builder.data,
+                vertex_start,
+                index_start,
+                &bounds,
+            );
+        }
+
+        for vertex in &mut self.builder.data.vertices[vertex_start..] {
+            vertex.position = transform.apply(vertex.position);
+        }
+
+        // A mirroring transform reverses the winding of the triangles, which would
+        // cause them to be culled, so their winding has to be flipped back.
+        if transform.determinant() < 0.0 {
+            for triangle in self.builder.data.indices[index_start..].chunks_exact_mut(3) {
+                triangle.swap(1, 2);
+            }
+        }
+    }
+
+    fn resolve_paint(&self, paint: &SvgPaint, opacity: f32) -> Option<ShapePaint> {
+        match paint {
+            SvgPaint::None => None,
+            SvgPaint::Color(color) => Some(ShapePaint::Color(color.with_alpha(color.a * opacity))),
+            SvgPaint::Url(id) => {
+                let gradient = self.gradients.get(id)?;
+                let mut stops = gradient.stops.clone();
+
+                for stop in &mut stops {
+                    stop.color.a *= opacity;
+                }
+
+                match stops.len() {
+                    0 => None,
+                    1 => Some(ShapePaint::Color(stops[0].color)),
+                    _ => Some(ShapePaint::Gradient(
+                        gradient.to_paint(stops, self.viewport),
+                        gradient.user_space,
+                    )),
+                }
+            }
+        }
+    }
+}
+
+/// A paint that has been resolved for a specific shape.
+enum ShapePaint {
+    Color(Color),
+    Gradient(Paint, bool),
+}
+
+impl ShapePaint {
+    fn base_color(&self) -> Color {
+        match self {
+            ShapePaint::Color(color) => *color,
+            ShapePaint::Gradient(..) => Color::WHITE,
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Styles
+// ----------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+enum SvgPaint {
+    None,
+    Color(Color),
+    Url(String),
+}
+
+#[derive(Debug, Clone)]
+struct Style {
+    fill: SvgPaint,
+    fill_opacity: f32,
+    fill_rule: FillRule,
+    stroke_paint: SvgPaint,
+    stroke_opacity: f32,
+    stroke: Stroke,
+    opacity: f32,
+    visible: bool,
+}
+
+impl Default for Style {
+    fn default() -> Style {
+        Style {
+            fill: SvgPaint::Color(Color::BLACK),
+            fill_opacity: 1.0,
+            fill_rule: FillRule::NonZero,
+            stroke_paint: SvgPaint::None,
+            stroke_opacity: 1.0,
+            stroke: Stroke::new(1.0),
+            opacity: 1.0,
+            visible: true,
+        }
+    }
+}
+
+impl Style {
+    /// Applies the presentation attributes and inline styles of an element.
+    ///
+    /// Returns `false` if the element should not be displayed at all.
+    fn apply_element(&mut self, element: &Element) -> bool {
+        let mut displayed = true;
+
+        let declarations = element
+            .attributes
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .chain(element.attr("style").into_iter().flat_map(parse_style));
+
+        for (name, value) in declarations {
+            if name == "display" {
+                displayed = value != "none";
+            } else {
+                self.apply(name, value);
+            }
+        }
+
+        displayed
+    }
+
+    fn apply(&mut self, name: &str, value: &str) {
+        let value = value.trim();
+
+        if value == "inherit" {
+            return;
+        }
+
+        match name {
+            "fill" => {
+                if let Some(paint) = parse_paint(value) {
+                    self.fill = paint;
+                }
+            }
+
+            "stroke" => {
+                if let Some(paint) = parse_paint(value) {
+                    self.stroke_paint = paint;
+                }
+            }
+
+            "fill-opacity" => {
+                if let Some(opacity) = parse_opacity(value) {
+                    self.fill_opacity = opacity;
+                }
+            }
+
+            "stroke-opacity" => {
+                if let Some(opacity) = parse_opacity(value) {
+                    self.stroke_opacity = opacity;
+                }
+            }
+
+            "opacity" => {
+                // Group opacity should really be applied to the group as a whole, but
+                // multiplying it into the children is a close enough approximation.
+                if let Some(opacity) = parse_opacity(value) {
+                    self.opacity *= opacity;
+                }
+            }
+
+            "fill-rule" => match value {
+                "nonzero" => self.fill_rule = FillRule::NonZero,
+                "evenodd" => self.fill_rule = FillRule::EvenOdd,
+                _ => {}
+            },
+
+            "stroke-width" => {
+                if let Some(width) = parse_length(value) {
+                    self.stroke.width = width.value;
+                }
+            }
+
+            "stroke-linejoin" => match value {
+                "miter" | "arcs" => self.stroke.line_join = LineJoin::Miter,
+                "miter-clip" => self.stroke.line_join = LineJoin::MiterClip,
+                "round" => self.stroke.line_join = LineJoin::Round,
+                "bevel" => self.stroke.line_join = LineJoin::Bevel,
+                _ => {}
+            },
+
+            "stroke-linecap" => match value {
+                "butt" => self.stroke = self.stroke.clone().line_cap(LineCap::Butt),
+                "round" => self.stroke = self.stroke.clone().line_cap(LineCap::Round),
+                "square" => self.stroke = self.stroke.clone().line_cap(LineCap::Square),
+                _ => {}
+            },
+
+            "stroke-miterlimit" => {
+                if let Ok(limit) = value.parse() {
+                    self.stroke.miter_limit = limit;
+                }
+            }
+
+            "stroke-dasharray" => {
+                self.stroke.dash_pattern = if value == "none" {
+                    Vec::new()
+                } else {
+                    parse_numbers(value)
+                };
+            }
+
+            "stroke-dashoffset" => {
+                if let Some(offset) = parse_length(value) {
+                    self.stroke.dash_offset = offset.value;
+                }
+            }
+
+            "visibility" => self.visible = value == "visible",
+
+            _ => {}
+        }
+    }
+}
+
+/// Splits a `style` attribute into its declarations.
+fn parse_style(style: &str) -> impl Iterator<Item = (&str, &str)> {
+    style.split(';').filter_map(|declaration| {
+        let (name, value) = declaration.split_once(':')?;
+        Some((name.trim(), value.trim()))
+    })
+}
+
+fn parse_paint(value: &str) -> Option<SvgPaint> {
+    if value == "none" {
+        return Some(SvgPaint::None);
+    }
+
+    if let Some(rest) = value.strip_prefix("url(") {
+        let end = rest.find(')')?;
+        let id = rest[..end].trim().trim_matches(|c| c == '"' || c == '\'');
+        return Some(SvgPaint::Url(id.trim_start_matches('#').to_owned()));
+    }
+
+    parse_color(value).map(SvgPaint::Color)
+}
+
+fn parse_opacity(value: &str) -> Option<f32> {
+    let opacity = match value.strip_suffix('%') {
+        Some(percent) => percent.trim().parse::<f32>().ok()? / 100.0,
+        None => value.parse().ok()?,
+    };
+
+    Some(opacity.clamp(0.0, 1.0))
+}
+
+fn parse_color(value: &str) -> Option<Color> {
+    let value = value.trim();
+
+    if let Some(hex) = value.strip_prefix('#') {
+        if hex.len() == 3 || hex.len() == 4 {
+            let expanded: String = hex.chars().flat_map(|c| [c, c]).collect();
+            return Color::try_hex(&expanded).ok();
+        }
+
+        return Color::try_hex(hex).ok();
+    }
+
+    if let Some(args) = value
+        .strip_prefix("rgba(")
+        .or_else(|| value.strip_prefix("rgb("))
+    {
+        let args = args.strip_suffix(')')?;
+        let mut channels = args.split([',', ' ', '/']).filter(|s| !s.is_empty());
+
+        let mut channel = || -> Option<f32> {
+            let channel = channels.next()?.trim();
+
+            match channel.strip_suffix('%') {
+                Some(percent) => Some(percent.parse::<f32>().ok()? / 100.0),
+                None => Some(channel.parse::<f32>().ok()? / 255.0),
+            }
+        };
+
+        let (r, g, b) = (channel()?, channel()?, channel()?);
+        let a = channels.next().and_then(parse_opacity).unwrap_or(1.0);
+
+        return Some(Color::rgba(r, g, b, a).clamp());
+    }
+
+    let rgb = match value.to_ascii_lowercase().as_str() {
+        "transparent" => return Some(Color::rgba(0.0, 0.0, 0.0, 0.0)),
+        "black" | "currentcolor" => 0x000000,
+        "white" => 0xffffff,
+        "red" => 0xff0000,
+        "lime" => 0x00ff00,
+        "green" => 0x008000,
+        "blue" => 0x0000ff,
+        "yellow" => 0xffff00,
+        "cyan" | "aqua" => 0x00ffff,
+        "magenta" | "fuchsia" => 0xff00ff,
+        "gray" | "grey" => 0x808080,
+        "silver" => 0xc0c0c0,
+        "lightgray" | "lightgrey" => 0xd3d3d3,
+        "darkgray" | "darkgrey" => 0xa9a9a9,
+        "maroon" => 0x800000,
+        "olive" => 0x808000,
+        "navy" => 0x000080,
+        "purple" => 0x800080,
+        "teal" => 0x008080,
+        "orange" => 0xffa500,
+        "pink" => 0xffc0cb,
+        "brown" => 0xa52a2a,
+        "gold" => 0xffd700,
+        "indigo" => 0x4b0082,
+        "violet" => 0xee82ee,
+        _ => return None,
+    };
+
+    Some(Color::rgb8((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8))
+}
+
+// ----------------------------------------------------------------------------
+// Gradients
+// ----------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Length {
+    value: f32,
+    percent: bool,
+}
+
+fn parse_length(value: &str) -> Option<Length> {
+    let mut scanner = Scanner::new(value);
+    let number = scanner.number()?;
+
+    Some(match scanner.peek() {
+        Some(b'%') => Length {
+            value: number,
+            percent: true,
+        },
+        _ => Length {
+            value: number,
+            percent: false,
+        },
+    })
+}
+
+#[derive(Debug, Clone)]
+enum GradientShape {
+    Linear {
+        x1: Length,
+        y1: Length,
+        x2: Length,
+        y2: Length,
+    },
+    Radial {
+        cx: Length,
+        cy: Length,
+        r: Length,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct Gradient {
+    shape: GradientShape,
+    stops: Vec<GradientStop>,
+    user_space: bool,
+    transform: Transform,
+    href: Option<String>,
+}
+
+impl Gradient {
+    fn parse(element: &Element, linear: bool) -> Gradient {
+        let length = |name, default| {
+            element.attr(name).and_then(parse_length).unwrap_or(Length {
+                value: default,
+                percent: true,
+            })
+        };
+
+        let shape = if linear {
+            GradientShape::Linear {
+                x1: length("x1", 0.0),
+                y1: length("y1", 0.0),
+                x2: length("x2", 100.0),
+                y2: length("y2", 0.0),
+            }
+        } else {
+            GradientShape::Radial {
+                cx: length("cx", 50.0),
+                cy: length("cy", 50.0),
+                r: length("r", 50.0),
+            }
+        };
+
+        let mut stops: Vec<GradientStop> = Vec::new();
+
+        for child in &element.children {
+            if child.name != "stop" {
+                continue;
+            }
+
+            let mut color = Color::BLACK;
+            let mut opacity = 1.0;
+
+            let declarations = child
+                .attributes
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.as_str()))
+                .chain(child.attr("style").into_iter().flat_map(parse_style));
+
+            for (name, value) in declarations {
+                match name {
+                    "stop-color" => color = parse_color(value).unwrap_or(color),
+                    "stop-opacity" => opacity = parse_opacity(value).unwrap_or(opacity),
+                    _ => {}
+                }
+            }
+
+            let offset = child.attr("offset").and_then(parse_opacity).unwrap_or(0.0);
+
+            // Offsets must never decrease, as per the spec.
+            let offset = stops.last().map_or(offset, |last| offset.max(last.offset));
+
+            stops.push(GradientStop::new(
+                offset,
+                color.with_alpha(color.a * opacity),
+            ));
+        }
+
+        Gradient {
+            shape,
+            stops,
+            user_space: element.attr("gradientUnits") == Some("userSpaceOnUse"),
+            transform: element
+                .attr("gradientTransform")
+                .map(parse_transform)
+                .unwrap_or(Transform::IDENTITY),
+            href: element
+                .href()
+                .map(|href| href.trim_start_matches('#').to_owned()),
+        }
+    }
+
+    fn to_paint(&self, stops: Vec<GradientStop>, viewport: Vec2<f32>) -> Paint {
+        // In user space, percentages are relative to the viewport. In bounding box space,
+        // they're relative to the bounding box, which is what `Paint` expects anyway.
+        let resolve = |length: Length, size: f32| {
+            if !length.percent {
+                length.value
+            } else if self.user_space {
+                length.value / 100.0 * size
+            } else {
+                length.value / 100.0
+            }
+        };
+
+        match self.shape {
+            GradientShape::Linear { x1, y1, x2, y2 } => Paint::LinearGradient {
+                start: self
+                    .transform
+                    .apply(Vec2::new(resolve(x1, viewport.x), resolve(y1, viewport.y))),
+                end: self
+                    .transform
+                    .apply(Vec2::new(resolve(x2, viewport.x), resolve(y2, viewport.y))),
+                stops,
+            },
+
+            GradientShape::Radial { cx, cy, r } => {
+                let diagonal = viewport.magnitude() / std::f32::consts::SQRT_2;
+
+                // Rotations and skews can't be represented by `Paint`, so only the
+                // average scale is applied to the radius.
+                let scale = (self.transform.a * self.transform.d
+                    - self.transform.b * self.transform.c)
+                    .abs()
+                    .sqrt();
+
+                Paint::RadialGradient {
+                    center: self
+                        .transform
+                        .apply(Vec2::new(resolve(cx, viewport.x), resolve(cy, viewport.y))),
+                    radius: resolve(r, diagonal) * scale,
+                    stops,
+                }
+            }
+        }
+    }
+}
+
+fn collect_gradients(element: &Element, gradients: &mut HashMap<String, Gradient>) {
+    let name = element.name.as_str();
+
+    if name == "linearGradient" || name == "radialGradient" {
+        if let Some(id) = element.attr("id") {
+            gradients.insert(
+                id.to_owned(),
+                Gradient::parse(element, name == "linearGradient"),
+            );
+        }
+    }
+
+    for child in &element.children {
+        collect_gradients(child, gradients);
+    }
+}
+
+/// Copies stops from referenced gradients, which is how editors like Inkscape share
+/// stops between multiple gradients.
+fn resolve_gradient_references(gradients: &mut HashMap<String, Gradient>) {
+    let ids: Vec<String> = gradients.keys().cloned().collect();
+
+    for id in ids {
+        let mut current = id.clone();
+
+        for _ in 0..MAX_GRADIENT_REFERENCES {
+            let gradient = &gradients[&current];
+
+            if !gradient.stops.is_empty() {
+                if current != id {
+                    let stops = gradient.stops.clone();
+                    gradients.get_mut(&id).unwrap().stops = stops;
+                }
+
+                break;
+            }
+
+            match &gradient.href {
+                Some(href) if gradients.contains_key(href) => current = href.clone(),
+                _ => break,
+            }
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Transforms
+// ----------------------------------------------------------------------------
+
+/// A 2D affine transform, in the same layout as SVG's `matrix(a b c d e f)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Transform {
+    a: f32,
+    b: f32,
+    c: f32,
+    d: f32,
+    e: f32,
+    f: f32,
+}
+
+impl Transform {
+    const IDENTITY: Transform = Transform {
+        a: 1.0,
+        b: 0.0,
+        c: 0.0,
+        d: 1.0,
+        e: 0.0,
+        f: 0.0,
+    };
+
+    /// Returns a transform that applies `other`, followed by `self`.
+    fn multiply(&self, other: &Transform) -> Transform {
+        Transform {
+            a: self.a * other.a + self.c * other.b,
+            b: self.b * other.a + self.d * other.b,
+            c: self.a * other.c + self.c * other.d,
+            d: self.b * other.c + self.d * other.d,
+            e: self.a * other.e + self.c * other.f + self.e,
+            f: self.b * other.e + self.d * other.f + self.f,
+        }
+    }
+
+    fn determinant(&self) -> f32 {
+        self.a * self.d - self.b * self.c
+    }
+
+    fn apply(&self, point: Vec2<f32>) -> Vec2<f32> {
+        Vec2::new(
+            self.a * point.x + self.c * point.y + self.e,
+            self.b * point.x + self.d * point.y + self.f,
+        )
+    }
+}
+
+fn parse_transform(value: &str) -> Transform {
+    let mut transform = Transform::IDENTITY;
+    let mut rest = value;
+
+    while let Some(open) = rest.find('(') {
+        let name = rest[..open].trim_matches(|c: char| c.is_whitespace() || c == ',');
+
+        let close = match rest[open..].find(')') {
+            Some(close) => open + close,
+            None => break,
+        };
+
+        let args = parse_numbers(&rest[open + 1..close]);
+        let arg = |i: usize| args.get(i).copied();
+
+        let next = match (name, args.len()) {
+            ("matrix", 6) => Transform {
+                a: args[0],
+                b: args[1],
+                c: args[2],
+                d: args[3],
+                e: args[4],
+                f: args[5],
+            },
+
+            ("translate", 1..=2) => Transform {
+                e: args[0],
+                f: arg(1).unwrap_or(0.0),
+                ..Transform::IDENTITY
+            },
+
+            ("scale", 1..=2) => Transform {
+                a: args[0],
+                d: arg(1).unwrap_or(args[0]),
+                ..Transform::IDENTITY
+            },
+
+            ("rotate", 1) | ("rotate", 3) => {
+                let (sin, cos) = args[0].to_radians().sin_cos();
+                let (cx, cy) = (arg(1).unwrap_or(0.0), arg(2).unwrap_or(0.0));
+
+                let rotation = Transform {
+                    a: cos,
+                    b: sin,
+                    c: -sin,
+                    d: cos,
+                    e: 0.0,
+                    f: 0.0,
+                };
+
+                let to_center = Transform {
+                    e: cx,
+                    f: cy,
+                    ..Transform::IDENTITY
+                };
+
+                let from_center = Transform {
+                    e: -cx,
+                    f: -cy,
+                    ..Transform::IDENTITY
+                };
+
+                to_center.multiply(&rotation).multiply(&from_center)
+            }
+
+            ("skewX", 1) => Transform {
+                c: args[0].to_radians().tan(),
+                ..Transform::IDENTITY
+            },
+
+            ("skewY", 1) => Transform {
+                b: args[0].to_radians().tan(),
+                ..Transform::IDENTITY
+            },
+
+            _ => Transform::IDENTITY,
+        };
+
+        transform = transform.multiply(&next);
+        rest = &rest[close + 1..];
+    }
+
+    transform
+}
+
+// ----------------------------------------------------------------------------
+// Path data
+// ----------------------------------------------------------------------------
+
+/// Scans numbers out of SVG attributes, which can be separated by whitespace and/or
+/// commas, or not at all (e.g. `1-2.5.5` is three numbers).
+struct Scanner<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Scanner<'a> {
+    fn new(value: &'a str) -> Scanner<'a> {
+        Scanner {
+            bytes: value.as_bytes(),
+            pos: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn skip_separators(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\r' | b'\n' | b',')) {
+            self.pos += 1;
+        }
+    }
+
+    fn skip_digits(&mut self) -> usize {
+        let start = self.pos;
+
+        while matches!(self.peek(), Some(b'0'..=b'9')) {
+            self.pos += 1;
+        }
+
+        self.pos - start
+    }
+
+    fn number(&mut self) -> Option<f32> {
+        self.skip_separators();
+
+        let start = self.pos;
+
+        if matches!(self.peek(), Some(b'+' | b'-')) {
+            self.pos += 1;
+        }
+
+        let mut digits = self.skip_digits();
+
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            digits += self.skip_digits();
+        }
+
+        if digits == 0 {
+            self.pos = start;
+            return None;
+        }
+
+        if matches!(self.peek(), Some(b'e' | b'E')) {
+            let exponent_start = self.pos;
+            self.pos += 1;
+
+            if matches!(self.peek(), Some(b'+' | b'-')) {
+                self.pos += 1;
+            }
+
+            if self.skip_digits() == 0 {
+                self.pos = exponent_start;
+            }
+        }
+
+        std::str::from_utf8(&self.bytes[start..self.pos])
+            .ok()?
+            .parse()
+            .ok()
+    }
+
+    fn flag(&mut self) -> Option<bool> {
+        self.skip_separators();
+
+        let flag = match self.peek()? {
+            b'0' => false,
+            b'1' => true,
+            _ => return None,
+        };
+
+        self.pos += 1;
+        Some(flag)
+    }
+
+    fn point(&mut self) -> Option<Point> {
+        Some(point(self.number()?, self.number()?))
+    }
+}
+
+fn parse_numbers(value: &str) -> Vec<f32> {
+    let mut scanner = Scanner::new(value);
+    std::iter::from_fn(|| scanner.number()).collect()
+}
+
+/// Parses SVG path data.
+///
+/// As the spec recommends, if an error is encountered, everything up to that point
+/// is still returned.
+fn parse_path_data(data: &str) -> LyonPath {
+    let mut builder = LyonPath::builder().with_svg();
+    let mut scanner = Scanner::new(data);
+    let mut command = None;
+
+    loop {
+        scanner.skip_separators();
+
+        let next = match scanner.peek() {
+            Some(next) => next,
+            None => break,
+        };
+
+        if next.is_ascii_alphabetic() {
+            scanner.pos += 1;
+
+            if next == b'Z' || next == b'z' {
+                builder.close();
+                command = None;
+                continue;
+            }
+
+            command = Some(next);
+        }
+
+        let current = match command {
+            Some(current) => current,
+            None => break,
+        };
+
+        // After a move, any further coordinates are treated as lines.
+        match current {
+            b'M' => command = Some(b'L'),
+            b'm' => command = Some(b'l'),
+            _ => {}
+        }
+
+        if parse_segment(&mut builder, &mut scanner, current).is_none() {
+            break;
+        }
+    }
+
+    builder.build()
+}
+
+fn parse_segment(
+    builder: &mut impl SvgPathBuilder,
+    scanner: &mut Scanner,
+    command: u8,
+) -> Option<()> {
+    match command {
+        b'M' => builder.move_to(scanner.point()?),
+        b'm' => builder.relative_move_to(scanner.point()?.to_vector()),
+        b'L' => builder.line_to(scanner.point()?),
+        b'l' => builder.relative_line_to(scanner.point()?.to_vector()),
+        b'H' => builder.horizontal_line_to(scanner.number()?),
+        b'h' => builder.relative_horizontal_line_to(scanner.number()?),
+        b'V' => builder.vertical_line_to(scanner.number()?),
+        b'v' => builder.relative_vertical_line_to(scanner.number()?),
+
+        b'C' => {
+            let (ctrl1, ctrl2, to) = (scanner.point()?, scanner.point()?, scanner.point()?);
+            builder.cubic_bezier_to(ctrl1, ctrl2, to);
+        }
+
+        b'c' => {
+            let (ctrl1, ctrl2, to) = (scanner.point()?, scanner.point()?, scanner.point()?);
+            builder.relative_cubic_bezier_to(ctrl1.to_vector(), ctrl2.to_vector(), to.to_vector());
+        }
+
+        b'S' => {
+            let (ctrl2, to) = (scanner.point()?, scanner.point()?);
+            builder.smooth_cubic_bezier_to(ctrl2, to);
+        }
+
+        b's' => {
+            let (ctrl2, to) = (scanner.point()?, scanner.point()?);
+            builder.smooth_relative_cubic_bezier_to(ctrl2.to_vector(), to.to_vector());
+        }
+
+        b'Q' => {
+            let (ctrl, to) = (scanner.point()?, scanner.point()?);
+            builder.quadratic_bezier_to(ctrl, to);
+        }
+
+        b'q' => {
+            let (ctrl, to) = (scanner.point()?, scanner.point()?);
+            builder.relative_quadratic_bezier_to(ctrl.to_vector(), to.to_vector());
+        }
+
+        b'T' => builder.smooth_quadratic_bezier_to(scanner.point()?),
+        b't' => builder.smooth_relative_quadratic_bezier_to(scanner.point()?.to_vector()),
+
+        b'A' | b'a' => {
+            let radii = vector(scanner.number()?.abs(), scanner.number()?.abs());
+            let x_rotation = Angle::degrees(scanner.number()?);
+
+            let flags = ArcFlags {
+                large_arc: scanner.flag()?,
+                sweep: scanner.flag()?,
+            };
+
+            let to = scanner.point()?;
+
+            if command == b'A' {
+                builder.arc_to(radii, x_rotation, flags, to);
+            } else {
+                builder.relative_arc_to(radii, x_rotation, flags, to.to_vector());
+            }
+        }
+
+        _ => return None,
+    }
+
+    Some(())
+}
+
+// ----------------------------------------------------------------------------
+// XML
+// ----------------------------------------------------------------------------
+
+#[derive(Debug)]
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<Element>,
+}
+
+impl Element {
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn href(&self) -> Option<&str> {
+        self.attr("href")
+    }
+}
+
+/// The namespace used by `xlink:href` attributes.
+const XLINK_NAMESPACE: &str = "http://www.w3.org/1999/xlink";
+
+/// Parses an XML document, keeping track of only the elements and their attributes.
+fn parse_document(data: &str) -> Result<Element> {
+    let options = ParsingOptions {
+        allow_dtd: true,
+        ..ParsingOptions::default()
+    };
+
+    let document = Document::parse_with_options(data, options)
+        .map_err(|e| TetraError::InvalidSvg(e.to_string()))?;
+
+    convert_element(document.root_element(), 0)
+}
+
+fn convert_element(node: Node<'_, '_>, depth: usize) -> Result<Element> {
+    if depth > MAX_ELEMENT_DEPTH {
+        return Err(TetraError::InvalidSvg(format!(
+            "elements are nested more than {} levels deep",
+            MAX_ELEMENT_DEPTH
+        )));
+    }
+
+    // Namespace prefixes are stripped from the names, so that e.g. `svg:path` and
+    // `xlink:href` are treated the same as `path` and `href`.
+    let attributes = node
+        .attributes()
+        .filter(|a| matches!(a.namespace(), None | Some(XLINK_NAMESPACE)))
+        .map(|a| (a.name().to_owned(), a.value().to_owned()))
+        .collect();
+
+    let children = node
+        .children()
+        .filter(Node::is_element)
+        .map(|child| convert_element(child, depth + 1))
+        .collect::<Result<_>>()?;
+
+    Ok(Element {
+        name: node.tag_name().name().to_owned(),
+        attributes,
+        children,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_xml() {
+        let root = parse_document(
+            r##"<?xml version="1.0"?>
+            <!DOCTYPE svg [ <!ENTITY foo "bar"> ]>
+            <!-- comment -->
+            <svg:svg xmlns:svg="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" width='10'>
+                <svg:g id="a &amp; b">text<svg:path d="M0 0" xlink:href="#foo"/><![CDATA[ <ignored> ]]></svg:g>
+            </svg:svg>"##,
+        )
+        .unwrap();
+
+        assert_eq!(root.name, "svg");
+        assert_eq!(root.attr("width"), Some("10"));
+        assert_eq!(root.children.len(), 1);
+        assert_eq!(root.children[0].attr("id"), Some("a & b"));
+        assert_eq!(root.children[0].children[0].name, "path");
+        assert_eq!(root.children[0].children[0].href(), Some("#foo"));
+    }
+
+    #[test]
+    fn parse_invalid_xml() {
+        assert!(parse_document("<svg><g></svg>").is_err());
+        assert!(parse_document("<svg width=10/>").is_err());
+        assert!(parse_document("<svg>").is_err());
+    }
+
+    #[test]
+    fn parse_deeply_nested_xml() {
+        let nested = |depth| format!("<svg>{}{}</svg>", "<g>".repeat(depth), "</g>".repeat(depth));
+
+        assert!(parse_document(&nested(MAX_ELEMENT_DEPTH)).is_ok());
+
+        assert!(matches!(
+            parse_document(&nested(MAX_ELEMENT_DEPTH + 1)),
+            Err(TetraError::InvalidSvg(_))
+        ));
+    }
+
+    #[test]
+    fn parse_compact_numbers() {
+        assert_eq!(
+            parse_numbers("1-2.5.5,3e2 -1E-1"),
+            vec![1.0, -2.5, 0.5, 300.0, -0.1]
+        );
+    }
+
+    #[test]
+    fn parse_colors() {
+        assert_eq!(parse_color("#f00"), Some(Color::RED));
+        assert_eq!(parse_color("#0000ff"), Some(Color::BLUE));
+        assert_eq!(parse_color("rgb(255, 255, 255)"), Some(Color::WHITE));
+        assert_eq!(parse_color("rgb(100%,0%,0%)"), Some(Color::RED));
+        assert_eq!(parse_color("White"), Some(Color::WHITE));
+        assert_eq!(parse_color("nonsense"), None);
+    }
+
+    #[test]
+    fn parse_transforms() {
+        let transform = parse_transform("translate(10, 20) scale(2)");
+        assert_eq!(transform.apply(Vec2::new(1.0, 1.0)), Vec2::new(12.0, 22.0));
+
+        let transform = parse_transform("rotate(90 10 10)");
+        let rotated = transform.apply(Vec2::new(20.0, 10.0));
+        assert!((rotated - Vec2::new(10.0, 20.0)).magnitude() < 0.001);
+    }
+
+    #[test]
+    fn parse_path_commands() {
+        let path =
+            parse_path_data("M10 10h10v10H10z m5 5 l1 1 a1 1 0 011 1 Q 1 1 2 2 T 3 3 oops 1 1");
+        assert_eq!(
+            path.iter()
+                .filter(|e| matches!(e, lyon_tessellation::path::PathEvent::Begin { .. }))
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn load_document() {
+        let mut builder = GeometryBuilder::new();
+
+        add_svg(
+            &mut builder,
+            r##"<svg viewBox="0 0 20 20" width="40" height="40" xmlns:xlink="http://www.w3.org/1999/xlink">
+                <defs>
+                    <linearGradient id="base"><stop offset="0" stop-color="red"/><stop offset="1" stop-color="blue"/></linearGradient>
+                    <linearGradient id="ref" xlink:href="#base"/>
+                </defs>
+                <g transform="translate(5 5)">
+                    <rect width="10" height="10" fill="url(#ref)" stroke="#000" stroke-width="1"/>
+                </g>
+                <circle cx="10" cy="10" r="2" style="fill: none"/>
+            </svg>"##,
+        )
+        .unwrap();
+
+        assert!(!builder.vertices().is_empty());
+
+        // The viewBox is scaled up by 2x, after the group's translation.
+        for vertex in builder.vertices() {
+            assert!(vertex.position.x >= 9.0 && vertex.position.x <= 31.0);
+            assert!(vertex.position.y >= 9.0 && vertex.position.y <= 31.0);
+        }
+
+        assert!(builder.vertices().iter().any(|v| v.color == Color::RED));
+        assert!(builder.vertices().iter().any(|v| v.color == Color::BLUE));
+    }
+
+    #[test]
+    fn mirrored_shapes_keep_winding() {
+        let winding = |builder: &GeometryBuilder| {
+            let v = builder.vertices();
+
+            builder
+                .indices()
+                .chunks_exact(3)
+                .map(|t| {
+                    let (a, b, c) = (
+                        v[t[0] as usize].position,
+                        v[t[1] as usize].position,
+                        v[t[2] as usize].position,
+                    );
+
+                    (b - a).x * (c - a).y - (b - a).y * (c - a).x
+                })
+                .filter(|area| area.abs() > f32::EPSILON)
+                .map(f32::signum)
+                .collect::<Vec<_>>()
+        };
+
+        let mut plain = GeometryBuilder::new();
+        add_svg(&mut plain, r#"<svg><rect width="10" height="10"/></svg>"#).unwrap();
+
+        let mut mirrored = GeometryBuilder::new();
+        add_svg(
+            &mut mirrored,
+            r#"<svg><rect width="10" height="10" transform="scale(-1 1)"/></svg>"#,
+        )
+        .unwrap();
+
+        let expected = winding(&plain)[0];
+
+        assert!(!winding(&mirrored).is_empty());
+        assert!(winding(&mirrored).iter().all(|&w| w == expected));
+    }
+
+    #[test]
+    fn load_invalid_document() {
+        let mut builder = GeometryBuilder::new();
+
+        assert!(add_svg(&mut builder, "<html></html>").is_err());
+        assert!(add_svg(&mut builder, "not xml").is_err());
+    }
+}
@@ -0,0 +1,347 @@
+//! Functions and types relating to palette swapping.
+//!
+//! Palette swapping works by drawing textures that contain palette indices rather than
+//! colors, using a shader that looks up the final color of each pixel in a [`Palette`].
+//! Switching between palettes is then as cheap as changing a uniform, which makes it
+//! easy to implement team colors, damage flashes and other retro-style effects.
+//!
+//! Index data is read from the red channel of the texture, with each value from `0` to
+//! `255` corresponding to an entry in the palette. [`TextureFormat::R8`] textures are the
+//! most efficient way of storing this data, and indexed PNG files can be loaded into that
+//! format via [`IndexedImage`].
+
+use crate::error::Result;
+use crate::graphics::{self, Color, FlushReason, Shader, Texture, TextureFormat};
+use crate::Context;
+
+#[cfg(feature = "texture_png")]
+pub use indexed::IndexedImage;
+
+/// The fragment shader used for palette swapping.
+///
+/// This can be combined with your own vertex shader via [`Shader::builder`], if you need
+/// to draw index data with custom geometry. The palette texture should be assigned to the
+/// `u_palette` sampler.
+///
+/// The source code for this shader is available in [`src/resources/palette.frag`](https://github.com/17cupsofcoffee/tetra/blob/main/src/resources/palette.frag).
+pub const PALETTE_FRAGMENT_SHADER: &str = include_str!("../resources/palette.frag");
+
+/// A list of colors, stored in GPU memory.
+///
+/// Palettes are stored as textures that are one pixel high, with each pixel holding one
+/// entry. They can be created from a list of [`Color`]s, or from an existing texture (e.g.
+/// one that was authored in an image editor).
+///
+/// # Performance
+///
+/// Creating a palette uploads a (small) texture to the GPU, so you should create them ahead
+/// of time rather than every frame. Cloning a palette is cheap, as it is a reference-counted
+/// handle to the texture.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Palette {
+    texture: Texture,
+}
+
+impl Palette {
+    /// Creates a new palette from a list of colors.
+    ///
+    /// Only the first 256 colors can be referenced by index data.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned
+    /// if the underlying graphics API encounters an error.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `colors` is empty.
+    pub fn new(ctx: &mut Context, colors: &[Color]) -> Result<Palette> {
+        assert!(
+            !colors.is_empty(),
+            "palette must contain at least one color"
+        );
+
+        let texture = Texture::with_device(
+            &mut ctx.device,
+            colors.len() as i32,
+            1,
+            &to_bytes(colors),
+            TextureFormat::Rgba8,
+            graphics::FilterMode::Nearest,
+        )?;
+
+        Ok(Palette { texture })
+    }
+
+    /// Creates a palette from an existing texture.
+    ///
+    /// Each pixel in the first row of the texture will be treated as an entry in the palette.
+    pub fn from_texture(texture: Texture) -> Palette {
+        Palette { texture }
+    }
+
+    /// Overwrites the colors in the palette, starting from the specified index.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned
+    /// if the underlying graphics API encounters an error.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the colors would be written outside of the palette.
+    pub fn set_colors(&self, ctx: &mut Context, offset: usize, colors: &[Color]) -> Result {
+        self.texture.set_data(
+            ctx,
+            offset as i32,
+            0,
+            colors.len() as i32,
+            1,
+            &to_bytes(colors),
+        )
+    }
+
+    /// Returns the number of colors in the palette.
+    pub fn len(&self) -> usize {
+        self.texture.width() as usize
+    }
+
+    /// Returns `true` if the palette has no colors.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns a reference to the texture that stores the palette.
+    pub fn texture(&self) -> &Texture {
+        &self.texture
+    }
+}
+
+/// Sets the renderer to draw index data using the specified palette.
+///
+/// This activates Tetra's built-in palette shader, so future drawing operations will
+/// look up the color of each pixel in the palette, based on the red channel of the
+/// texture. The palette can be changed between draw calls, which will trigger a
+/// [`flush`](graphics::flush).
+///
+/// Call [`reset_palette`] (or [`graphics::reset_shader`]) to go back to drawing
+/// normally.
+///
+/// # Errors
+///
+/// * [`TetraError::InvalidShader`](crate::TetraError::InvalidShader) will be returned
+/// if the palette shader could not be compiled. This will only happen the first time
+/// that the shader is used.
+pub fn set_palette(ctx: &mut Context, palette: &Palette) -> Result {
+    let shader = match &ctx.graphics.palette_shader {
+        Some(shader) => shader.clone(),
+        None => {
+            let shader = Shader::from_fragment_string(ctx, PALETTE_FRAGMENT_SHADER)?;
+            ctx.graphics.palette_shader = Some(shader.clone());
+            shader
+        }
+    };
+
+    // Uniforms don't trigger a flush by themselves, so anything drawn with the
+    // previous palette has to be sent to the GPU first.
+    if ctx.graphics.shader.as_ref() == Some(&shader) {
+        graphics::flush_with_reason(ctx, FlushReason::ShaderChanged);
+    }
+
+    shader.set_uniform(ctx, "u_palette", palette.texture.clone());
+    graphics::set_shader(ctx, &shader);
+
+    Ok(())
+}
+
+/// Sets the renderer back to drawing normally, after calling [`set_palette`].
+///
+/// This is equivalent to calling [`graphics::reset_shader`].
+pub fn reset_palette(ctx: &mut Context) {
+    graphics::reset_shader(ctx);
+}
+
+fn to_bytes(colors: &[Color]) -> Vec<u8> {
+    colors
+        .iter()
+        .flat_map(|&color| <[u8; 4]>::from(color))
+        .collect()
+}
+
+#[cfg(feature = "texture_png")]
+mod indexed {
+    use std::io::Cursor;
+    use std::path::Path;
+
+    use image::error::{DecodingError, ImageFormatHint};
+    use image::{ImageError, ImageFormat};
+    use png::{BitDepth, ColorType, Transformations};
+
+    use crate::error::{Result, TetraError};
+    use crate::fs;
+    use crate::graphics::{Color, ImageData, TextureFormat};
+    use crate::Context;
+
+    use super::Palette;
+
+    /// An image made up of palette indices, along with the palette that it was saved with.
+    ///
+    /// This type is only available when the `texture_png` feature is enabled.
+    #[derive(Debug, Clone)]
+    pub struct IndexedImage {
+        indices: ImageData,
+        palette: Vec<Color>,
+    }
+
+    impl IndexedImage {
+        /// Loads an indexed PNG from the given file.
+        ///
+        /// # Errors
+        ///
+        /// * [`TetraError::FailedToLoadAsset`] will be returned if the file could not be loaded.
+        /// * [`TetraError::InvalidTexture`] will be returned if the image data was invalid, or
+        /// if the PNG does not use indexed color.
+        pub fn new<P>(path: P) -> Result<IndexedImage>
+        where
+            P: AsRef<Path>,
+        {
+            IndexedImage::from_encoded(&fs::read(path)?)
+        }
+
+        /// Decodes an indexed PNG from a slice of data.
+        ///
+        /// This is useful in combination with [`include_bytes`](std::include_bytes), as it
+        /// allows you to include your image data directly in the binary.
+        ///
+        /// # Errors
+        ///
+        /// * [`TetraError::InvalidTexture`] will be returned if the image data was invalid, or
+        /// if the PNG does not use indexed color.
+        pub fn from_encoded(data: &[u8]) -> Result<IndexedImage> {
+            let mut decoder = png::Decoder::new(Cursor::new(data));
+            decoder.set_transformations(Transformations::IDENTITY);
+
+            let mut reader = decoder.read_info().map_err(invalid)?;
+            let mut buffer = vec![0; reader.output_buffer_size()];
+            let frame = reader.next_frame(&mut buffer).map_err(invalid)?;
+
+            if frame.color_type != ColorType::Indexed {
+                return Err(invalid("image does not use indexed color"));
+            }
+
+            let info = reader.info();
+
+            let rgb = info.palette.as_deref().unwrap_or_default();
+            let alpha = info.trns.as_deref().unwrap_or_default();
+
+            let palette = rgb
+                .chunks_exact(3)
+                .enumerate()
+                .map(|(i, c)| Color::rgba8(c[0], c[1], c[2], alpha.get(i).copied().unwrap_or(255)))
+                .collect();
+
+            let width = frame.width as usize;
+            let height = frame.height as usize;
+
+            let bits = match frame.bit_depth {
+                BitDepth::One => 1,
+                BitDepth::Two => 2,
+                BitDepth::Four => 4,
+                BitDepth::Eight => 8,
+                BitDepth::Sixteen => return Err(invalid("invalid bit depth for indexed color")),
+            };
+
+            // Rows with a bit depth of less than 8 are packed, with the leftmost
+            // pixel in the most significant bits.
+            let mask = ((1u16 << bits) - 1) as u8;
+            let mut indices = Vec::with_capacity(width * height);
+
+            for row in buffer.chunks(frame.line_size).take(height) {
+                for x in 0..width {
+                    let bit = x * bits;
+                    let shift = 8 - bits - bit % 8;
+
+                    indices.push((row[bit / 8] >> shift) & mask);
+                }
+            }
+
+            Ok(IndexedImage {
+                indices: ImageData::from_data(
+                    width as i32,
+                    height as i32,
+                    TextureFormat::R8,
+                    indices,
+                )?,
+                palette,
+            })
+        }
+
+        /// Returns the index data, in [`TextureFormat::R8`] format.
+        pub fn indices(&self) -> &ImageData {
+            &self.indices
+        }
+
+        /// Returns the palette that was stored in the image.
+        pub fn palette(&self) -> &[Color] {
+            &self.palette
+        }
+
+        /// Consumes the image, returning the index data and the palette.
+        pub fn into_parts(self) -> (ImageData, Vec<Color>) {
+            (self.indices, self.palette)
+        }
+
+        /// Creates a new [`Palette`] from the colors stored in the image.
+        ///
+        /// # Errors
+        ///
+        /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned
+        /// if the underlying graphics API encounters an error.
+        ///
+        /// # Panics
+        ///
+        /// Panics if the image's palette is empty.
+        pub fn create_palette(&self, ctx: &mut Context) -> Result<Palette> {
+            Palette::new(ctx, &self.palette)
+        }
+    }
+
+    fn invalid<E>(error: E) -> TetraError
+    where
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        TetraError::InvalidTexture(ImageError::Decoding(DecodingError::new(
+            ImageFormatHint::Exact(ImageFormat::Png),
+            error,
+        )))
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn unpack_low_bit_depth() {
+            // A 3x1 image with 2 bits per pixel, using indices 1, 2 and 3.
+            let mut data = Vec::new();
+
+            {
+                let mut encoder = png::Encoder::new(&mut data, 3, 1);
+                encoder.set_color(ColorType::Indexed);
+                encoder.set_depth(BitDepth::Two);
+                encoder.set_palette(vec![0, 0, 0, 255, 0, 0, 0, 255, 0, 0, 0, 255]);
+                encoder.set_trns(vec![0]);
+
+                let mut writer = encoder.write_header().unwrap();
+                writer.write_image_data(&[0b01_10_11_00]).unwrap();
+            }
+
+            let image = IndexedImage::from_encoded(&data).unwrap();
+
+            assert_eq!(image.indices().as_bytes(), &[1, 2, 3]);
+            assert_eq!(image.palette().len(), 4);
+            assert_eq!(image.palette()[0].a, 0.0);
+            assert_eq!(image.palette()[1], Color::RED);
+        }
+    }
+}
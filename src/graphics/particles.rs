@@ -0,0 +1,634 @@
+//! Functions and types relating to particle effects.
+
+use std::f32::consts::TAU;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::graphics::texture::Texture;
+use crate::graphics::{self, Color, DrawParams, Rectangle};
+use crate::math::Vec2;
+use crate::time;
+use crate::Context;
+
+/// The area in which new particles will be spawned, relative to the emitter's position.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EmitterShape {
+    /// Particles will be spawned at the emitter's position.
+    Point,
+
+    /// Particles will be spawned at a random point along a line.
+    Line {
+        /// The start of the line.
+        from: Vec2<f32>,
+
+        /// The end of the line.
+        to: Vec2<f32>,
+    },
+
+    /// Particles will be spawned at a random point inside a rectangle, centered
+    /// on the emitter's position.
+    Rectangle {
+        /// The width of the rectangle.
+        width: f32,
+
+        /// The height of the rectangle.
+        height: f32,
+    },
+
+    /// Particles will be spawned at a random point inside a circle, centered
+    /// on the emitter's position.
+    Circle {
+        /// The radius of the circle.
+        radius: f32,
+    },
+}
+
+/// The settings that control how a [`ParticleEmitter`] spawns and updates its particles.
+///
+/// Settings that take a pair of values will pick a random value between the two for each
+/// particle. Settings that take a list of keyframes will interpolate between them over the
+/// lifetime of each particle, where `0.0` is the moment the particle spawns and `1.0` is
+/// the moment it dies.
+///
+/// You can either use this as a builder by calling [`EmitterConfig::new`] and then chaining
+/// methods, or construct it manually - whichever you find more pleasant to write.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmitterConfig {
+    /// The area in which particles will be spawned. Defaults to [`EmitterShape::Point`].
+    pub shape: EmitterShape,
+
+    /// The number of particles spawned per second while emitting. Defaults to `10.0`.
+    pub spawn_rate: f32,
+
+    /// The maximum number of particles that can be alive at once. Defaults to `1000`.
+    pub max_particles: usize,
+
+    /// How long each particle lives for, in seconds. Defaults to `(1.0, 1.0)`.
+    pub lifetime: (f32, f32),
+
+    /// The direction particles will travel in, in radians. Defaults to `0.0` (right).
+    pub direction: f32,
+
+    /// How far (in radians) each particle's direction can vary from
+    /// [`direction`](Self::direction), in either direction. Defaults to `PI` (all directions).
+    pub spread: f32,
+
+    /// The initial speed of each particle, in pixels per second. Defaults to `(50.0, 50.0)`.
+    pub speed: (f32, f32),
+
+    /// A constant acceleration applied to each particle (e.g. gravity), in pixels per second
+    /// squared. Defaults to `(0.0, 0.0)`.
+    pub acceleration: Vec2<f32>,
+
+    /// The fraction of each particle's velocity that is lost per second. Defaults to `0.0`.
+    pub drag: f32,
+
+    /// The initial rotation of each particle, in radians. Defaults to `(0.0, 0.0)`.
+    pub rotation: (f32, f32),
+
+    /// How fast each particle rotates, in radians per second. Defaults to `(0.0, 0.0)`.
+    pub angular_velocity: (f32, f32),
+
+    /// A random multiplier applied to each particle's scale. Defaults to `(1.0, 1.0)`.
+    pub scale_variance: (f32, f32),
+
+    /// The color of the particles over their lifetime. Defaults to white.
+    pub color: Vec<(f32, Color)>,
+
+    /// The scale of the particles over their lifetime. Defaults to `1.0`.
+    pub scale: Vec<(f32, f32)>,
+
+    /// The regions of the texture used to draw the particles. If this is empty (the default),
+    /// the whole texture will be used.
+    pub regions: Vec<Rectangle>,
+
+    /// If `true`, particles will cycle through [`regions`](Self::regions) over their lifetime,
+    /// like an animation. If `false` (the default), each particle will pick a random region.
+    pub animate_regions: bool,
+
+    /// If `true`, particles will move along with the emitter after they are spawned. If `false`
+    /// (the default), they will be left behind when the emitter moves.
+    pub local_space: bool,
+}
+
+impl EmitterConfig {
+    /// Creates a new set of emitter settings.
+    pub fn new() -> EmitterConfig {
+        EmitterConfig::default()
+    }
+
+    /// Sets the area in which particles will be spawned.
+    pub fn shape(mut self, shape: EmitterShape) -> EmitterConfig {
+        self.shape = shape;
+        self
+    }
+
+    /// Sets the number of particles spawned per second.
+    pub fn spawn_rate(mut self, spawn_rate: f32) -> EmitterConfig {
+        self.spawn_rate = spawn_rate;
+        self
+    }
+
+    /// Sets the maximum number of particles that can be alive at once.
+    pub fn max_particles(mut self, max_particles: usize) -> EmitterConfig {
+        self.max_particles = max_particles;
+        self
+    }
+
+    /// Sets how long each particle lives for, in seconds.
+    pub fn lifetime(mut self, min: f32, max: f32) -> EmitterConfig {
+        self.lifetime = (min, max);
+        self
+    }
+
+    /// Sets the direction particles will travel in, and how far it can vary.
+    pub fn direction(mut self, direction: f32, spread: f32) -> EmitterConfig {
+        self.direction = direction;
+        self.spread = spread;
+        self
+    }
+
+    /// Sets the initial speed of each particle.
+    pub fn speed(mut self, min: f32, max: f32) -> EmitterConfig {
+        self.speed = (min, max);
+        self
+    }
+
+    /// Sets the constant acceleration applied to each particle.
+    pub fn acceleration(mut self, acceleration: Vec2<f32>) -> EmitterConfig {
+        self.acceleration = acceleration;
+        self
+    }
+
+    /// Sets the fraction of each particle's velocity that is lost per second.
+    pub fn drag(mut self, drag: f32) -> EmitterConfig {
+        self.drag = drag;
+        self
+    }
+
+    /// Sets the initial rotation of each particle.
+    pub fn rotation(mut self, min: f32, max: f32) -> EmitterConfig {
+        self.rotation = (min, max);
+        self
+    }
+
+    /// Sets how fast each particle rotates.
+    pub fn angular_velocity(mut self, min: f32, max: f32) -> EmitterConfig {
+        self.angular_velocity = (min, max);
+        self
+    }
+
+    /// Sets the random multiplier applied to each particle's scale.
+    pub fn scale_variance(mut self, min: f32, max: f32) -> EmitterConfig {
+        self.scale_variance = (min, max);
+        self
+    }
+
+    /// Sets the color keyframes for the particles.
+    pub fn color(mut self, keyframes: &[(f32, Color)]) -> EmitterConfig {
+        self.color = keyframes.to_vec();
+        self
+    }
+
+    /// Sets the scale keyframes for the particles.
+    pub fn scale(mut self, keyframes: &[(f32, f32)]) -> EmitterConfig {
+        self.scale = keyframes.to_vec();
+        self
+    }
+
+    /// Sets the regions of the texture used to draw the particles.
+    pub fn regions(mut self, regions: Vec<Rectangle>, animate: bool) -> EmitterConfig {
+        self.regions = regions;
+        self.animate_regions = animate;
+        self
+    }
+
+    /// Sets whether particles will move along with the emitter.
+    pub fn local_space(mut self, local_space: bool) -> EmitterConfig {
+        self.local_space = local_space;
+        self
+    }
+}
+
+impl Default for EmitterConfig {
+    fn default() -> EmitterConfig {
+        EmitterConfig {
+            shape: EmitterShape::Point,
+            spawn_rate: 10.0,
+            max_particles: 1000,
+            lifetime: (1.0, 1.0),
+            direction: 0.0,
+            spread: std::f32::consts::PI,
+            speed: (50.0, 50.0),
+            acceleration: Vec2::zero(),
+            drag: 0.0,
+            rotation: (0.0, 0.0),
+            angular_velocity: (0.0, 0.0),
+            scale_variance: (1.0, 1.0),
+            color: Vec::new(),
+            scale: Vec::new(),
+            regions: Vec::new(),
+            animate_regions: false,
+            local_space: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Particle {
+    position: Vec2<f32>,
+    velocity: Vec2<f32>,
+    rotation: f32,
+    angular_velocity: f32,
+    scale: f32,
+    region: usize,
+    age: f32,
+    lifetime: f32,
+}
+
+/// A particle effect, spawning and simulating particles that are drawn using a texture.
+///
+/// Particles are simulated on the CPU, and drawn via the same batching renderer as
+/// regular sprites, so an emitter with thousands of particles will usually only
+/// require a single draw call.
+///
+/// Calling [`update`](Self::update) or [`update_by`](Self::update_by) will spawn new
+/// particles and advance the existing ones.
+#[derive(Debug, Clone)]
+pub struct ParticleEmitter {
+    texture: Texture,
+    config: EmitterConfig,
+
+    position: Vec2<f32>,
+    particles: Vec<Particle>,
+    spawn_timer: f32,
+    emitting: bool,
+    rng: Rng,
+}
+
+impl ParticleEmitter {
+    /// Creates a new particle emitter.
+    ///
+    /// The emitter will start emitting particles straight away - call [`stop`](Self::stop)
+    /// if you only want to trigger [bursts](Self::burst) manually.
+    pub fn new(texture: Texture, config: EmitterConfig) -> ParticleEmitter {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+
+        ParticleEmitter {
+            texture,
+            config,
+
+            position: Vec2::zero(),
+            particles: Vec::new(),
+            spawn_timer: 0.0,
+            emitting: true,
+            rng: Rng::new(seed),
+        }
+    }
+
+    /// Draws the particles to the screen (or to a canvas, if one is enabled).
+    ///
+    /// The draw params will be applied to the emitter as a whole - for example, setting
+    /// the position will offset all of the particles, and setting the color will tint
+    /// them.
+    pub fn draw<P>(&self, ctx: &mut Context, params: P)
+    where
+        P: Into<DrawParams>,
+    {
+        let params = params.into();
+        let (sin, cos) = params.rotation.sin_cos();
+
+        let full_texture = Rectangle::new(
+            0.0,
+            0.0,
+            self.texture.width() as f32,
+            self.texture.height() as f32,
+        );
+
+        for particle in &self.particles {
+            let life = particle.age / particle.lifetime;
+
+            let region = particle_region(&self.config, particle, life, full_texture);
+
+            let mut position = particle.position;
+
+            if self.config.local_space {
+                position += self.position;
+            }
+
+            let offset = (position - params.origin) * params.flipped_scale();
+            let scale = particle.scale * evaluate_scale(&self.config.scale, life);
+
+            self.texture.draw_region(
+                ctx,
+                region,
+                DrawParams::new()
+                    .position(
+                        params.position
+                            + Vec2::new(
+                                offset.x * cos - offset.y * sin,
+                                offset.x * sin + offset.y * cos,
+                            ),
+                    )
+                    .origin(Vec2::new(region.width / 2.0, region.height / 2.0))
+                    .scale(params.flipped_scale() * scale)
+                    .rotation(params.rotation + particle.rotation)
+                    .color(params.color * evaluate_color(&self.config.color, life)),
+            );
+        }
+    }
+
+    /// Spawns new particles and advances the existing ones.
+    ///
+    /// This method uses the current [delta time](crate::time::get_delta_time)
+    /// to calculate how much time has passed.
+    pub fn update(&mut self, ctx: &Context) {
+        self.update_by(time::get_delta_time(ctx));
+    }
+
+    /// Spawns new particles and advances the existing ones, by a specified amount of time.
+    pub fn update_by(&mut self, duration: Duration) {
+        let dt = duration.as_secs_f32();
+
+        let acceleration = self.config.acceleration * dt;
+        let drag = (1.0 - self.config.drag * dt).max(0.0);
+
+        self.particles.retain_mut(|particle| {
+            particle.age += dt;
+
+            particle.velocity = (particle.velocity + acceleration) * drag;
+            particle.position += particle.velocity * dt;
+            particle.rotation += particle.angular_velocity * dt;
+
+            particle.age < particle.lifetime
+        });
+
+        if self.emitting && self.config.spawn_rate > 0.0 {
+            self.spawn_timer += dt * self.config.spawn_rate;
+
+            let count = self.spawn_timer as usize;
+            self.spawn_timer -= count as f32;
+
+            self.burst(count);
+        }
+    }
+
+    /// Immediately spawns a number of particles, regardless of the spawn rate.
+    ///
+    /// Particles will not be spawned past the emitter's
+    /// [`max_particles`](EmitterConfig::max_particles) limit.
+    pub fn burst(&mut self, count: usize) {
+        let count = count.min(
+            self.config
+                .max_particles
+                .saturating_sub(self.particles.len()),
+        );
+
+        for _ in 0..count {
+            let particle = self.spawn();
+            self.particles.push(particle);
+        }
+    }
+
+    fn spawn(&mut self) -> Particle {
+        let config = &self.config;
+        let rng = &mut self.rng;
+
+        let offset = match config.shape {
+            EmitterShape::Point => Vec2::zero(),
+            EmitterShape::Line { from, to } => Vec2::lerp(from, to, rng.next_f32()),
+            EmitterShape::Rectangle { width, height } => Vec2::new(
+                (rng.next_f32() - 0.5) * width,
+                (rng.next_f32() - 0.5) * height,
+            ),
+            EmitterShape::Circle { radius } => {
+                // The square root keeps the distribution uniform across the circle's area.
+                let angle = rng.next_f32() * TAU;
+                let distance = rng.next_f32().sqrt() * radius;
+                Vec2::new(angle.cos(), angle.sin()) * distance
+            }
+        };
+
+        let direction = config.direction + rng.range(-config.spread, config.spread);
+        let speed = rng.range(config.speed.0, config.speed.1);
+
+        let position = if config.local_space {
+            offset
+        } else {
+            self.position + offset
+        };
+
+        Particle {
+            position,
+            velocity: Vec2::new(direction.cos(), direction.sin()) * speed,
+            rotation: rng.range(config.rotation.0, config.rotation.1),
+            angular_velocity: rng.range(config.angular_velocity.0, config.angular_velocity.1),
+            scale: rng.range(config.scale_variance.0, config.scale_variance.1),
+            region: (rng.next_f32() * config.regions.len() as f32) as usize
+                % config.regions.len().max(1),
+            age: 0.0,
+            lifetime: rng
+                .range(config.lifetime.0, config.lifetime.1)
+                .max(f32::EPSILON),
+        }
+    }
+
+    /// Starts spawning particles at the configured rate.
+    pub fn start(&mut self) {
+        self.emitting = true;
+    }
+
+    /// Stops spawning particles. Particles that are already alive will continue to be
+    /// updated until they die.
+    pub fn stop(&mut self) {
+        self.emitting = false;
+        self.spawn_timer = 0.0;
+    }
+
+    /// Returns `true` if the emitter is spawning particles at the configured rate.
+    pub fn is_emitting(&self) -> bool {
+        self.emitting
+    }
+
+    /// Removes all of the particles that are currently alive.
+    pub fn clear(&mut self) {
+        self.particles.clear();
+    }
+
+    /// Returns the number of particles that are currently alive.
+    pub fn particle_count(&self) -> usize {
+        self.particles.len()
+    }
+
+    /// Returns the position that particles are spawned from.
+    pub fn position(&self) -> Vec2<f32> {
+        self.position
+    }
+
+    /// Sets the position that particles are spawned from.
+    ///
+    /// Unless the emitter is in [local space](EmitterConfig::local_space), this will not
+    /// affect particles that have already been spawned.
+    pub fn set_position(&mut self, position: Vec2<f32>) {
+        self.position = position;
+    }
+
+    /// Returns a reference to the texture used to draw the particles.
+    pub fn texture(&self) -> &Texture {
+        &self.texture
+    }
+
+    /// Sets the texture used to draw the particles.
+    pub fn set_texture(&mut self, texture: Texture) {
+        self.texture = texture;
+    }
+
+    /// Returns a reference to the emitter's settings.
+    pub fn config(&self) -> &EmitterConfig {
+        &self.config
+    }
+
+    /// Returns a mutable reference to the emitter's settings.
+    ///
+    /// Changes will apply to existing particles as well as new ones, except for
+    /// settings that are only used when spawning (e.g. lifetime and speed).
+    pub fn config_mut(&mut self) -> &mut EmitterConfig {
+        &mut self.config
+    }
+
+    /// Reseeds the random number generator used by the emitter.
+    ///
+    /// By default, the emitter is seeded using the system time - setting the seed manually
+    /// can be useful if you need effects to play out the same way every time.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = Rng::new(seed);
+    }
+}
+
+/// Returns the region of the texture that a particle should be drawn with.
+fn particle_region(
+    config: &EmitterConfig,
+    particle: &Particle,
+    life: f32,
+    full_texture: Rectangle,
+) -> Rectangle {
+    let count = config.regions.len();
+
+    if count == 0 {
+        full_texture
+    } else if config.animate_regions {
+        config.regions[((life * count as f32) as usize).min(count - 1)]
+    } else {
+        // The regions may have been changed via `config_mut` since the particle was
+        // spawned, so the index has to be wrapped to stay in bounds.
+        config.regions[particle.region % count]
+    }
+}
+
+fn evaluate_color(keyframes: &[(f32, Color)], t: f32) -> Color {
+    graphics::interpolate_keyframes(keyframes, t, Color::WHITE, |&k| k, Color::lerp)
+}
+
+fn evaluate_scale(keyframes: &[(f32, f32)], t: f32) -> f32 {
+    graphics::interpolate_keyframes(keyframes, t, 1.0, |&k| k, |a, b, t| a + (b - a) * t)
+}
+
+/// A small, fast pseudo-random number generator (SplitMix64).
+///
+/// This doesn't need to be high quality, and avoids pulling in a dependency on `rand`.
+#[derive(Debug, Clone)]
+struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number in the range `[0.0, 1.0)`.
+    fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    fn range(&mut self, min: f32, max: f32) -> f32 {
+        min + (max - min) * self.next_f32()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keyframe_evaluation() {
+        let keyframes = [(0.0, 0.0), (0.5, 2.0), (1.0, 1.0)];
+
+        assert_eq!(evaluate_scale(&keyframes, -1.0), 0.0);
+        assert_eq!(evaluate_scale(&keyframes, 0.25), 1.0);
+        assert_eq!(evaluate_scale(&keyframes, 0.75), 1.5);
+        assert_eq!(evaluate_scale(&keyframes, 2.0), 1.0);
+        assert_eq!(evaluate_scale(&[], 0.5), 1.0);
+    }
+
+    #[test]
+    fn regions_shrunk_while_particles_alive() {
+        let regions = (0..4)
+            .map(|i| Rectangle::new(i as f32 * 8.0, 0.0, 8.0, 8.0))
+            .collect();
+
+        let mut config = EmitterConfig::new().regions(regions, false);
+        let full_texture = Rectangle::new(0.0, 0.0, 32.0, 8.0);
+
+        let particle = Particle {
+            position: Vec2::zero(),
+            velocity: Vec2::zero(),
+            rotation: 0.0,
+            angular_velocity: 0.0,
+            scale: 1.0,
+            region: 3,
+            age: 0.0,
+            lifetime: 1.0,
+        };
+
+        assert_eq!(
+            particle_region(&config, &particle, 0.0, full_texture),
+            config.regions[3]
+        );
+
+        config.regions.truncate(2);
+
+        assert_eq!(
+            particle_region(&config, &particle, 0.0, full_texture),
+            config.regions[1]
+        );
+
+        config.regions.clear();
+
+        assert_eq!(
+            particle_region(&config, &particle, 0.0, full_texture),
+            full_texture
+        );
+    }
+
+    #[test]
+    fn rng_range() {
+        let mut rng = Rng::new(1234);
+
+        for _ in 0..1000 {
+            let value = rng.range(-2.0, 3.0);
+            assert!((-2.0..3.0).contains(&value));
+        }
+    }
+}
@@ -1,14 +1,45 @@
 //! Functions and types relating to screen scaling.
 
+use std::cell::{Cell, RefCell};
+
 use crate::Context;
 use crate::error::Result;
-use crate::graphics::{self, Canvas, DrawParams, Rectangle};
+use crate::graphics::{self, Canvas, Color, DrawParams, FilterMode, Rectangle};
 use crate::input;
-use crate::math::Vec2;
+use crate::math::{Mat4, Vec2, Vec3};
 use crate::window;
 
 /// A wrapper for a [`Canvas`] that handles scaling the image to fit the screen.
 ///
+/// # Window Tracking
+///
+/// Scalers that are created via [`with_window_size`](Self::with_window_size) or
+/// [`transform_only`](Self::transform_only) will automatically keep their outer size in
+/// sync with the window, so there is no need to call [`set_outer_size`](Self::set_outer_size)
+/// when [`Event::Resized`](crate::Event::Resized) is fired. These scalers also take the
+/// window's DPI scale into account, so the pixel perfect scaling modes will scale by whole
+/// physical pixels on high-DPI displays.
+///
+/// # Letterboxing
+///
+/// By default, the areas of the screen that are not covered by the scaled image are left
+/// untouched. These areas can be filled with a solid color via
+/// [`set_letterbox_color`](Self::set_letterbox_color), or with custom rendering via
+/// [`draw_with_letterbox`](Self::draw_with_letterbox).
+///
+/// # Transform-Only Mode
+///
+/// A scaler can also be created without a canvas, via [`transform_only`](Self::transform_only).
+/// Rather than rendering to a low resolution canvas and scaling up the result, this applies
+/// the scaling to the [transform matrix](graphics::set_transform_matrix) and clips to the
+/// scaled area via the [scissor rectangle](graphics::set_scissor) - see
+/// [`apply_transform`](Self::apply_transform). This allows you to draw using the same
+/// co-ordinates as your game's screen, while still rendering at the full resolution of the
+/// window.
+///
+/// `apply_transform` can also be used with canvas-based scalers, which is useful for
+/// drawing high resolution UI on top of pixel art content.
+///
 /// # Examples
 ///
 /// The [`scaling`](https://github.com/17cupsofcoffee/tetra/blob/main/examples/scaling.rs)
@@ -16,13 +47,17 @@ use crate::window;
 /// scaling algorithms.
 #[derive(Debug)]
 pub struct ScreenScaler {
-    canvas: Canvas,
+    canvas: Option<Canvas>,
+    hybrid_canvas: RefCell<Option<Canvas>>,
     mode: ScalingMode,
-    screen_rect: Rectangle,
+    screen_rect: Cell<Rectangle>,
     inner_width: i32,
     inner_height: i32,
-    outer_width: i32,
-    outer_height: i32,
+    outer_width: Cell<i32>,
+    outer_height: Cell<i32>,
+    dpi_scale: Cell<f32>,
+    tracks_window: bool,
+    letterbox_color: Option<Color>,
 }
 
 impl ScreenScaler {
@@ -37,22 +72,23 @@ impl ScreenScaler {
         mode: ScalingMode,
     ) -> Result<ScreenScaler> {
         let canvas = Canvas::new(ctx, inner_width, inner_height)?;
-        let screen_rect =
-            get_screen_rect(mode, inner_width, inner_height, outer_width, outer_height);
 
-        Ok(ScreenScaler {
-            canvas,
-            mode,
-            screen_rect,
+        Ok(ScreenScaler::from_parts(
+            Some(canvas),
             inner_width,
             inner_height,
             outer_width,
             outer_height,
-        })
+            1.0,
+            false,
+            mode,
+        ))
     }
 
     /// Returns a new `ScreenScaler`, with the specified inner width and height, and the outer
     /// size set to the current dimensions of the window.
+    ///
+    /// The outer size will automatically be updated when the window is resized.
     pub fn with_window_size(
         ctx: &mut Context,
         inner_width: i32,
@@ -60,49 +96,296 @@ impl ScreenScaler {
         mode: ScalingMode,
     ) -> Result<ScreenScaler> {
         let (outer_width, outer_height) = window::get_size(ctx);
+        let canvas = Canvas::new(ctx, inner_width, inner_height)?;
 
-        ScreenScaler::new(
-            ctx,
+        Ok(ScreenScaler::from_parts(
+            Some(canvas),
+            inner_width,
+            inner_height,
+            outer_width,
+            outer_height,
+            window::get_dpi_scale(ctx),
+            true,
+            mode,
+        ))
+    }
+
+    /// Returns a new `ScreenScaler` that does not render via a canvas, with the specified
+    /// inner width and height, and the outer size set to the current dimensions of the window.
+    ///
+    /// The outer size will automatically be updated when the window is resized.
+    ///
+    /// Call [`apply_transform`](Self::apply_transform) before drawing to scale your content
+    /// to fit the screen. [`canvas`](Self::canvas) must not be called on scalers created via
+    /// this function.
+    pub fn transform_only(
+        ctx: &Context,
+        inner_width: i32,
+        inner_height: i32,
+        mode: ScalingMode,
+    ) -> ScreenScaler {
+        let (outer_width, outer_height) = window::get_size(ctx);
+
+        ScreenScaler::from_parts(
+            None,
             inner_width,
             inner_height,
             outer_width,
             outer_height,
+            window::get_dpi_scale(ctx),
+            true,
             mode,
         )
     }
 
+    #[allow(clippy::too_many_arguments)]
+    fn from_parts(
+        canvas: Option<Canvas>,
+        inner_width: i32,
+        inner_height: i32,
+        outer_width: i32,
+        outer_height: i32,
+        dpi_scale: f32,
+        tracks_window: bool,
+        mode: ScalingMode,
+    ) -> ScreenScaler {
+        let scaler = ScreenScaler {
+            canvas,
+            hybrid_canvas: RefCell::new(None),
+            mode,
+            screen_rect: Cell::new(Rectangle::default()),
+            inner_width,
+            inner_height,
+            outer_width: Cell::new(outer_width),
+            outer_height: Cell::new(outer_height),
+            dpi_scale: Cell::new(dpi_scale),
+            tracks_window,
+            letterbox_color: None,
+        };
+
+        scaler.update_screen_rect();
+        scaler
+    }
+
     /// Draws the scaled image to the screen.
+    ///
+    /// If a [letterbox color](Self::set_letterbox_color) has been set, the areas outside
+    /// of the scaled image will be filled in first. If the scaler was created via
+    /// [`transform_only`](Self::transform_only), only the letterboxing will be drawn.
     pub fn draw(&self, ctx: &mut Context) {
-        graphics::set_texture(ctx, &self.canvas.texture);
+        self.sync_with_window(ctx);
 
-        graphics::push_quad(
+        if let Some(color) = self.letterbox_color {
+            let texture = ctx.graphics.default_texture.clone();
+            let params = DrawParams::new().color(color);
+
+            for rect in self.letterbox_rects() {
+                graphics::set_texture(ctx, &texture);
+                push_rect(ctx, rect, &params);
+            }
+        }
+
+        self.draw_screen(ctx);
+    }
+
+    /// Draws the scaled image to the screen, calling the provided function to draw
+    /// each of the letterbox areas first.
+    ///
+    /// The rectangles passed to the function are in window co-ordinates. This can be
+    /// used to fill the letterbox areas with something other than a solid color, such as
+    /// a blurred copy of the screen or a decorative border.
+    pub fn draw_with_letterbox<F>(&self, ctx: &mut Context, mut draw_letterbox: F)
+    where
+        F: FnMut(&mut Context, Rectangle),
+    {
+        self.sync_with_window(ctx);
+
+        for rect in self.letterbox_rects() {
+            draw_letterbox(ctx, rect);
+        }
+
+        self.draw_screen(ctx);
+    }
+
+    fn draw_screen(&self, ctx: &mut Context) {
+        let canvas = match &self.canvas {
+            Some(canvas) => canvas,
+            None => return,
+        };
+
+        let screen_rect = self.screen_rect.get();
+
+        let texture = match self.mode {
+            ScalingMode::ShowAllHybrid => self.prescale(ctx, canvas),
+            _ => None,
+        }
+        .unwrap_or_else(|| canvas.texture.clone());
+
+        graphics::set_texture(ctx, &texture);
+        push_rect(ctx, screen_rect, &DrawParams::new());
+    }
+
+    /// Scales up the canvas by a whole number using nearest neighbour filtering, so that
+    /// the result can be scaled to the final size with linear filtering.
+    fn prescale(&self, ctx: &mut Context, canvas: &Canvas) -> Option<graphics::Texture> {
+        let screen_rect = self.screen_rect.get();
+
+        let factor = f32::min(
+            screen_rect.width / self.inner_width as f32,
+            screen_rect.height / self.inner_height as f32,
+        )
+        .ceil()
+        .max(1.0) as i32;
+
+        let width = self.inner_width * factor;
+        let height = self.inner_height * factor;
+
+        let mut hybrid_canvas = self.hybrid_canvas.borrow_mut();
+
+        if hybrid_canvas.as_ref().map(Canvas::size) != Some((width, height)) {
+            // If the canvas can't be created, the caller will fall back to drawing the
+            // screen directly, which is better than drawing nothing at all.
+            *hybrid_canvas = Canvas::new(ctx, width, height).ok().map(|mut c| {
+                c.set_filter_mode(ctx, FilterMode::Linear);
+                c
+            });
+        }
+
+        let target = hybrid_canvas.clone()?;
+
+        let previous_canvas = ctx.graphics.canvas.clone();
+        let previous_transform = graphics::get_transform_matrix(ctx);
+
+        graphics::set_canvas(ctx, &target);
+        graphics::reset_transform_matrix(ctx);
+        graphics::clear(ctx, Color::rgba(0.0, 0.0, 0.0, 0.0));
+
+        graphics::set_texture(ctx, &canvas.texture);
+        push_rect(
             ctx,
-            self.screen_rect.x,
-            self.screen_rect.y,
-            self.screen_rect.x + self.screen_rect.width,
-            self.screen_rect.y + self.screen_rect.height,
-            0.0,
-            0.0,
-            1.0,
-            1.0,
+            Rectangle::new(0.0, 0.0, width as f32, height as f32),
             &DrawParams::new(),
         );
+
+        graphics::set_canvas_ex(ctx, previous_canvas.as_ref());
+        graphics::set_transform_matrix(ctx, previous_transform);
+
+        Some(target.texture)
+    }
+
+    /// Returns the areas of the outer box that are not covered by the scaled image.
+    ///
+    /// The rectangles are in window co-ordinates. If the scaled image covers the entire
+    /// box, no rectangles will be returned.
+    pub fn letterbox_rects(&self) -> Vec<Rectangle> {
+        let outer_width = self.outer_width.get() as f32;
+        let outer_height = self.outer_height.get() as f32;
+
+        let screen_rect = self.screen_rect.get();
+
+        let top = screen_rect.y.clamp(0.0, outer_height);
+        let bottom = screen_rect.bottom().clamp(0.0, outer_height);
+        let left = screen_rect.x.clamp(0.0, outer_width);
+        let right = screen_rect.right().clamp(0.0, outer_width);
+
+        [
+            Rectangle::new(0.0, 0.0, outer_width, top),
+            Rectangle::new(0.0, bottom, outer_width, outer_height - bottom),
+            Rectangle::new(0.0, top, left, bottom - top),
+            Rectangle::new(right, top, outer_width - right, bottom - top),
+        ]
+        .into_iter()
+        .filter(|r| r.width > 0.0 && r.height > 0.0)
+        .collect()
+    }
+
+    /// Returns the color that will be used to fill the letterbox areas, if any.
+    pub fn letterbox_color(&self) -> Option<Color> {
+        self.letterbox_color
+    }
+
+    /// Sets the color that will be used to fill the letterbox areas when calling
+    /// [`draw`](Self::draw).
+    ///
+    /// If this is set to [`None`] (the default), the letterbox areas will not be drawn to.
+    pub fn set_letterbox_color(&mut self, color: Option<Color>) {
+        self.letterbox_color = color;
+    }
+
+    /// Returns the transformation matrix that maps the scaler's inner co-ordinates to
+    /// window co-ordinates.
+    pub fn as_matrix(&self) -> Mat4<f32> {
+        let screen_rect = self.screen_rect.get();
+
+        let mut matrix = Mat4::scaling_3d(Vec3::new(
+            screen_rect.width / self.inner_width as f32,
+            screen_rect.height / self.inner_height as f32,
+            1.0,
+        ));
+
+        matrix.translate_2d(Vec2::new(screen_rect.x, screen_rect.y));
+        matrix
+    }
+
+    /// Sets the renderer to draw using the scaler's inner co-ordinates, without rendering
+    /// via a canvas.
+    ///
+    /// This sets the [transform matrix](graphics::set_transform_matrix) to the scaler's
+    /// [matrix](Self::as_matrix), and the [scissor rectangle](graphics::set_scissor) to the
+    /// area covered by the scaled image. Call [`reset_transform`](Self::reset_transform) to
+    /// go back to drawing normally.
+    ///
+    /// This should be called while rendering to the window (i.e. not while a canvas is
+    /// active).
+    pub fn apply_transform(&self, ctx: &mut Context) {
+        self.sync_with_window(ctx);
+
+        let screen_rect = self.screen_rect.get();
+
+        let left = screen_rect.x.max(0.0).floor();
+        let top = screen_rect.y.max(0.0).floor();
+        let right = screen_rect
+            .right()
+            .min(self.outer_width.get() as f32)
+            .ceil();
+        let bottom = screen_rect
+            .bottom()
+            .min(self.outer_height.get() as f32)
+            .ceil();
+
+        graphics::set_logical_scissor(
+            ctx,
+            Rectangle::new(
+                left as i32,
+                top as i32,
+                (right - left).max(0.0) as i32,
+                (bottom - top).max(0.0) as i32,
+            ),
+        );
+
+        graphics::set_transform_matrix(ctx, self.as_matrix());
+    }
+
+    /// Sets the renderer back to drawing normally, after calling
+    /// [`apply_transform`](Self::apply_transform).
+    ///
+    /// This disables the scissor rectangle and resets the transform matrix.
+    pub fn reset_transform(&self, ctx: &mut Context) {
+        graphics::reset_scissor(ctx);
+        graphics::reset_transform_matrix(ctx);
     }
 
     /// Updates the scaler's outer size (i.e. the size of the box that the screen will be scaled to
     /// fit within).
+    ///
+    /// If the scaler is [tracking the window size](Self::set_tracks_window), the outer size will
+    /// be overwritten the next time that the window's size changes.
     pub fn set_outer_size(&mut self, outer_width: i32, outer_height: i32) {
-        if outer_width != self.outer_width || outer_height != self.outer_height {
-            self.outer_width = outer_width;
-            self.outer_height = outer_height;
-
-            self.screen_rect = get_screen_rect(
-                self.mode,
-                self.canvas().width(),
-                self.canvas().height(),
-                outer_width,
-                outer_height,
-            );
+        if outer_width != self.outer_width.get() || outer_height != self.outer_height.get() {
+            self.outer_width.set(outer_width);
+            self.outer_height.set(outer_height);
+
+            self.update_screen_rect();
         }
     }
 
@@ -110,7 +393,7 @@ impl ScreenScaler {
     /// fit within).  
     /// The format is (width, height).
     pub fn outer_size(&self) -> (i32, i32) {
-        (self.outer_width, self.outer_height)
+        (self.outer_width.get(), self.outer_height.get())
     }
 
     /// Returns the scaler's inner size (i.e. the logical screen size).  
@@ -119,19 +402,50 @@ impl ScreenScaler {
         (self.inner_width, self.inner_height)
     }
 
+    /// Returns whether the scaler's outer size is automatically kept in sync with the window.
+    pub fn tracks_window(&self) -> bool {
+        self.tracks_window
+    }
+
+    /// Sets whether the scaler's outer size should automatically be kept in sync with the window.
+    ///
+    /// While this is enabled, the scaler will check the window's size (and DPI scale) whenever
+    /// it is drawn, or when the mouse position is requested.
+    pub fn set_tracks_window(&mut self, tracks_window: bool) {
+        self.tracks_window = tracks_window;
+
+        if !tracks_window {
+            self.dpi_scale.set(1.0);
+            self.update_screen_rect();
+        }
+    }
+
     /// Returns the optimal scale factor for the current `ScalingMode` and configured sizes.  
     /// This can be used for simple use cases where scaling by [canvas](Self::canvas) is not
     /// feasible (e.g. 3rd party UI libraries).
     pub fn scale_factor(&self) -> f32 {
+        let screen_rect = self.screen_rect.get();
+
         f32::min(
-            self.screen_rect.width as f32 / self.inner_width as f32,
-            self.screen_rect.height as f32 / self.inner_height as f32,
+            screen_rect.width / self.inner_width as f32,
+            screen_rect.height / self.inner_height as f32,
         )
     }
 
+    /// Returns the area of the window that the scaled image covers.
+    pub fn screen_rect(&self) -> Rectangle {
+        self.screen_rect.get()
+    }
+
     /// Returns a reference to the canvas that is being scaled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the scaler was created via [`transform_only`](Self::transform_only).
     pub fn canvas(&self) -> &Canvas {
-        &self.canvas
+        self.canvas
+            .as_ref()
+            .expect("scaler was created without a canvas")
     }
 
     /// Returns the current scaling mode.
@@ -142,51 +456,45 @@ impl ScreenScaler {
     /// Sets the scaling mode that should be used.
     pub fn set_mode(&mut self, mode: ScalingMode) {
         self.mode = mode;
-        self.screen_rect = get_screen_rect(
-            self.mode,
-            self.canvas().width(),
-            self.canvas().height(),
-            self.outer_width,
-            self.outer_height,
-        );
+        self.update_screen_rect();
     }
 
     /// Converts a point from window co-ordinates to scaled screen co-ordinates.
     pub fn project(&self, position: Vec2<f32>) -> Vec2<f32> {
-        let (width, height) = self.canvas().size();
+        let screen_rect = self.screen_rect.get();
 
         Vec2::new(
             project_impl(
                 position.x,
-                self.screen_rect.x,
-                self.screen_rect.width,
-                width as f32,
+                screen_rect.x,
+                screen_rect.width,
+                self.inner_width as f32,
             ),
             project_impl(
                 position.y,
-                self.screen_rect.y,
-                self.screen_rect.height,
-                height as f32,
+                screen_rect.y,
+                screen_rect.height,
+                self.inner_height as f32,
             ),
         )
     }
 
     /// Converts a point from scaled screen co-ordinates to window co-ordinates.
     pub fn unproject(&self, position: Vec2<f32>) -> Vec2<f32> {
-        let (width, height) = self.canvas().size();
+        let screen_rect = self.screen_rect.get();
 
         Vec2::new(
             unproject_impl(
                 position.x,
-                self.screen_rect.x,
-                self.screen_rect.width,
-                width as f32,
+                screen_rect.x,
+                screen_rect.width,
+                self.inner_width as f32,
             ),
             unproject_impl(
                 position.y,
-                self.screen_rect.y,
-                self.screen_rect.height,
-                height as f32,
+                screen_rect.y,
+                screen_rect.height,
+                self.inner_height as f32,
             ),
         )
     }
@@ -195,6 +503,7 @@ impl ScreenScaler {
     ///
     /// This is a shortcut for calling [`.project(input::get_mouse_position(ctx))`](Self::project).
     pub fn mouse_position(&self, ctx: &Context) -> Vec2<f32> {
+        self.sync_with_window(ctx);
         self.project(input::get_mouse_position(ctx))
     }
 
@@ -202,13 +511,15 @@ impl ScreenScaler {
     ///
     /// This is a shortcut for calling [`project(input::get_mouse_position(ctx)).x`](Self::project).
     pub fn mouse_x(&self, ctx: &Context) -> f32 {
-        let width = self.canvas().width();
+        self.sync_with_window(ctx);
+
+        let screen_rect = self.screen_rect.get();
 
         project_impl(
             input::get_mouse_x(ctx),
-            self.screen_rect.x,
-            self.screen_rect.width,
-            width as f32,
+            screen_rect.x,
+            screen_rect.width,
+            self.inner_width as f32,
         )
     }
 
@@ -216,15 +527,73 @@ impl ScreenScaler {
     ///
     /// This is a shortcut for calling [`project(input::get_mouse_position(ctx)).y`](Self::project).
     pub fn mouse_y(&self, ctx: &Context) -> f32 {
-        let height = self.canvas().height();
+        self.sync_with_window(ctx);
+
+        let screen_rect = self.screen_rect.get();
 
         project_impl(
             input::get_mouse_y(ctx),
-            self.screen_rect.y,
-            self.screen_rect.height,
-            height as f32,
+            screen_rect.y,
+            screen_rect.height,
+            self.inner_height as f32,
         )
     }
+
+    fn sync_with_window(&self, ctx: &Context) {
+        if !self.tracks_window {
+            return;
+        }
+
+        let (outer_width, outer_height) = window::get_size(ctx);
+        let dpi_scale = window::get_dpi_scale(ctx);
+
+        if outer_width != self.outer_width.get()
+            || outer_height != self.outer_height.get()
+            || dpi_scale != self.dpi_scale.get()
+        {
+            self.outer_width.set(outer_width);
+            self.outer_height.set(outer_height);
+            self.dpi_scale.set(dpi_scale);
+
+            self.update_screen_rect();
+        }
+    }
+
+    fn update_screen_rect(&self) {
+        let dpi_scale = self.dpi_scale.get();
+
+        // The rectangle is calculated in physical pixels, so that the pixel perfect
+        // modes line up with the display on high-DPI screens.
+        let physical = get_screen_rect(
+            self.mode,
+            self.inner_width,
+            self.inner_height,
+            (self.outer_width.get() as f32 * dpi_scale).round() as i32,
+            (self.outer_height.get() as f32 * dpi_scale).round() as i32,
+        );
+
+        self.screen_rect.set(Rectangle::new(
+            physical.x / dpi_scale,
+            physical.y / dpi_scale,
+            physical.width / dpi_scale,
+            physical.height / dpi_scale,
+        ));
+    }
+}
+
+fn push_rect(ctx: &mut Context, rect: Rectangle, params: &DrawParams) {
+    graphics::push_quad(
+        ctx,
+        rect.x,
+        rect.y,
+        rect.x + rect.width,
+        rect.y + rect.height,
+        0.0,
+        0.0,
+        1.0,
+        1.0,
+        params,
+    );
 }
 
 fn project_impl(window_pos: f32, rect_pos: f32, rect_size: f32, real_size: f32) -> f32 {
@@ -255,6 +624,16 @@ pub enum ScalingMode {
     /// Works the same as ShowAll, but will only scale by integer values.
     ShowAllPixelPerfect,
 
+    /// Works the same as ShowAll, but the screen is first scaled up by an integer value
+    /// using nearest neighbour filtering, and then scaled to the final size using linear
+    /// filtering.
+    ///
+    /// This keeps pixel art looking sharp while filling as much of the window as possible,
+    /// without the uneven pixel sizes that fractional nearest neighbour scaling causes. This
+    /// requires an extra canvas, which will be created the first time the scaler is drawn.
+    /// When used without a canvas, this mode works the same as ShowAll.
+    ShowAllHybrid,
+
     /// The screen will fill the entire window, maintaining the original aspect ratio but
     /// potentially being cropped.
     Crop,
@@ -295,7 +674,7 @@ pub fn get_screen_rect(
             )
         }
         ScalingMode::Stretch => Rectangle::new(0.0, 0.0, outer_width as f32, outer_height as f32),
-        ScalingMode::ShowAll => {
+        ScalingMode::ShowAll | ScalingMode::ShowAllHybrid => {
             let scale_factor = if internal_aspect_ratio > screen_aspect_ratio {
                 f_outer_width / f_inner_width
             } else {
@@ -370,3 +749,55 @@ pub fn get_screen_rect(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn letterbox_and_matrix() {
+        let scaler =
+            ScreenScaler::from_parts(None, 320, 240, 800, 480, 1.0, false, ScalingMode::ShowAll);
+
+        assert_eq!(
+            scaler.screen_rect(),
+            Rectangle::new(80.0, 0.0, 640.0, 480.0)
+        );
+
+        assert_eq!(
+            scaler.letterbox_rects(),
+            vec![
+                Rectangle::new(0.0, 0.0, 80.0, 480.0),
+                Rectangle::new(720.0, 0.0, 80.0, 480.0),
+            ]
+        );
+
+        let corner = scaler.as_matrix() * Vec2::new(320.0, 240.0).with_z(0.0).with_w(1.0);
+
+        assert_eq!(Vec2::new(corner.x, corner.y), Vec2::new(720.0, 480.0));
+        assert_eq!(
+            scaler.project(Vec2::new(720.0, 480.0)),
+            Vec2::new(320.0, 240.0)
+        );
+    }
+
+    #[test]
+    fn pixel_perfect_uses_physical_pixels() {
+        let scaler = ScreenScaler::from_parts(
+            None,
+            320,
+            240,
+            800,
+            600,
+            1.5,
+            true,
+            ScalingMode::ShowAllPixelPerfect,
+        );
+
+        // 1200x900 physical pixels fits the screen at 3x, which is 640x480 logical pixels.
+        assert_eq!(
+            scaler.screen_rect(),
+            Rectangle::new(80.0, 60.0, 640.0, 480.0)
+        );
+    }
+}
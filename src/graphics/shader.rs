@@ -1,19 +1,24 @@
 //! Functions and types relating to shader programs.
 
+mod preprocessor;
+
 use std::cell::{Cell, RefCell};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::slice;
 
+use bytemuck::Pod;
 use hashbrown::HashMap;
 
-use crate::error::Result;
-use crate::fs;
+use crate::error::{Result, TetraError};
+use crate::graphics::mesh::BufferUsage;
 use crate::graphics::{Color, Texture};
 use crate::math::{Mat2, Mat3, Mat4, Vec2, Vec3, Vec4};
-use crate::platform::{GraphicsDevice, RawShader};
+use crate::platform::{GraphicsDevice, RawShader, RawUniformBuffer};
 use crate::Context;
 
+pub(crate) use preprocessor::ShaderSource;
+
 /// The default vertex shader.
 ///
 /// The source code for this shader is available in [`src/resources/shader.vert`](https://github.com/17cupsofcoffee/tetra/blob/main/src/resources/shader.vert).
@@ -30,11 +35,21 @@ pub(crate) struct Sampler {
     pub(crate) unit: u32,
 }
 
+#[derive(Debug)]
+pub(crate) struct UniformBlock {
+    pub(crate) buffer: UniformBuffer,
+    pub(crate) binding: u32,
+}
+
 #[derive(Debug)]
 pub(crate) struct ShaderSharedData {
     pub(crate) handle: RawShader,
     pub(crate) samplers: RefCell<HashMap<String, Sampler>>,
     pub(crate) next_unit: Cell<u32>,
+    pub(crate) uniform_blocks: RefCell<HashMap<String, UniformBlock>>,
+    pub(crate) next_binding: Cell<u32>,
+    pub(crate) uniforms: Vec<ShaderUniform>,
+    pub(crate) attributes: Vec<ShaderAttribute>,
 }
 
 impl PartialEq for ShaderSharedData {
@@ -43,6 +58,121 @@ impl PartialEq for ShaderSharedData {
     }
 }
 
+#[derive(Debug, Clone)]
+enum ShaderInput {
+    Default,
+    File(PathBuf),
+    String(String),
+}
+
+impl ShaderInput {
+    fn load(
+        &self,
+        default: &str,
+        name: &str,
+        defines: &[(String, String)],
+    ) -> Result<ShaderSource> {
+        match self {
+            ShaderInput::Default => ShaderSource::new(default, name, None, defines),
+            ShaderInput::File(path) => ShaderSource::from_file(path, defines),
+            ShaderInput::String(code) => ShaderSource::new(code, name, None, defines),
+        }
+    }
+}
+
+/// A builder for creating advanced shader configurations.
+///
+/// This allows you to combine shader code from files and strings, and to inject
+/// `#define` directives into the code before it is compiled. If the vertex or fragment
+/// shader is not set, the default will be used.
+#[derive(Debug, Clone)]
+pub struct ShaderBuilder {
+    vertex: ShaderInput,
+    fragment: ShaderInput,
+    defines: Vec<(String, String)>,
+}
+
+impl ShaderBuilder {
+    /// Creates a new shader builder.
+    ///
+    /// You can also use [`Shader::builder`] as a shortcut for this, if you want
+    /// to avoid the extra import.
+    pub fn new() -> ShaderBuilder {
+        ShaderBuilder {
+            vertex: ShaderInput::Default,
+            fragment: ShaderInput::Default,
+            defines: Vec::new(),
+        }
+    }
+
+    /// Sets the vertex shader to be loaded from the given file.
+    pub fn vertex_file<P>(&mut self, path: P) -> &mut ShaderBuilder
+    where
+        P: AsRef<Path>,
+    {
+        self.vertex = ShaderInput::File(path.as_ref().to_owned());
+        self
+    }
+
+    /// Sets the vertex shader to the given string.
+    pub fn vertex_string(&mut self, shader: &str) -> &mut ShaderBuilder {
+        self.vertex = ShaderInput::String(shader.to_owned());
+        self
+    }
+
+    /// Sets the fragment shader to be loaded from the given file.
+    pub fn fragment_file<P>(&mut self, path: P) -> &mut ShaderBuilder
+    where
+        P: AsRef<Path>,
+    {
+        self.fragment = ShaderInput::File(path.as_ref().to_owned());
+        self
+    }
+
+    /// Sets the fragment shader to the given string.
+    pub fn fragment_string(&mut self, shader: &str) -> &mut ShaderBuilder {
+        self.fragment = ShaderInput::String(shader.to_owned());
+        self
+    }
+
+    /// Adds a `#define` directive to both the vertex and fragment shader.
+    ///
+    /// The directive will be inserted after the `#version` directive, if there is one, or
+    /// at the start of the code otherwise.
+    pub fn define(&mut self, name: &str, value: &str) -> &mut ShaderBuilder {
+        self.defines.push((name.to_owned(), value.to_owned()));
+        self
+    }
+
+    /// Builds the shader.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the
+    /// underlying graphics API encounters an error.
+    /// * [`TetraError::FailedToLoadAsset`](crate::TetraError::FailedToLoadAsset) will be returned
+    /// if a file (or an included file) could not be loaded.
+    /// * [`TetraError::InvalidShader`](crate::TetraError::InvalidShader) will be returned if the
+    /// shader could not be compiled.
+    pub fn build(&self, ctx: &mut Context) -> Result<Shader> {
+        let vertex_shader =
+            self.vertex
+                .load(DEFAULT_VERTEX_SHADER, "<vertex shader>", &self.defines)?;
+
+        let fragment_shader =
+            self.fragment
+                .load(DEFAULT_FRAGMENT_SHADER, "<fragment shader>", &self.defines)?;
+
+        Shader::with_device(&mut ctx.device, &vertex_shader, &fragment_shader)
+    }
+}
+
+impl Default for ShaderBuilder {
+    fn default() -> Self {
+        ShaderBuilder::new()
+    }
+}
+
 /// A shader program, consisting of a vertex shader and a fragment shader.
 ///
 /// # Data Format
@@ -77,6 +207,38 @@ impl PartialEq for ShaderSharedData {
 ///
 /// You can also set data into your own uniform variables via the [`set_uniform`](Shader::set_uniform) method.
 ///
+/// Arrays of uniforms can be set by passing a slice, and the members of uniform structs can be set
+/// individually by name (e.g. `u_lights[2].color`).
+///
+/// Uniform blocks can be backed by a [`UniformBuffer`], which is also set via
+/// [`set_uniform`](Shader::set_uniform). This allows a single set of data to be shared between
+/// multiple shaders, and only uploaded to the GPU once per update.
+///
+/// ## Preprocessing
+///
+/// Before being compiled, shader code is run through a simple preprocessor, which adds a couple of
+/// features that GLSL does not provide by itself:
+///
+/// * `#include "file"` will be replaced with the contents of the specified file. The path is resolved
+///   relative to the file containing the directive (or the current working directory, if the shader
+///   was loaded from a string).
+/// * Defines can be injected into the code via [`ShaderBuilder::define`]. These are inserted after the
+///   `#version` directive, if there is one.
+///
+/// If the shader fails to compile, line numbers in the error message will be rewritten to point at
+/// the original file and line, rather than at the preprocessed code.
+///
+/// # Reflection
+///
+/// Once a shader has been compiled, you can find out which uniforms and attributes it uses via the
+/// [`uniforms`](Shader::uniforms), [`samplers`](Shader::samplers) and [`attributes`](Shader::attributes)
+/// methods.
+///
+/// [`set_uniform`](Shader::set_uniform) will silently ignore uniforms that do not exist, as the GLSL
+/// compiler is allowed to remove uniforms that do not affect the output of the shader. If you want to
+/// catch typos in uniform names or mismatched types, use [`try_set_uniform`](Shader::try_set_uniform)
+/// instead.
+///
 /// Bear in mind that there is a hardware-defined limit on how many uniform locations can be used
 /// per shader. OpenGL 3.0 guarantees there will be at least 1024 of these locations available,
 /// which sounds like a lot - however, some types can use up multiple locations (e.g. a `vec2`
@@ -116,11 +278,10 @@ impl Shader {
     where
         P: AsRef<Path>,
     {
-        Shader::with_device(
-            &mut ctx.device,
-            &fs::read_to_string(vertex_path)?,
-            &fs::read_to_string(fragment_path)?,
-        )
+        ShaderBuilder::new()
+            .vertex_file(vertex_path)
+            .fragment_file(fragment_path)
+            .build(ctx)
     }
 
     /// Creates a new shader program from the given vertex shader file.
@@ -139,11 +300,7 @@ impl Shader {
     where
         P: AsRef<Path>,
     {
-        Shader::with_device(
-            &mut ctx.device,
-            &fs::read_to_string(path)?,
-            DEFAULT_FRAGMENT_SHADER,
-        )
+        ShaderBuilder::new().vertex_file(path).build(ctx)
     }
 
     /// Creates a new shader program from the given fragment shader file.
@@ -162,11 +319,7 @@ impl Shader {
     where
         P: AsRef<Path>,
     {
-        Shader::with_device(
-            &mut ctx.device,
-            DEFAULT_VERTEX_SHADER,
-            &fs::read_to_string(path)?,
-        )
+        ShaderBuilder::new().fragment_file(path).build(ctx)
     }
 
     /// Creates a new shader program from the given strings.
@@ -175,6 +328,8 @@ impl Shader {
     ///
     /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the
     /// underlying graphics API encounters an error.
+    /// * [`TetraError::FailedToLoadAsset`](crate::TetraError::FailedToLoadAsset) will be returned
+    /// if an included file could not be loaded.
     /// * [`TetraError::InvalidShader`](crate::TetraError::InvalidShader) will be returned if the
     /// shader could not be compiled.
     pub fn from_string(
@@ -182,7 +337,10 @@ impl Shader {
         vertex_shader: &str,
         fragment_shader: &str,
     ) -> Result<Shader> {
-        Shader::with_device(&mut ctx.device, vertex_shader, fragment_shader)
+        ShaderBuilder::new()
+            .vertex_string(vertex_shader)
+            .fragment_string(fragment_shader)
+            .build(ctx)
     }
 
     /// Creates a new shader program from the given vertex shader string.
@@ -193,10 +351,12 @@ impl Shader {
     ///
     /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the
     /// underlying graphics API encounters an error.
+    /// * [`TetraError::FailedToLoadAsset`](crate::TetraError::FailedToLoadAsset) will be returned
+    /// if an included file could not be loaded.
     /// * [`TetraError::InvalidShader`](crate::TetraError::InvalidShader) will be returned if the
     /// shader could not be compiled.
     pub fn from_vertex_string(ctx: &mut Context, shader: &str) -> Result<Shader> {
-        Shader::with_device(&mut ctx.device, shader, DEFAULT_FRAGMENT_SHADER)
+        ShaderBuilder::new().vertex_string(shader).build(ctx)
     }
 
     /// Creates a new shader program from the given fragment shader string.
@@ -207,24 +367,38 @@ impl Shader {
     ///
     /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the
     /// underlying graphics API encounters an error.
+    /// * [`TetraError::FailedToLoadAsset`](crate::TetraError::FailedToLoadAsset) will be returned
+    /// if an included file could not be loaded.
     /// * [`TetraError::InvalidShader`](crate::TetraError::InvalidShader) will be returned if the
     /// shader could not be compiled.
     pub fn from_fragment_string(ctx: &mut Context, shader: &str) -> Result<Shader> {
-        Shader::with_device(&mut ctx.device, DEFAULT_VERTEX_SHADER, shader)
+        ShaderBuilder::new().fragment_string(shader).build(ctx)
+    }
+
+    /// Creates a new shader builder, which can be used to create a shader with more advanced
+    /// configurations, such as injected defines.
+    pub fn builder() -> ShaderBuilder {
+        ShaderBuilder::new()
     }
 
     pub(crate) fn with_device(
         device: &mut GraphicsDevice,
-        vertex_shader: &str,
-        fragment_shader: &str,
+        vertex_shader: &ShaderSource,
+        fragment_shader: &ShaderSource,
     ) -> Result<Shader> {
         let handle = device.new_shader(vertex_shader, fragment_shader)?;
+        let uniforms = device.get_active_uniforms(&handle);
+        let attributes = device.get_active_attributes(&handle);
 
         Ok(Shader {
             data: Rc::new(ShaderSharedData {
                 handle,
                 samplers: RefCell::new(HashMap::new()),
                 next_unit: Cell::new(1),
+                uniform_blocks: RefCell::new(HashMap::new()),
+                next_binding: Cell::new(0),
+                uniforms,
+                attributes,
             }),
         })
     }
@@ -240,6 +414,94 @@ impl Shader {
         value.set_uniform(ctx, self, name)
     }
 
+    /// Sets the value of the specifed uniform parameter, checking that the uniform exists
+    /// and that its type is compatible with the value.
+    ///
+    /// See the [`UniformValue`] trait's docs for a list of which types can be used as a uniform,
+    /// and what their corresponding GLSL types are.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::InvalidUniform`](crate::TetraError::InvalidUniform) will be returned if the
+    /// shader does not have an active uniform with the given name, if an array index in the name
+    /// is out of bounds, or if the uniform's type does not match the value.
+    pub fn try_set_uniform<V>(&self, ctx: &mut Context, name: &str, value: V) -> Result
+    where
+        V: UniformValue,
+    {
+        value.validate_uniform(ctx, self, name)?;
+        value.set_uniform(ctx, self, name);
+
+        Ok(())
+    }
+
+    /// Returns the active uniforms in this shader.
+    ///
+    /// Uniforms that are not used by the shader may have been removed by the GLSL compiler,
+    /// and so will not be included. Members of uniform structs and arrays of structs are
+    /// listed individually.
+    pub fn uniforms(&self) -> &[ShaderUniform] {
+        &self.data.uniforms
+    }
+
+    /// Returns the active uniform with the given name, if there is one.
+    ///
+    /// Individual elements of arrays can also be looked up (e.g. `u_values[2]`), as long
+    /// as the index is within the bounds of the array.
+    pub fn uniform(&self, name: &str) -> Option<&ShaderUniform> {
+        self.data
+            .uniforms
+            .iter()
+            .find(|u| u.name == name)
+            .or_else(|| {
+                let (base, index) = split_array_index(name)?;
+
+                self.data
+                    .uniforms
+                    .iter()
+                    .find(|u| u.name == base && index < u.size)
+            })
+    }
+
+    /// Returns an iterator over the active sampler uniforms in this shader.
+    pub fn samplers(&self) -> impl Iterator<Item = &ShaderUniform> {
+        self.data
+            .uniforms
+            .iter()
+            .filter(|u| u.data_type == ShaderDataType::Sampler2D)
+    }
+
+    /// Returns the active vertex attributes in this shader.
+    pub fn attributes(&self) -> &[ShaderAttribute] {
+        &self.data.attributes
+    }
+
+    fn validate_uniform_type(&self, name: &str, accepted: &[ShaderDataType]) -> Result {
+        let uniform = self.uniform(name).ok_or_else(|| {
+            let array = split_array_index(name).and_then(|(base, index)| {
+                let uniform = self.data.uniforms.iter().find(|u| u.name == base)?;
+                Some((index, uniform.size))
+            });
+
+            TetraError::InvalidUniform(match array {
+                Some((index, size)) => format!(
+                    "index {} is out of bounds for uniform '{}', which has {} elements",
+                    index, name, size
+                ),
+                None => format!("no active uniform named '{}'", name),
+            })
+        })?;
+
+        if accepted.contains(&uniform.data_type) {
+            Ok(())
+        } else {
+            Err(TetraError::InvalidUniform(format!(
+                "uniform '{}' has type {:?}, which is not compatible with the value provided",
+                name, uniform.data_type
+            )))
+        }
+    }
+
     pub(crate) fn set_default_uniforms(
         &self,
         device: &mut GraphicsDevice,
@@ -252,6 +514,12 @@ impl Shader {
             device.attach_texture_to_sampler(&sampler.texture.data.handle, sampler.unit)?;
         }
 
+        let uniform_blocks = self.data.uniform_blocks.borrow();
+
+        for block in uniform_blocks.values() {
+            device.attach_uniform_buffer_to_binding(&block.buffer.handle, block.binding)?;
+        }
+
         let projection_location = device.get_uniform_location(&self.data.handle, "u_projection");
 
         device.set_uniform_mat4(
@@ -272,6 +540,104 @@ impl Shader {
     }
 }
 
+/// The type of a uniform or attribute, as declared in GLSL.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShaderDataType {
+    /// A `float`.
+    Float,
+
+    /// A `vec2`.
+    Vec2,
+
+    /// A `vec3`.
+    Vec3,
+
+    /// A `vec4`.
+    Vec4,
+
+    /// An `int`.
+    Int,
+
+    /// An `ivec2`.
+    IVec2,
+
+    /// An `ivec3`.
+    IVec3,
+
+    /// An `ivec4`.
+    IVec4,
+
+    /// A `uint`.
+    UInt,
+
+    /// A `uvec2`.
+    UVec2,
+
+    /// A `uvec3`.
+    UVec3,
+
+    /// A `uvec4`.
+    UVec4,
+
+    /// A `bool`.
+    Bool,
+
+    /// A `bvec2`.
+    BVec2,
+
+    /// A `bvec3`.
+    BVec3,
+
+    /// A `bvec4`.
+    BVec4,
+
+    /// A `mat2`.
+    Mat2,
+
+    /// A `mat3`.
+    Mat3,
+
+    /// A `mat4`.
+    Mat4,
+
+    /// A `sampler2D`.
+    Sampler2D,
+
+    /// A type that Tetra does not currently recognize (e.g. a `samplerCube`).
+    Other,
+}
+
+/// Information about an active uniform in a [`Shader`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShaderUniform {
+    /// The name of the uniform.
+    ///
+    /// For arrays, this is the name of the array itself, without an index.
+    pub name: String,
+
+    /// The GLSL type of the uniform (or of each element, if the uniform is an array).
+    pub data_type: ShaderDataType,
+
+    /// The number of elements in the uniform. This will be 1 for uniforms that are
+    /// not arrays.
+    pub size: usize,
+}
+
+/// Information about an active vertex attribute in a [`Shader`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShaderAttribute {
+    /// The name of the attribute.
+    pub name: String,
+
+    /// The GLSL type of the attribute.
+    pub data_type: ShaderDataType,
+
+    /// The number of elements in the attribute. This will be 1 for attributes that are
+    /// not arrays.
+    pub size: usize,
+}
+
 /// Implemented for types that can be passed as a uniform value to a shader.
 ///
 /// As the implementation of this trait currently interacts directly with the platform layer,
@@ -279,10 +645,13 @@ impl Shader {
 pub trait UniformValue {
     #[doc(hidden)]
     fn set_uniform(&self, ctx: &mut Context, shader: &Shader, name: &str);
+
+    #[doc(hidden)]
+    fn validate_uniform(&self, ctx: &Context, shader: &Shader, name: &str) -> Result;
 }
 
 macro_rules! simple_uniforms {
-    ($($t:ty => $f:ident, $doc:expr, $arraydoc:expr),* $(,)?) => {
+    ($($t:ty => $f:ident, [$($accepted:ident),*], $doc:expr, $arraydoc:expr),* $(,)?) => {
         $(
             #[doc = $doc]
             impl UniformValue for $t {
@@ -296,6 +665,11 @@ macro_rules! simple_uniforms {
                     let location = ctx.device.get_uniform_location(&shader.data.handle, name);
                     ctx.device.$f(&shader.data.handle, location.as_ref(), slice::from_ref(self));
                 }
+
+                #[doc(hidden)]
+                fn validate_uniform(&self, _: &Context, shader: &Shader, name: &str) -> Result {
+                    shader.validate_uniform_type(name, &[$(ShaderDataType::$accepted),*])
+                }
             }
 
             #[doc = $arraydoc]
@@ -310,6 +684,30 @@ macro_rules! simple_uniforms {
                     let location = ctx.device.get_uniform_location(&shader.data.handle, name);
                     ctx.device.$f(&shader.data.handle, location.as_ref(), self);
                 }
+
+                #[doc(hidden)]
+                fn validate_uniform(&self, _: &Context, shader: &Shader, name: &str) -> Result {
+                    shader.validate_uniform_type(name, &[$(ShaderDataType::$accepted),*])
+                }
+            }
+
+            #[doc = $arraydoc]
+            impl UniformValue for Vec<$t> {
+                #[doc(hidden)]
+                 fn set_uniform(
+                    &self,
+                    ctx: &mut Context,
+                    shader: &Shader,
+                    name: &str,
+                ) {
+                    let location = ctx.device.get_uniform_location(&shader.data.handle, name);
+                    ctx.device.$f(&shader.data.handle, location.as_ref(), self);
+                }
+
+                #[doc(hidden)]
+                fn validate_uniform(&self, _: &Context, shader: &Shader, name: &str) -> Result {
+                    shader.validate_uniform_type(name, &[$(ShaderDataType::$accepted),*])
+                }
             }
 
             #[doc = $arraydoc]
@@ -324,22 +722,27 @@ macro_rules! simple_uniforms {
                     let location = ctx.device.get_uniform_location(&shader.data.handle, name);
                     ctx.device.$f(&shader.data.handle, location.as_ref(), self);
                 }
+
+                #[doc(hidden)]
+                fn validate_uniform(&self, _: &Context, shader: &Shader, name: &str) -> Result {
+                    shader.validate_uniform_type(name, &[$(ShaderDataType::$accepted),*])
+                }
             }
         )*
     };
 }
 
 simple_uniforms! {
-    i32 => set_uniform_i32, "Can be accessed as an `int` in your shader.", "Can be accessed as an array of `int`s in your shader.",
-    u32 => set_uniform_u32, "Can be accessed as a `uint` in your shader.", "Can be accessed as an array of `uint`s in your shader.",
-    f32 => set_uniform_f32, "Can be accessed as a `float` in your shader.", "Can be accessed as an array of `float`s in your shader.",
-    Vec2<f32> => set_uniform_vec2, "Can be accessed as a `vec2` in your shader.", "Can be accessed as an array of `vec2`s in your shader.",
-    Vec3<f32> => set_uniform_vec3, "Can be accessed as a `vec3` in your shader.", "Can be accessed as an array of `vec3`s in your shader.",
-    Vec4<f32> => set_uniform_vec4, "Can be accessed as a `vec4` in your shader.", "Can be accessed as an array of `vec4`s in your shader.",
-    Mat2<f32> => set_uniform_mat2, "Can be accessed as a `mat2` in your shader.", "Can be accessed as an array of `mat2`s in your shader.",
-    Mat3<f32> => set_uniform_mat3, "Can be accessed as a `mat3` in your shader.", "Can be accessed as an array of `mat3`s in your shader.",
-    Mat4<f32> => set_uniform_mat4, "Can be accessed as a `mat4` in your shader.", "Can be accessed as an array of `mat4`s in your shader.",
-    Color => set_uniform_color, "Can be accessed as a `vec4` in your shader.", "Can be accessed as an array of `vec4`s in your shader.",
+    i32 => set_uniform_i32, [Int, Bool, Sampler2D], "Can be accessed as an `int` in your shader.", "Can be accessed as an array of `int`s in your shader.",
+    u32 => set_uniform_u32, [UInt, Bool], "Can be accessed as a `uint` in your shader.", "Can be accessed as an array of `uint`s in your shader.",
+    f32 => set_uniform_f32, [Float, Bool], "Can be accessed as a `float` in your shader.", "Can be accessed as an array of `float`s in your shader.",
+    Vec2<f32> => set_uniform_vec2, [Vec2, BVec2], "Can be accessed as a `vec2` in your shader.", "Can be accessed as an array of `vec2`s in your shader.",
+    Vec3<f32> => set_uniform_vec3, [Vec3, BVec3], "Can be accessed as a `vec3` in your shader.", "Can be accessed as an array of `vec3`s in your shader.",
+    Vec4<f32> => set_uniform_vec4, [Vec4, BVec4], "Can be accessed as a `vec4` in your shader.", "Can be accessed as an array of `vec4`s in your shader.",
+    Mat2<f32> => set_uniform_mat2, [Mat2], "Can be accessed as a `mat2` in your shader.", "Can be accessed as an array of `mat2`s in your shader.",
+    Mat3<f32> => set_uniform_mat3, [Mat3], "Can be accessed as a `mat3` in your shader.", "Can be accessed as an array of `mat3`s in your shader.",
+    Mat4<f32> => set_uniform_mat4, [Mat4], "Can be accessed as a `mat4` in your shader.", "Can be accessed as an array of `mat4`s in your shader.",
+    Color => set_uniform_color, [Vec4], "Can be accessed as a `vec4` in your shader.", "Can be accessed as an array of `vec4`s in your shader.",
 }
 
 /// Can be accessed via a `sampler2D` in your shader.
@@ -369,6 +772,57 @@ impl UniformValue for Texture {
             shader.data.next_unit.set(next_unit + 1);
         }
     }
+
+    #[doc(hidden)]
+    fn validate_uniform(&self, _: &Context, shader: &Shader, name: &str) -> Result {
+        shader.validate_uniform_type(name, &[ShaderDataType::Sampler2D])
+    }
+}
+
+/// Can be accessed via a uniform block in your shader.
+impl UniformValue for UniformBuffer {
+    #[doc(hidden)]
+    fn set_uniform(&self, ctx: &mut Context, shader: &Shader, name: &str) {
+        let mut uniform_blocks = shader.data.uniform_blocks.borrow_mut();
+
+        if let Some(block) = uniform_blocks.get_mut(name) {
+            if block.buffer != *self {
+                block.buffer = self.clone();
+            }
+        } else if let Some(index) = ctx
+            .device
+            .get_uniform_block_index(&shader.data.handle, name)
+        {
+            let next_binding = shader.data.next_binding.get();
+
+            uniform_blocks.insert(
+                name.to_owned(),
+                UniformBlock {
+                    buffer: self.clone(),
+                    binding: next_binding,
+                },
+            );
+
+            ctx.device
+                .set_uniform_block_binding(&shader.data.handle, index, next_binding);
+
+            shader.data.next_binding.set(next_binding + 1);
+        }
+    }
+
+    #[doc(hidden)]
+    fn validate_uniform(&self, ctx: &Context, shader: &Shader, name: &str) -> Result {
+        match ctx
+            .device
+            .get_uniform_block_index(&shader.data.handle, name)
+        {
+            Some(_) => Ok(()),
+            None => Err(TetraError::InvalidUniform(format!(
+                "no active uniform block named '{}'",
+                name
+            ))),
+        }
+    }
 }
 
 /// Any type that can be passed by value to a shader can also be passed by reference.
@@ -383,4 +837,146 @@ where
             inner.set_uniform(ctx, shader, name);
         }
     }
+
+    #[doc(hidden)]
+    fn validate_uniform(&self, ctx: &Context, shader: &Shader, name: &str) -> Result {
+        let inner = *self;
+        inner.validate_uniform(ctx, shader, name)
+    }
+}
+
+/// Splits a trailing array index (e.g. `[2]`) from a uniform name.
+fn split_array_index(name: &str) -> Option<(&str, usize)> {
+    let open = name.strip_suffix(']')?.rfind('[')?;
+    let index = &name[open + 1..name.len() - 1];
+
+    if !index.is_empty() && index.bytes().all(|b| b.is_ascii_digit()) {
+        Some((&name[..open], index.parse().ok()?))
+    } else {
+        None
+    }
+}
+
+/// Uniform data, stored in GPU memory.
+///
+/// A uniform buffer can be used to supply the data for a uniform block in a [`Shader`], by
+/// passing it to [`Shader::set_uniform`] along with the name of the block. The same buffer
+/// can be attached to multiple shaders, so data that is shared between them (e.g. a list of
+/// lights, or a palette of bone transforms) only needs to be uploaded once.
+///
+/// The data in the buffer must match the memory layout of the block in your shader. The easiest
+/// way to ensure this is to declare the block with `layout(std140)`, and to pad your data
+/// according to the rules of that layout.
+///
+/// # Performance
+///
+/// When you create or modify a uniform buffer, you are effectively 'uploading' data to the GPU, which
+/// can be relatively slow. You should try to minimize how often you do this - for example, update
+/// a buffer once per frame, rather than once per shader that uses it.
+///
+/// You can clone a uniform buffer cheaply, as it is a [reference-counted](https://doc.rust-lang.org/std/rc/struct.Rc.html)
+/// handle to a GPU resource. However, this does mean that modifying a buffer (e.g.
+/// calling `set_data`) will also affect any clones that exist of it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UniformBuffer {
+    pub(crate) handle: Rc<RawUniformBuffer>,
+}
+
+impl UniformBuffer {
+    /// Creates a new uniform buffer, with the specified size in bytes.
+    ///
+    /// The contents of the buffer will be undefined until data is written to it via
+    /// [`set_data`](Self::set_data).
+    ///
+    /// The buffer will be created with the [`BufferUsage::Dynamic`] usage hint - this can
+    /// be overridden via the [`with_usage`](Self::with_usage) constructor.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the underlying
+    /// graphics API encounters an error.
+    pub fn new(ctx: &mut Context, size: usize) -> Result<UniformBuffer> {
+        UniformBuffer::with_usage(ctx, size, BufferUsage::Dynamic)
+    }
+
+    /// Creates a new uniform buffer, with the specified size in bytes and usage hint.
+    ///
+    /// The GPU may optionally use the usage hint to optimize data storage and access.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the underlying
+    /// graphics API encounters an error.
+    pub fn with_usage(ctx: &mut Context, size: usize, usage: BufferUsage) -> Result<UniformBuffer> {
+        let buffer = ctx.device.new_uniform_buffer(size, usage)?;
+
+        Ok(UniformBuffer {
+            handle: Rc::new(buffer),
+        })
+    }
+
+    /// Creates a new uniform buffer, filled with the provided data.
+    ///
+    /// The size of the buffer will be the size of the data, in bytes.
+    ///
+    /// # Errors
+    ///
+    /// * [`TetraError::PlatformError`](crate::TetraError::PlatformError) will be returned if the underlying
+    /// graphics API encounters an error.
+    pub fn from_data<T>(ctx: &mut Context, data: &[T]) -> Result<UniformBuffer>
+    where
+        T: Pod,
+    {
+        let data: &[u8] = bytemuck::cast_slice(data);
+        let buffer = UniformBuffer::new(ctx, data.len())?;
+
+        ctx.device.set_uniform_buffer_data(&buffer.handle, data, 0);
+        ctx.graphics.stats.buffer_uploads += 1;
+
+        Ok(buffer)
+    }
+
+    /// Uploads new data to the GPU.
+    ///
+    /// The offset is specified in bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the data does not fit within the buffer at the given offset.
+    pub fn set_data<T>(&self, ctx: &mut Context, data: &[T], offset: usize)
+    where
+        T: Pod,
+    {
+        ctx.device
+            .set_uniform_buffer_data(&self.handle, bytemuck::cast_slice(data), offset);
+        ctx.graphics.stats.buffer_uploads += 1;
+    }
+
+    /// Returns the size of the buffer, in bytes.
+    pub fn size(&self) -> usize {
+        self.handle.size()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::split_array_index;
+
+    #[test]
+    fn split_array_index_from_name() {
+        assert_eq!(split_array_index("u_values[2]"), Some(("u_values", 2)));
+        assert_eq!(
+            split_array_index("u_lights[1].colors[10]"),
+            Some(("u_lights[1].colors", 10))
+        );
+    }
+
+    #[test]
+    fn split_array_index_without_index() {
+        assert_eq!(split_array_index("u_value"), None);
+        assert_eq!(split_array_index("u_values[]"), None);
+        assert_eq!(split_array_index("u_values[i]"), None);
+        assert_eq!(split_array_index("u_values[99999999999999999999999]"), None);
+        assert_eq!(split_array_index("u_lights[1].color"), None);
+    }
 }
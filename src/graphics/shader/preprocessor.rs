@@ -0,0 +1,431 @@
+//! A minimal preprocessing step for GLSL source code.
+//!
+//! This runs before the code is passed to the graphics driver, and handles the features that GLSL
+//! doesn't provide itself - namely, including other files and injecting defines from Rust code.
+
+use std::fmt::Write;
+use std::path::{Component, Path, PathBuf};
+use std::rc::Rc;
+
+use crate::error::{Result, TetraError};
+use crate::fs;
+
+/// The maximum depth of nested includes, to avoid blowing the stack on accidental recursion.
+const MAX_INCLUDE_DEPTH: usize = 32;
+
+/// Where a line of the preprocessed source originally came from.
+#[derive(Debug, Clone)]
+struct SourceLine {
+    file: Rc<str>,
+    line: usize,
+}
+
+/// Preprocessed shader source, along with enough information to map line numbers in
+/// compiler errors back to the original files.
+#[derive(Debug, Clone)]
+pub(crate) struct ShaderSource {
+    pub(crate) code: String,
+    lines: Vec<SourceLine>,
+}
+
+impl ShaderSource {
+    /// Preprocesses a piece of shader code.
+    ///
+    /// `name` is used to identify the code in error messages. If `path` is provided,
+    /// includes will be resolved relative to it - otherwise, they will be resolved
+    /// relative to the current working directory.
+    pub(crate) fn new(
+        code: &str,
+        name: &str,
+        path: Option<&Path>,
+        defines: &[(String, String)],
+    ) -> Result<ShaderSource> {
+        let mut source = ShaderSource {
+            code: String::with_capacity(code.len()),
+            lines: Vec::new(),
+        };
+
+        let mut stack = Vec::new();
+
+        if let Some(path) = path {
+            stack.push(normalize_path(path));
+        }
+
+        source.push_file(code, name.into(), path, Some(defines), &mut stack)?;
+
+        Ok(source)
+    }
+
+    /// Loads and preprocesses a shader file.
+    pub(crate) fn from_file(path: &Path, defines: &[(String, String)]) -> Result<ShaderSource> {
+        let code = fs::read_to_string(path)?;
+
+        ShaderSource::new(&code, &path.to_string_lossy(), Some(path), defines)
+    }
+
+    fn push_file(
+        &mut self,
+        code: &str,
+        file: Rc<str>,
+        path: Option<&Path>,
+        mut defines: Option<&[(String, String)]>,
+        stack: &mut Vec<PathBuf>,
+    ) -> Result {
+        // Defines have to come after the `#version` directive, so if there isn't
+        // one, we insert them at the very start.
+        if !code.lines().any(is_version_directive) {
+            if let Some(defines) = defines.take() {
+                self.push_defines(defines);
+            }
+        }
+
+        for (i, line) in code.lines().enumerate() {
+            let line_number = i + 1;
+
+            match parse_include(line) {
+                Some(include) => {
+                    let include_path = normalize_path(&match path.and_then(Path::parent) {
+                        Some(dir) => dir.join(include),
+                        None => PathBuf::from(include),
+                    });
+
+                    if stack.contains(&include_path) {
+                        return Err(TetraError::InvalidShader(format!(
+                            "{}:{}: circular include of '{}'",
+                            file,
+                            line_number,
+                            include_path.to_string_lossy()
+                        )));
+                    }
+
+                    if stack.len() >= MAX_INCLUDE_DEPTH {
+                        return Err(TetraError::InvalidShader(format!(
+                            "{}:{}: includes are nested more than {} levels deep",
+                            file, line_number, MAX_INCLUDE_DEPTH
+                        )));
+                    }
+
+                    let include_code = fs::read_to_string(&include_path)?;
+
+                    stack.push(include_path.clone());
+
+                    self.push_file(
+                        &include_code,
+                        include_path.to_string_lossy().into(),
+                        Some(&include_path),
+                        None,
+                        stack,
+                    )?;
+
+                    stack.pop();
+                }
+
+                None => {
+                    self.push_line(line, Rc::clone(&file), line_number);
+
+                    if is_version_directive(line) {
+                        if let Some(defines) = defines.take() {
+                            self.push_defines(defines);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn push_defines(&mut self, defines: &[(String, String)]) {
+        let file: Rc<str> = "<defines>".into();
+
+        for (i, (name, value)) in defines.iter().enumerate() {
+            let _ = writeln!(self.code, "#define {} {}", name, value);
+
+            self.lines.push(SourceLine {
+                file: Rc::clone(&file),
+                line: i + 1,
+            });
+        }
+    }
+
+    fn push_line(&mut self, line: &str, file: Rc<str>, line_number: usize) {
+        self.code.push_str(line);
+        self.code.push('\n');
+
+        self.lines.push(SourceLine {
+            file,
+            line: line_number,
+        });
+    }
+
+    /// Rewrites the line numbers in a compiler log to point at the original files.
+    ///
+    /// Drivers are not consistent about how they format their logs, so this looks for
+    /// the most common styles:
+    ///
+    /// * `0(12) : error ...` (NVIDIA)
+    /// * `0:12(5): error ...` (Mesa)
+    /// * `ERROR: 0:12: ...` (AMD, Apple)
+    ///
+    /// Lines that don't match any of these are left as they are.
+    pub(crate) fn format_log(&self, log: &str) -> String {
+        let mut output = String::with_capacity(log.len());
+
+        for line in log.lines() {
+            let reference = find_line_reference(line).and_then(|(start, end, number)| {
+                let source = self.lines.get(number.checked_sub(1)?)?;
+                Some((start, end, source))
+            });
+
+            match reference {
+                Some((start, end, source)) => {
+                    let _ = writeln!(
+                        output,
+                        "{}{}:{}{}",
+                        &line[..start],
+                        source.file,
+                        source.line,
+                        &line[end..]
+                    );
+                }
+
+                None => {
+                    output.push_str(line);
+                    output.push('\n');
+                }
+            }
+        }
+
+        output
+    }
+}
+
+/// Returns whether the line is a `#version` directive.
+fn is_version_directive(line: &str) -> bool {
+    line.trim_start()
+        .strip_prefix('#')
+        .is_some_and(|rest| rest.trim_start().starts_with("version"))
+}
+
+/// Removes `.` and `..` components from a path, so that different ways of referring to
+/// the same file compare as equal.
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match normalized.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    normalized.pop();
+                }
+                Some(Component::RootDir) | Some(Component::Prefix(_)) => {}
+                _ => normalized.push(".."),
+            },
+            other => normalized.push(other),
+        }
+    }
+
+    normalized
+}
+
+/// Returns the path from an `#include "file"` directive, if the line contains one.
+fn parse_include(line: &str) -> Option<&str> {
+    let rest = line.trim().strip_prefix('#')?.trim_start();
+    let rest = rest.strip_prefix("include")?.trim();
+
+    let path = rest
+        .strip_prefix('"')
+        .and_then(|r| r.strip_suffix('"'))
+        .or_else(|| rest.strip_prefix('<').and_then(|r| r.strip_suffix('>')))?;
+
+    if path.is_empty() {
+        None
+    } else {
+        Some(path)
+    }
+}
+
+/// Finds a `<source>(<line>)` or `<source>:<line>` reference in a line of a compiler log,
+/// returning the byte range of the reference and the line number.
+fn find_line_reference(line: &str) -> Option<(usize, usize, usize)> {
+    let bytes = line.as_bytes();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if !bytes[i].is_ascii_digit() || (i > 0 && bytes[i - 1].is_ascii_alphanumeric()) {
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+
+        let terminator = match bytes.get(i) {
+            Some(b'(') => Some(b')'),
+            Some(b':') => None,
+            _ => continue,
+        };
+
+        let number_start = i + 1;
+        let mut number_end = number_start;
+
+        while number_end < bytes.len() && bytes[number_end].is_ascii_digit() {
+            number_end += 1;
+        }
+
+        if number_end == number_start {
+            continue;
+        }
+
+        let end = match terminator {
+            Some(t) if bytes.get(number_end) == Some(&t) => number_end + 1,
+            Some(_) => continue,
+            None => number_end,
+        };
+
+        let number = line[number_start..number_end].parse().ok()?;
+
+        return Some((start, end, number));
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn defines() -> Vec<(String, String)> {
+        vec![
+            ("MAX_LIGHTS".into(), "8".into()),
+            ("USE_FOG".into(), "1".into()),
+        ]
+    }
+
+    #[test]
+    fn defines_after_version() {
+        let source = ShaderSource::new(
+            "#version 150\nvoid main() {}\n",
+            "test.frag",
+            None,
+            &defines(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            source.code,
+            "#version 150\n#define MAX_LIGHTS 8\n#define USE_FOG 1\nvoid main() {}\n"
+        );
+    }
+
+    #[test]
+    fn defines_after_version_with_leading_comment() {
+        let source = ShaderSource::new(
+            "// Copyright (c) Someone\n\n#version 150\nvoid main() {}\n",
+            "test.frag",
+            None,
+            &defines(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            source.code,
+            "// Copyright (c) Someone\n\n#version 150\n#define MAX_LIGHTS 8\n#define USE_FOG 1\nvoid main() {}\n"
+        );
+    }
+
+    #[test]
+    fn normalize_include_paths() {
+        assert_eq!(
+            normalize_path(Path::new("shaders/a/../b.glsl")),
+            PathBuf::from("shaders/b.glsl")
+        );
+        assert_eq!(
+            normalize_path(Path::new("./shaders/./b.glsl")),
+            PathBuf::from("shaders/b.glsl")
+        );
+        assert_eq!(
+            normalize_path(Path::new("../lib/../b.glsl")),
+            PathBuf::from("../b.glsl")
+        );
+    }
+
+    #[test]
+    fn defines_without_version() {
+        let source = ShaderSource::new("void main() {}", "test.frag", None, &defines()).unwrap();
+
+        assert_eq!(
+            source.code,
+            "#define MAX_LIGHTS 8\n#define USE_FOG 1\nvoid main() {}\n"
+        );
+    }
+
+    #[test]
+    fn parse_include_directives() {
+        assert_eq!(parse_include("#include \"noise.glsl\""), Some("noise.glsl"));
+        assert_eq!(
+            parse_include("  #  include <lib/light.glsl>  "),
+            Some("lib/light.glsl")
+        );
+        assert_eq!(parse_include("#include \"\""), None);
+        assert_eq!(parse_include("#define INCLUDE 1"), None);
+        assert_eq!(parse_include("// #include"), None);
+    }
+
+    #[test]
+    fn format_nvidia_log() {
+        let source = ShaderSource::new(
+            "#version 150\nvoid main() {\n    oops\n}\n",
+            "test.frag",
+            None,
+            &defines(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            source.format_log("0(5) : error C0000: syntax error"),
+            "test.frag:3 : error C0000: syntax error\n"
+        );
+    }
+
+    #[test]
+    fn format_mesa_log() {
+        let source = ShaderSource::new(
+            "#version 150\nvoid main() {\n    oops\n}\n",
+            "test.frag",
+            None,
+            &defines(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            source.format_log("0:5(5): error: syntax error\n0:2(1): warning: defined here"),
+            "test.frag:3(5): error: syntax error\n<defines>:1(1): warning: defined here\n"
+        );
+    }
+
+    #[test]
+    fn format_amd_log() {
+        let source =
+            ShaderSource::new("void main() {\n    oops\n}\n", "test.frag", None, &[]).unwrap();
+
+        assert_eq!(
+            source.format_log("ERROR: 0:2: 'oops' : undeclared identifier"),
+            "ERROR: test.frag:2: 'oops' : undeclared identifier\n"
+        );
+    }
+
+    #[test]
+    fn format_unrecognized_log() {
+        let source = ShaderSource::new("void main() {}\n", "test.frag", None, &[]).unwrap();
+
+        assert_eq!(
+            source.format_log("error: something went wrong"),
+            "error: something went wrong\n"
+        );
+    }
+}
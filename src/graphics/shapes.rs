@@ -0,0 +1,202 @@
+use crate::error::Result;
+use crate::graphics::mesh::{GeometryBuilder, ShapeStyle, Vertex};
+use crate::graphics::{Color, Rectangle};
+use crate::math::Vec2;
+use crate::Context;
+
+use super::{FlushReason, MAX_INDICES};
+
+/// Draws a rectangle.
+///
+/// Unlike [`Mesh::rectangle`](super::mesh::Mesh::rectangle), this does not create any
+/// GPU buffers - the shape is tessellated on the CPU and added to the sprite batch,
+/// which makes it a good fit for debug overlays and simple UI that changes every frame.
+/// Shapes are drawn using a plain white texture, so consecutive shapes will be batched
+/// together, but switching between shapes and textured sprites will cause a flush.
+///
+/// # Errors
+///
+/// * [`TetraError::TessellationError`](crate::TetraError::TessellationError) will be returned if the shape
+/// could not be turned into vertex data.
+pub fn draw_rectangle(
+    ctx: &mut Context,
+    style: ShapeStyle,
+    rectangle: Rectangle,
+    color: Color,
+) -> Result {
+    draw_shape(ctx, color, |builder| {
+        builder.rectangle(style, rectangle)?;
+        Ok(())
+    })
+}
+
+/// Draws a circle.
+///
+/// See [`draw_rectangle`] for details of how immediate-mode shapes are batched.
+///
+/// # Errors
+///
+/// * [`TetraError::TessellationError`](crate::TetraError::TessellationError) will be returned if the shape
+/// could not be turned into vertex data.
+pub fn draw_circle(
+    ctx: &mut Context,
+    style: ShapeStyle,
+    center: Vec2<f32>,
+    radius: f32,
+    color: Color,
+) -> Result {
+    draw_shape(ctx, color, |builder| {
+        builder.circle(style, center, radius)?;
+        Ok(())
+    })
+}
+
+/// Draws an ellipse.
+///
+/// See [`draw_rectangle`] for details of how immediate-mode shapes are batched.
+///
+/// # Errors
+///
+/// * [`TetraError::TessellationError`](crate::TetraError::TessellationError) will be returned if the shape
+/// could not be turned into vertex data.
+pub fn draw_ellipse(
+    ctx: &mut Context,
+    style: ShapeStyle,
+    center: Vec2<f32>,
+    radii: Vec2<f32>,
+    color: Color,
+) -> Result {
+    draw_shape(ctx, color, |builder| {
+        builder.ellipse(style, center, radii)?;
+        Ok(())
+    })
+}
+
+/// Draws a line between two points.
+///
+/// See [`draw_rectangle`] for details of how immediate-mode shapes are batched.
+///
+/// # Errors
+///
+/// * [`TetraError::TessellationError`](crate::TetraError::TessellationError) will be returned if the shape
+/// could not be turned into vertex data.
+pub fn draw_line(
+    ctx: &mut Context,
+    from: Vec2<f32>,
+    to: Vec2<f32>,
+    width: f32,
+    color: Color,
+) -> Result {
+    draw_polyline(ctx, width, &[from, to], color)
+}
+
+/// Draws a polygon.
+///
+/// See [`draw_rectangle`] for details of how immediate-mode shapes are batched.
+///
+/// # Errors
+///
+/// * [`TetraError::TessellationError`](crate::TetraError::TessellationError) will be returned if the shape
+/// could not be turned into vertex data.
+pub fn draw_polygon(
+    ctx: &mut Context,
+    style: ShapeStyle,
+    points: &[Vec2<f32>],
+    color: Color,
+) -> Result {
+    draw_shape(ctx, color, |builder| {
+        builder.polygon(style, points)?;
+        Ok(())
+    })
+}
+
+/// Draws a polyline.
+///
+/// See [`draw_rectangle`] for details of how immediate-mode shapes are batched.
+///
+/// # Errors
+///
+/// * [`TetraError::TessellationError`](crate::TetraError::TessellationError) will be returned if the shape
+/// could not be turned into vertex data.
+pub fn draw_polyline(
+    ctx: &mut Context,
+    stroke_width: f32,
+    points: &[Vec2<f32>],
+    color: Color,
+) -> Result {
+    draw_shape(ctx, color, |builder| {
+        builder.polyline(stroke_width, points)?;
+        Ok(())
+    })
+}
+
+fn draw_shape<F>(ctx: &mut Context, color: Color, build: F) -> Result
+where
+    F: FnOnce(&mut GeometryBuilder) -> Result,
+{
+    // The builder is kept around between calls, so that its buffers only have to be
+    // allocated once.
+    let mut builder = std::mem::take(&mut ctx.graphics.shape_builder);
+    builder.clear().set_color(color);
+
+    let result = build(&mut builder);
+
+    if result.is_ok() {
+        push_triangles(ctx, builder.vertices(), builder.indices());
+    }
+
+    ctx.graphics.shape_builder = builder;
+
+    result
+}
+
+/// Adds a list of indexed triangles to the sprite batch.
+///
+/// The batch's index buffer is pre-filled with quads, so each triangle is pushed as a
+/// degenerate quad (with the last vertex repeated). This wastes a vertex per triangle,
+/// but means that shapes and sprites can share the same buffers.
+pub(crate) fn push_triangles(ctx: &mut Context, vertices: &[Vertex], indices: &[u32]) {
+    let texture = ctx.graphics.default_texture.clone();
+    super::set_texture(ctx, &texture);
+
+    for triangle in indices.chunks_exact(3) {
+        if ctx.graphics.element_count + 6 > MAX_INDICES {
+            super::flush_with_reason(ctx, FlushReason::BatchFull);
+        }
+
+        let a = vertices[triangle[0] as usize];
+        let mut b = vertices[triangle[1] as usize];
+        let mut c = vertices[triangle[2] as usize];
+
+        // Sprites are wound counter-clockwise (in screen space), so triangles
+        // need to match in order to survive backface culling.
+        if winding(a.position, b.position, c.position) > 0.0 {
+            std::mem::swap(&mut b, &mut c);
+        }
+
+        ctx.graphics.vertex_data.extend_from_slice(&[a, b, c, c]);
+        ctx.graphics.element_count += 6;
+    }
+}
+
+/// Returns a positive value if the points are wound clockwise in screen space (i.e. with
+/// the Y axis pointing down), and a negative value if they are wound counter-clockwise.
+fn winding(a: Vec2<f32>, b: Vec2<f32>, c: Vec2<f32>) -> f32 {
+    (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sprite_quads_are_counter_clockwise() {
+        // The first three corners of a quad, as generated by `push_quad`.
+        let top_left = Vec2::new(0.0, 0.0);
+        let bottom_left = Vec2::new(0.0, 1.0);
+        let bottom_right = Vec2::new(1.0, 1.0);
+
+        assert!(winding(top_left, bottom_left, bottom_right) < 0.0);
+        assert!(winding(top_left, bottom_right, bottom_left) > 0.0);
+    }
+}
@@ -0,0 +1,164 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::platform::{GraphicsDevice, RawQuery};
+
+/// The reason that queued drawing operations were sent to the graphics hardware.
+///
+/// Each flush results in a draw call, so if your game is performing more draw calls
+/// than expected, this can be used to find out what is breaking up the batches.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FlushReason {
+    /// [`flush`](super::flush) was called manually.
+    Manual,
+
+    /// The batch was full, and no more vertices could be added to it.
+    BatchFull,
+
+    /// A different texture was drawn.
+    TextureChanged,
+
+    /// A different shader was set.
+    ShaderChanged,
+
+    /// A different canvas was set.
+    CanvasChanged,
+
+    /// A different blend state was set.
+    BlendStateChanged,
+
+    /// The transform matrix was changed.
+    TransformChanged,
+
+    /// The scissor rectangle was changed.
+    ScissorChanged,
+
+    /// The stencil state was changed, or the stencil buffer was cleared.
+    StencilChanged,
+
+    /// The color mask was changed.
+    ColorMaskChanged,
+
+    /// A [`Mesh`](super::mesh::Mesh) was drawn, which is not batched.
+    MeshDrawn,
+
+    /// The frame was presented to the screen.
+    Present,
+}
+
+/// Statistics about the rendering work that was done in a frame.
+///
+/// These can be retrieved via [`get_stats`](super::get_stats).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RenderStats {
+    /// The number of draw calls that were made.
+    ///
+    /// This includes both flushes of the sprite batch and mesh draws.
+    pub draw_calls: usize,
+
+    /// The number of times the sprite batch was flushed.
+    pub flushes: usize,
+
+    /// The number of vertices (or indices, for indexed geometry) that were drawn.
+    pub vertices: usize,
+
+    /// The number of times the active texture was changed.
+    pub texture_switches: usize,
+
+    /// The number of times the active shader was changed.
+    pub shader_switches: usize,
+
+    /// The number of times the active canvas was changed.
+    pub canvas_switches: usize,
+
+    /// The number of times data was uploaded to a vertex, index or uniform buffer.
+    pub buffer_uploads: usize,
+
+    /// The reason for each flush of the sprite batch, in the order that they happened.
+    pub flush_reasons: Vec<FlushReason>,
+
+    /// The time that the GPU spent rendering the frame.
+    ///
+    /// This will be [`None`] unless GPU timing has been enabled via
+    /// [`set_gpu_timing`](super::set_gpu_timing), and is supported by the
+    /// graphics device. The results are only available once the GPU
+    /// has caught up, so this may lag behind the other stats by a few frames.
+    pub gpu_time: Option<Duration>,
+}
+
+impl RenderStats {
+    pub(crate) fn reset(&mut self) {
+        self.draw_calls = 0;
+        self.flushes = 0;
+        self.vertices = 0;
+        self.texture_switches = 0;
+        self.shader_switches = 0;
+        self.canvas_switches = 0;
+        self.buffer_uploads = 0;
+        self.flush_reasons.clear();
+        self.gpu_time = None;
+    }
+}
+
+/// Measures how long the GPU spends on each frame, via timer queries.
+///
+/// Query results only become available once the GPU has finished the frame, so
+/// queries are kept in a queue and polled until they're ready.
+#[derive(Debug)]
+pub(crate) struct GpuTimer {
+    active: Option<RawQuery>,
+    pending: VecDeque<RawQuery>,
+    free: Vec<RawQuery>,
+    latest: Option<Duration>,
+}
+
+impl GpuTimer {
+    pub(crate) fn new() -> GpuTimer {
+        GpuTimer {
+            active: None,
+            pending: VecDeque::new(),
+            free: Vec::new(),
+            latest: None,
+        }
+    }
+
+    pub(crate) fn begin_frame(&mut self, device: &mut GraphicsDevice) {
+        if self.active.is_some() {
+            return;
+        }
+
+        let query = match self.free.pop() {
+            Some(query) => query,
+            None => match device.new_query() {
+                Ok(query) => query,
+                Err(_) => return,
+            },
+        };
+
+        device.begin_timer_query(&query);
+        self.active = Some(query);
+    }
+
+    pub(crate) fn end_frame(&mut self, device: &mut GraphicsDevice) {
+        if let Some(query) = self.active.take() {
+            device.end_timer_query();
+            self.pending.push_back(query);
+        }
+
+        while let Some(query) = self.pending.front() {
+            match device.get_query_result(query) {
+                Some(nanos) => {
+                    self.latest = Some(Duration::from_nanos(nanos.into()));
+                    self.free.extend(self.pending.pop_front());
+                }
+
+                None => break,
+            }
+        }
+    }
+
+    pub(crate) fn latest(&self) -> Option<Duration> {
+        self.latest
+    }
+}
@@ -3,6 +3,7 @@
 mod bmfont;
 mod cache;
 mod packer;
+mod rich;
 #[cfg(feature = "font_ttf")]
 mod vector;
 
@@ -20,6 +21,7 @@ use crate::Context;
 pub use crate::graphics::text::vector::VectorFontBuilder;
 
 pub use crate::graphics::text::bmfont::BmFontBuilder;
+pub use crate::graphics::text::rich::RichText;
 
 use super::FilterMode;
 
@@ -221,14 +223,15 @@ impl Text {
     }
 
     /// Draws the text to the screen (or to a canvas, if one is enabled).
+    ///
+    /// The [`pivot`](DrawParams::pivot) and flipping of the params are relative to the
+    /// [bounds](Text::get_bounds) of the text.
     pub fn draw<P>(&mut self, ctx: &mut Context, params: P)
     where
         P: Into<DrawParams>,
     {
         self.update_geometry(ctx);
 
-        let params = params.into();
-
         let data = self.font.data.borrow();
         let texture = data.texture();
         let geometry = self
@@ -236,6 +239,8 @@ impl Text {
             .as_ref()
             .expect("geometry should have been generated");
 
+        let params = params.into().resolve(geometry.bounds.unwrap_or_default());
+
         graphics::set_texture(ctx, texture);
         let (texture_width, texture_height) = texture.size();
 
@@ -44,7 +44,7 @@ impl TextQuad {
 }
 
 /// Errors that can occur when caching a glyph.
-enum CacheError {
+pub(crate) enum CacheError {
     /// Returned when the texture atlas is out of space.
     OutOfSpace,
 }
@@ -136,6 +136,26 @@ impl FontCache {
         self.packer.set_filter_mode(ctx, filter_mode);
     }
 
+    /// The height of a line of text, rounded to the nearest pixel.
+    pub fn line_height(&self) -> f32 {
+        self.rasterizer.line_height().round()
+    }
+
+    /// The ascent of the font, rounded to the nearest pixel.
+    pub fn ascent(&self) -> f32 {
+        self.rasterizer.ascent().round()
+    }
+
+    /// The horizontal advance for a given glyph.
+    pub fn advance(&self, glyph: char) -> f32 {
+        self.rasterizer.advance(glyph)
+    }
+
+    /// The amount of kerning that should be applied between the given glyphs.
+    pub fn kerning(&self, previous: char, current: char) -> f32 {
+        self.rasterizer.kerning(previous, current)
+    }
+
     /// Generates the geometry for the given string, resizing the texture atlas if needed.
     pub fn render(
         &mut self,
@@ -168,30 +188,33 @@ impl FontCache {
         let mut cursor = Vec2::new(0.0, self.rasterizer.ascent().round());
         let mut last_glyph: Option<char> = None;
         let mut text_bounds: Option<Rectangle> = None;
-        let mut words_on_line = 0;
+        let mut wrap = WordWrap::new(max_width);
 
         for (word, _) in UnicodeLineBreaks::new(input) {
-            if let Some(max_width) = max_width {
-                // We only allow wrapping to take place after the first word on each line,
-                // to avoid extra line breaks appearing when a word is too long to fit on
-                // a single line.
-                if words_on_line > 0 && cursor.x + self.measure_word(word) > max_width {
-                    cursor.x = 0.0;
-                    cursor.y += line_height;
-                    last_glyph = None;
-                    words_on_line = 0;
-                }
+            let wrapped = wrap.wrap_before_word(cursor.x, || {
+                measure_word(word.chars().map(|ch| (ch, ())), |ch, _, last| {
+                    let kerning = match last {
+                        Some((last, _)) => self.rasterizer.kerning(last, ch),
+                        None => 0.0,
+                    };
+
+                    (kerning, self.rasterizer.advance(ch))
+                })
+            });
+
+            if wrapped {
+                cursor.x = 0.0;
+                cursor.y += line_height;
+                last_glyph = None;
             }
 
-            words_on_line += 1;
-
             for ch in word.chars() {
                 if ch.is_control() {
                     if ch == '\n' {
                         cursor.x = 0.0;
                         cursor.y += line_height;
                         last_glyph = None;
-                        words_on_line = 0;
+                        wrap.new_line();
                     }
 
                     continue;
@@ -226,29 +249,8 @@ impl FontCache {
         })
     }
 
-    /// Measures the width of a word, not including any trailing whitespace.
-    ///
-    /// This is mainly used to determine if a word needs to break onto a
-    /// new line.
-    fn measure_word(&self, word: &str) -> f32 {
-        let mut last_glyph = None;
-        let mut word_width = 0.0;
-
-        for ch in word.trim_end().chars() {
-            word_width += self.rasterizer.advance(ch);
-
-            if let Some(last) = last_glyph {
-                word_width += self.rasterizer.kerning(last, ch);
-            }
-
-            last_glyph = Some(ch);
-        }
-
-        word_width
-    }
-
     /// Rasterizes a character with a given position, or pull it from the texture cache.
-    fn rasterize_char(
+    pub(crate) fn rasterize_char(
         &mut self,
         device: &mut GraphicsDevice,
         ch: char,
@@ -299,7 +301,7 @@ impl FontCache {
     }
 
     /// Resizes the texture atlas, clearing any cached data.
-    fn resize(&mut self, device: &mut GraphicsDevice) -> Result {
+    pub(crate) fn resize(&mut self, device: &mut GraphicsDevice) -> Result {
         let (texture_width, texture_height) = self.packer.texture().size();
 
         let new_width = texture_width * 2;
@@ -348,14 +350,15 @@ fn add_glyph_to_texture(
     })
 }
 
-struct UnicodeLineBreaks<'a> {
+/// Splits a string into words, at the points where a line break is allowed.
+pub(crate) struct UnicodeLineBreaks<'a> {
     input: &'a str,
     breaker: LineBreakIterator<'a>,
     last_break: usize,
 }
 
 impl<'a> UnicodeLineBreaks<'a> {
-    fn new(input: &'a str) -> UnicodeLineBreaks<'a> {
+    pub fn new(input: &'a str) -> UnicodeLineBreaks<'a> {
         UnicodeLineBreaks {
             input,
             breaker: LineBreakIterator::new(input),
@@ -375,3 +378,81 @@ impl<'a> Iterator for UnicodeLineBreaks<'a> {
         })
     }
 }
+
+/// Decides where lines should be broken when word-wrapping text.
+pub(crate) struct WordWrap {
+    max_width: Option<f32>,
+    words_on_line: usize,
+}
+
+impl WordWrap {
+    pub fn new(max_width: Option<f32>) -> WordWrap {
+        WordWrap {
+            max_width,
+            words_on_line: 0,
+        }
+    }
+
+    /// Returns whether the next word should be moved onto a new line, based on the current
+    /// position of the cursor and the width of the word.
+    ///
+    /// The word will only be measured if wrapping is enabled.
+    pub fn wrap_before_word<F>(&mut self, cursor_x: f32, measure: F) -> bool
+    where
+        F: FnOnce() -> f32,
+    {
+        // We only allow wrapping to take place after the first word on each line,
+        // to avoid extra line breaks appearing when a word is too long to fit on
+        // a single line.
+        let wrap = match self.max_width {
+            Some(max_width) => self.words_on_line > 0 && cursor_x + measure() > max_width,
+            None => false,
+        };
+
+        if wrap {
+            self.words_on_line = 0;
+        }
+
+        self.words_on_line += 1;
+
+        wrap
+    }
+
+    /// Starts a new line, due to an explicit line break in the text.
+    pub fn new_line(&mut self) {
+        self.words_on_line = 0;
+    }
+}
+
+/// Measures the width of a word, not including any trailing whitespace.
+///
+/// `metrics` is called for each glyph, along with the glyph before it (if any), and should
+/// return the kerning to apply before the glyph and its advance. Control characters are
+/// skipped, as they are not drawn.
+pub(crate) fn measure_word<G, I, F>(glyphs: I, mut metrics: F) -> f32
+where
+    G: Copy,
+    I: IntoIterator<Item = (char, G)>,
+    F: FnMut(char, G, Option<(char, G)>) -> (f32, f32),
+{
+    let mut last_glyph = None;
+    let mut width = 0.0;
+    let mut trimmed_width = 0.0;
+
+    for (ch, glyph) in glyphs {
+        if ch.is_control() {
+            continue;
+        }
+
+        let (kerning, advance) = metrics(ch, glyph, last_glyph);
+        width += kerning + advance;
+
+        if !ch.is_whitespace() {
+            trimmed_width = width;
+        }
+
+        last_glyph = Some((ch, glyph));
+    }
+
+    trimmed_width
+}
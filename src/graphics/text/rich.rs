@@ -0,0 +1,990 @@
+use std::rc::Rc;
+
+use hashbrown::HashMap;
+
+use crate::graphics::text::cache::{
+    measure_word, CacheError, TextQuad, UnicodeLineBreaks, WordWrap,
+};
+use crate::graphics::text::Font;
+use crate::graphics::{self, Color, DrawParams, Rectangle, Texture};
+use crate::math::Vec2;
+use crate::platform::GraphicsDevice;
+use crate::Context;
+
+/// The character used to represent icons when calculating line breaks.
+const ICON_PLACEHOLDER: char = '\u{FFFC}';
+
+/// A piece of text with inline markup, allowing different parts of it to be styled
+/// differently.
+///
+/// All of the styled spans are laid out together, so word wrapping works the same way as
+/// it does for [`Text`](super::Text).
+///
+/// # Markup
+///
+/// The following tags are supported:
+///
+/// | Tag | Effect |
+/// |-----|--------|
+/// | `[color=#rrggbb]...[/color]` | Changes the color of the text. Three, four, six and eight digit hex codes can be used. |
+/// | `[b]...[/b]` | Makes the text bold, using the [bold font](RichText::set_bold_font) if one is set. Otherwise, the glyphs will be drawn twice with a slight offset. |
+/// | `[font=name]...[/font]` | Changes the font of the text to one that has been [added](RichText::add_font) with the given name. |
+/// | `[size=2.0]...[/size]` | Scales the text, relative to the size of its font. For the best quality, use `[font]` with a font that was loaded at the desired size. |
+/// | `[icon=name]` | Inserts an [icon](RichText::add_icon) inline with the text, scaled to fit the height of the line. |
+///
+/// Tags can be nested, and each closing tag will undo the most recent tag of the same type.
+/// `[[` can be used to insert a literal `[`. Tags that are not recognized (or that have invalid
+/// values) will be displayed as text, and fonts or icons that have not been added will fall
+/// back to the default font or be skipped, respectively.
+///
+/// The colors specified via markup are multiplied by the color in the [`DrawParams`], so
+/// the text can still be tinted or faded as a whole.
+///
+/// # Performance
+///
+/// The markup is parsed when it is set, and the layout and geometry of the text is cached
+/// after the first time it is calculated, so reusing a `RichText` will be much faster than
+/// recreating it every frame.
+///
+/// # Examples
+///
+/// The [`rich_text`](https://github.com/17cupsofcoffee/tetra/blob/main/examples/rich_text.rs)
+/// example demonstrates how to style text using markup.
+#[derive(Debug, Clone)]
+pub struct RichText {
+    markup: String,
+    spans: Vec<Span>,
+    font: Font,
+    bold_font: Option<Font>,
+    fonts: HashMap<String, Font>,
+    icons: HashMap<String, (Texture, Rectangle)>,
+    max_width: Option<f32>,
+    geometry: Option<RichTextGeometry>,
+}
+
+impl RichText {
+    /// Creates a new `RichText`, with the given markup and default font.
+    pub fn new<M>(markup: M, font: Font) -> RichText
+    where
+        M: Into<String>,
+    {
+        let markup = markup.into();
+
+        RichText {
+            spans: parse_markup(&markup),
+            markup,
+            font,
+            bold_font: None,
+            fonts: HashMap::new(),
+            icons: HashMap::new(),
+            max_width: None,
+            geometry: None,
+        }
+    }
+
+    /// Creates a new wrapped `RichText`, with the given markup, default font
+    /// and maximum width.
+    ///
+    /// If a word is too long to fit, it may extend beyond the max width - use
+    /// [`get_bounds`](RichText::get_bounds) if you need to find the actual bounds
+    /// of the text.
+    pub fn wrapped<M>(markup: M, font: Font, max_width: f32) -> RichText
+    where
+        M: Into<String>,
+    {
+        let mut text = RichText::new(markup, font);
+        text.max_width = Some(max_width);
+        text
+    }
+
+    /// Draws the text to the screen (or to a canvas, if one is enabled).
+    ///
+    /// The [`pivot`](DrawParams::pivot) and flipping of the params are relative to the
+    /// [bounds](RichText::get_bounds) of the text.
+    pub fn draw<P>(&mut self, ctx: &mut Context, params: P)
+    where
+        P: Into<DrawParams>,
+    {
+        self.update_geometry(ctx);
+
+        let geometry = self
+            .geometry
+            .as_ref()
+            .expect("geometry should have been generated");
+
+        let params = params.into().resolve(geometry.bounds.unwrap_or_default());
+
+        for quad in &geometry.quads {
+            match &quad.source {
+                QuadSource::Font(index) => {
+                    let data = geometry.fonts[*index].data.borrow();
+                    draw_quad(ctx, data.texture(), quad, &params);
+                }
+
+                QuadSource::Icon(texture) => draw_quad(ctx, texture, quad, &params),
+            }
+        }
+    }
+
+    /// Returns a reference to the markup of the text.
+    pub fn markup(&self) -> &str {
+        &self.markup
+    }
+
+    /// Sets the markup of the text.
+    ///
+    /// Calling this function will cause a re-layout of the text the next time it
+    /// is rendered.
+    pub fn set_markup<M>(&mut self, markup: M)
+    where
+        M: Into<String>,
+    {
+        self.geometry.take();
+        self.markup = markup.into();
+        self.spans = parse_markup(&self.markup);
+    }
+
+    /// Gets the default font of the text.
+    pub fn font(&self) -> &Font {
+        &self.font
+    }
+
+    /// Sets the default font of the text.
+    ///
+    /// Calling this function will cause a re-layout of the text the next time it
+    /// is rendered.
+    pub fn set_font(&mut self, font: Font) {
+        self.geometry.take();
+        self.font = font;
+    }
+
+    /// Gets the font that is used for bold text, if one is set.
+    pub fn bold_font(&self) -> Option<&Font> {
+        self.bold_font.as_ref()
+    }
+
+    /// Sets the font that is used for bold text.
+    ///
+    /// If `None` is passed, bold text will be drawn using the default font, with each
+    /// glyph drawn twice. Text that has had its font changed via `[font=name]` will
+    /// always be emboldened this way.
+    ///
+    /// Calling this function will cause a re-layout of the text the next time it
+    /// is rendered.
+    pub fn set_bold_font(&mut self, font: Option<Font>) {
+        self.geometry.take();
+        self.bold_font = font;
+    }
+
+    /// Adds a font that can be used via the `[font=name]` tag.
+    ///
+    /// If a font with the same name was already added, it will be replaced.
+    ///
+    /// Calling this function will cause a re-layout of the text the next time it
+    /// is rendered.
+    pub fn add_font<N>(&mut self, name: N, font: Font)
+    where
+        N: Into<String>,
+    {
+        self.geometry.take();
+        self.fonts.insert(name.into(), font);
+    }
+
+    /// Adds an icon that can be inserted via the `[icon=name]` tag.
+    ///
+    /// If an icon with the same name was already added, it will be replaced.
+    ///
+    /// Calling this function will cause a re-layout of the text the next time it
+    /// is rendered.
+    pub fn add_icon<N>(&mut self, name: N, texture: Texture)
+    where
+        N: Into<String>,
+    {
+        let (width, height) = texture.size();
+        let region = Rectangle::new(0.0, 0.0, width as f32, height as f32);
+
+        self.add_icon_region(name, texture, region);
+    }
+
+    /// Adds an icon that can be inserted via the `[icon=name]` tag, using a region of
+    /// a texture (e.g. from a sprite sheet).
+    ///
+    /// If an icon with the same name was already added, it will be replaced.
+    ///
+    /// Calling this function will cause a re-layout of the text the next time it
+    /// is rendered.
+    pub fn add_icon_region<N>(&mut self, name: N, texture: Texture, region: Rectangle)
+    where
+        N: Into<String>,
+    {
+        self.geometry.take();
+        self.icons.insert(name.into(), (texture, region));
+    }
+
+    /// Gets the maximum width of the text, if one is set.
+    ///
+    /// If a word is too long to fit, it may extend beyond this width - use
+    /// [`get_bounds`](RichText::get_bounds) if you need to find the actual bounds
+    /// of the text.
+    pub fn max_width(&self) -> Option<f32> {
+        self.max_width
+    }
+
+    /// Sets the maximum width of the text.
+    ///
+    /// If `Some` is passed, word-wrapping will be enabled. If `None` is passed,
+    /// it will be disabled.
+    ///
+    /// If a word is too long to fit, it may extend beyond this width - use
+    /// [`get_bounds`](RichText::get_bounds) if you need to find the actual bounds
+    /// of the text.
+    ///
+    /// Calling this function will cause a re-layout of the text the next time it
+    /// is rendered.
+    pub fn set_max_width(&mut self, max_width: Option<f32>) {
+        self.geometry.take();
+        self.max_width = max_width;
+    }
+
+    /// Get the outer bounds of the text when rendered to the screen.
+    ///
+    /// If the text's layout needs calculating, this method will do so.
+    ///
+    /// Note that this method will not take into account the positioning applied to the text via [`DrawParams`].
+    pub fn get_bounds(&mut self, ctx: &mut Context) -> Option<Rectangle> {
+        self.update_geometry(ctx);
+
+        self.geometry
+            .as_ref()
+            .expect("geometry should have been generated")
+            .bounds
+    }
+
+    fn update_geometry(&mut self, ctx: &mut Context) {
+        let needs_render = match &self.geometry {
+            None => true,
+            Some(g) => g
+                .fonts
+                .iter()
+                .zip(&g.resize_counts)
+                .any(|(font, &count)| font.data.borrow().resize_count() != count),
+        };
+
+        if needs_render {
+            loop {
+                match self.try_layout(&mut ctx.device) {
+                    Ok(new_geometry) => {
+                        self.geometry = Some(new_geometry);
+                        break;
+                    }
+
+                    Err(font) => {
+                        font.data
+                            .borrow_mut()
+                            .resize(&mut ctx.device)
+                            .expect("Failed to resize font texture");
+                    }
+                }
+            }
+        }
+    }
+
+    /// Generates the geometry for the text, returning the font that needs resizing if
+    /// one of the texture atlases is out of space.
+    fn try_layout(&self, device: &mut GraphicsDevice) -> Result<RichTextGeometry, Font> {
+        let mut fonts: Vec<Font> = Vec::new();
+        let mut resolved = Vec::with_capacity(self.spans.len());
+
+        for span in &self.spans {
+            let named_font = span.style.font.as_ref().and_then(|n| self.fonts.get(n));
+
+            let (font, faux_bold) = match (named_font, &self.bold_font) {
+                (Some(font), _) => (font, span.style.bold),
+                (None, Some(bold_font)) if span.style.bold => (bold_font, false),
+                (None, _) => (&self.font, span.style.bold),
+            };
+
+            let index = match fonts.iter().position(|f| Rc::ptr_eq(&f.data, &font.data)) {
+                Some(index) => index,
+                None => {
+                    fonts.push(font.clone());
+                    fonts.len() - 1
+                }
+            };
+
+            resolved.push(ResolvedStyle {
+                font: index,
+                scale: span.style.scale,
+                color: span.style.color,
+                faux_bold,
+            });
+        }
+
+        let mut plain = String::new();
+        let mut char_spans = Vec::new();
+
+        for (index, span) in self.spans.iter().enumerate() {
+            match &span.content {
+                SpanContent::Text(text) => {
+                    plain.push_str(text);
+                    char_spans.extend(text.chars().map(|_| index));
+                }
+
+                SpanContent::Icon(_) => {
+                    plain.push(ICON_PLACEHOLDER);
+                    char_spans.push(index);
+                }
+            }
+        }
+
+        let mut layout = Layout {
+            text: self,
+            fonts: &fonts,
+            resolved: &resolved,
+            quads: Vec::new(),
+            bounds: None,
+            line: Vec::new(),
+            line_top: 0.0,
+            line_ascent: 0.0,
+            line_height: 0.0,
+            cursor_x: 0.0,
+            last_glyph: None,
+        };
+
+        let mut wrap = WordWrap::new(self.max_width);
+
+        let mut char_index = 0;
+
+        for (word, _) in UnicodeLineBreaks::new(&plain) {
+            let word_spans = &char_spans[char_index..char_index + word.chars().count()];
+            char_index += word_spans.len();
+
+            let wrapped = wrap.wrap_before_word(layout.cursor_x, || {
+                measure_word(
+                    word.chars().zip(word_spans.iter().copied()),
+                    |ch, span, last| layout.char_metrics(ch, span, last),
+                )
+            });
+
+            if wrapped {
+                layout.finish_line(device)?;
+            }
+
+            for (ch, &span) in word.chars().zip(word_spans) {
+                if ch == '\n' {
+                    wrap.new_line();
+                }
+
+                layout.push_char(device, ch, span)?;
+            }
+        }
+
+        layout.finish_line(device)?;
+
+        let quads = layout.quads;
+        let bounds = layout.bounds;
+        let resize_counts = fonts
+            .iter()
+            .map(|f| f.data.borrow().resize_count())
+            .collect();
+
+        Ok(RichTextGeometry {
+            quads,
+            bounds,
+            fonts,
+            resize_counts,
+        })
+    }
+}
+
+/// Pushes a quad from the rich text geometry to the batcher.
+fn draw_quad(ctx: &mut Context, texture: &Texture, quad: &RichQuad, params: &DrawParams) {
+    graphics::set_texture(ctx, texture);
+
+    let (texture_width, texture_height) = texture.size();
+    let (texture_width, texture_height) = (texture_width as f32, texture_height as f32);
+
+    let params = DrawParams {
+        color: params.color * quad.color,
+        ..params.clone()
+    };
+
+    let push = |ctx: &mut Context, x: f32| {
+        graphics::push_quad(
+            ctx,
+            x,
+            quad.bounds.y,
+            x + quad.bounds.width,
+            quad.bounds.bottom(),
+            quad.region.x / texture_width,
+            quad.region.y / texture_height,
+            quad.region.right() / texture_width,
+            quad.region.bottom() / texture_height,
+            &params,
+        );
+    };
+
+    push(ctx, quad.bounds.x);
+
+    if quad.bold_offset > 0.0 {
+        push(ctx, quad.bounds.x + quad.bold_offset);
+    }
+}
+
+/// The styling of a span, after the fonts have been looked up.
+struct ResolvedStyle {
+    font: usize,
+    scale: f32,
+    color: Color,
+    faux_bold: bool,
+}
+
+/// An item that has been positioned on a line, but not yet rasterized.
+enum LineItem {
+    Glyph { ch: char, x: f32, span: usize },
+
+    Icon { x: f32, width: f32, span: usize },
+}
+
+/// The state used while laying out rich text.
+struct Layout<'a> {
+    text: &'a RichText,
+    fonts: &'a [Font],
+    resolved: &'a [ResolvedStyle],
+
+    quads: Vec<RichQuad>,
+    bounds: Option<Rectangle>,
+
+    line: Vec<LineItem>,
+    line_top: f32,
+    line_ascent: f32,
+    line_height: f32,
+
+    cursor_x: f32,
+    last_glyph: Option<(char, usize)>,
+}
+
+impl Layout<'_> {
+    /// Returns the amount of kerning to apply before the given character, and its advance.
+    fn char_metrics(&self, ch: char, span: usize, last_glyph: Option<(char, usize)>) -> (f32, f32) {
+        let style = &self.resolved[span];
+
+        match &self.text.spans[span].content {
+            SpanContent::Icon(name) => {
+                let width = self
+                    .text
+                    .icons
+                    .get(name)
+                    .map(|(_, region)| self.icon_width(span, region))
+                    .unwrap_or(0.0);
+
+                (0.0, width)
+            }
+
+            SpanContent::Text(_) => {
+                let data = self.fonts[style.font].data.borrow();
+
+                let kerning = match last_glyph {
+                    // Kerning is only applied between glyphs with the same font and size.
+                    Some((last, last_span))
+                        if matches!(self.text.spans[last_span].content, SpanContent::Text(_))
+                            && self.resolved[last_span].font == style.font
+                            && self.resolved[last_span].scale == style.scale =>
+                    {
+                        data.kerning(last, ch) * style.scale
+                    }
+                    _ => 0.0,
+                };
+
+                (kerning, data.advance(ch) * style.scale)
+            }
+        }
+    }
+
+    /// Returns the width of an icon, when scaled to fit the line height of its span.
+    fn icon_width(&self, span: usize, region: &Rectangle) -> f32 {
+        let (_, height) = self.span_metrics(span);
+
+        if region.height > 0.0 {
+            region.width / region.height * height
+        } else {
+            0.0
+        }
+    }
+
+    /// Returns the ascent and line height of the given span.
+    fn span_metrics(&self, span: usize) -> (f32, f32) {
+        let style = &self.resolved[span];
+        let data = self.fonts[style.font].data.borrow();
+
+        (
+            data.ascent() * style.scale,
+            data.line_height() * style.scale,
+        )
+    }
+
+    /// Adds a character to the current line.
+    fn push_char(
+        &mut self,
+        device: &mut GraphicsDevice,
+        ch: char,
+        span: usize,
+    ) -> Result<(), Font> {
+        let is_icon = matches!(self.text.spans[span].content, SpanContent::Icon(_));
+
+        if !is_icon && ch.is_control() {
+            if ch == '\n' {
+                self.expand_line(span);
+                self.finish_line(device)?;
+            }
+
+            return Ok(());
+        }
+
+        self.expand_line(span);
+
+        let (kerning, advance) = self.char_metrics(ch, span, self.last_glyph);
+        self.cursor_x += kerning;
+
+        if is_icon {
+            self.line.push(LineItem::Icon {
+                x: self.cursor_x,
+                width: advance,
+                span,
+            });
+
+            self.last_glyph = None;
+        } else {
+            self.line.push(LineItem::Glyph {
+                ch,
+                x: self.cursor_x,
+                span,
+            });
+
+            self.last_glyph = Some((ch, span));
+        }
+
+        self.cursor_x += advance;
+
+        Ok(())
+    }
+
+    /// Expands the current line to fit the given span's font.
+    fn expand_line(&mut self, span: usize) {
+        let (ascent, line_height) = self.span_metrics(span);
+
+        self.line_ascent = self.line_ascent.max(ascent);
+        self.line_height = self.line_height.max(line_height);
+    }
+
+    /// Rasterizes the items on the current line, and moves on to the next line.
+    ///
+    /// The rasterization is deferred until the end of the line, as the baseline depends
+    /// on the tallest font that is used on the line.
+    fn finish_line(&mut self, device: &mut GraphicsDevice) -> Result<(), Font> {
+        let baseline = self.line_top + self.line_ascent;
+
+        for item in std::mem::take(&mut self.line) {
+            let quad = match item {
+                LineItem::Glyph { ch, x, span } => {
+                    self.rasterize_glyph(device, ch, x, baseline, span)?
+                }
+                LineItem::Icon { x, width, span } => self.icon_quad(x, width, baseline, span),
+            };
+
+            if let Some(quad) = quad {
+                let mut bounds = quad.bounds;
+                bounds.width += quad.bold_offset;
+
+                self.bounds = Some(match self.bounds {
+                    Some(existing) => bounds.combine(&existing),
+                    None => bounds,
+                });
+
+                self.quads.push(quad);
+            }
+        }
+
+        self.line_top += self.line_height;
+        self.line_ascent = 0.0;
+        self.line_height = 0.0;
+        self.cursor_x = 0.0;
+        self.last_glyph = None;
+
+        Ok(())
+    }
+
+    fn rasterize_glyph(
+        &self,
+        device: &mut GraphicsDevice,
+        ch: char,
+        x: f32,
+        baseline: f32,
+        span: usize,
+    ) -> Result<Option<RichQuad>, Font> {
+        let style = &self.resolved[span];
+        let font = &self.fonts[style.font];
+        let mut data = font.data.borrow_mut();
+
+        let to_font_error = |_: CacheError| font.clone();
+
+        let bounds = if style.scale == 1.0 {
+            data.rasterize_char(device, ch, Vec2::new(x, baseline))
+                .map_err(to_font_error)?
+                .map(|quad| quad_bounds(&quad, Vec2::zero(), 1.0))
+        } else {
+            // Subpixel positioning is skipped for scaled glyphs, as the scaling will
+            // make the offset inaccurate anyway.
+            data.rasterize_char(device, ch, Vec2::zero())
+                .map_err(to_font_error)?
+                .map(|quad| quad_bounds(&quad, Vec2::new(x, baseline), style.scale))
+        };
+
+        Ok(bounds.map(|(bounds, region)| RichQuad {
+            source: QuadSource::Font(style.font),
+            bounds,
+            region,
+            color: style.color,
+            bold_offset: if style.faux_bold { style.scale } else { 0.0 },
+        }))
+    }
+
+    fn icon_quad(&self, x: f32, width: f32, baseline: f32, span: usize) -> Option<RichQuad> {
+        let name = match &self.text.spans[span].content {
+            SpanContent::Icon(name) => name,
+            SpanContent::Text(_) => return None,
+        };
+
+        let (texture, region) = self.text.icons.get(name)?;
+        let (ascent, height) = self.span_metrics(span);
+        let style = &self.resolved[span];
+
+        Some(RichQuad {
+            source: QuadSource::Icon(texture.clone()),
+            bounds: Rectangle::new(x, baseline - ascent, width, height),
+            region: *region,
+            color: style.color,
+            bold_offset: 0.0,
+        })
+    }
+}
+
+/// Returns the bounds and texture region of a cached glyph, offset and scaled.
+fn quad_bounds(quad: &TextQuad, offset: Vec2<f32>, scale: f32) -> (Rectangle, Rectangle) {
+    let bounds = Rectangle::new(
+        offset.x + quad.position.x * scale,
+        offset.y + quad.position.y * scale,
+        quad.region.width * scale,
+        quad.region.height * scale,
+    );
+
+    (bounds, quad.region)
+}
+
+/// Where the texture for a quad of rich text comes from.
+#[derive(Debug, Clone)]
+enum QuadSource {
+    /// The texture atlas of one of the fonts used by the text.
+    Font(usize),
+
+    /// The texture of an icon.
+    Icon(Texture),
+}
+
+/// An individual quad within a `RichTextGeometry`.
+#[derive(Debug, Clone)]
+struct RichQuad {
+    source: QuadSource,
+    bounds: Rectangle,
+    region: Rectangle,
+    color: Color,
+
+    /// If non-zero, the quad will be drawn a second time with this horizontal offset,
+    /// to simulate a bold font.
+    bold_offset: f32,
+}
+
+/// The geometry that can be used to render a piece of rich text.
+#[derive(Debug, Clone)]
+struct RichTextGeometry {
+    quads: Vec<RichQuad>,
+    bounds: Option<Rectangle>,
+
+    /// The fonts used by the text, along with their resize counts when the geometry
+    /// was generated. If any of them have changed, the geometry is stale.
+    fonts: Vec<Font>,
+    resize_counts: Vec<usize>,
+}
+
+/// The styling applied to a span of rich text.
+#[derive(Debug, Clone, PartialEq)]
+struct Style {
+    color: Color,
+    bold: bool,
+    font: Option<String>,
+    scale: f32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum SpanContent {
+    Text(String),
+    Icon(String),
+}
+
+/// A span of rich text, with a single style.
+#[derive(Debug, Clone, PartialEq)]
+struct Span {
+    content: SpanContent,
+    style: Style,
+}
+
+/// Parses rich text markup into a list of styled spans.
+fn parse_markup(markup: &str) -> Vec<Span> {
+    let mut parser = MarkupParser::default();
+    let mut rest = markup;
+
+    while let Some(start) = rest.find('[') {
+        parser.text.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        if let Some(after) = rest.strip_prefix("[[") {
+            parser.text.push('[');
+            rest = after;
+            continue;
+        }
+
+        let end = match rest.find(']') {
+            Some(end) => end,
+            None => break,
+        };
+
+        if parser.apply_tag(&rest[1..end]) {
+            rest = &rest[end + 1..];
+        } else {
+            // Unrecognized tags are treated as plain text.
+            parser.text.push('[');
+            rest = &rest[1..];
+        }
+    }
+
+    parser.text.push_str(rest);
+    parser.flush();
+
+    parser.spans
+}
+
+#[derive(Default)]
+struct MarkupParser {
+    spans: Vec<Span>,
+    text: String,
+    colors: Vec<Color>,
+    fonts: Vec<String>,
+    scales: Vec<f32>,
+    bold: usize,
+}
+
+impl MarkupParser {
+    /// Applies a tag, returning `false` if it was not recognized.
+    fn apply_tag(&mut self, tag: &str) -> bool {
+        let (name, value) = match tag.split_once('=') {
+            Some((name, value)) => (name.trim(), Some(value.trim())),
+            None => (tag.trim(), None),
+        };
+
+        match (name, value) {
+            ("b", None) => {
+                self.flush();
+                self.bold += 1;
+            }
+
+            ("/b", None) if self.bold > 0 => {
+                self.flush();
+                self.bold -= 1;
+            }
+
+            ("color", Some(value)) => match parse_color(value) {
+                Some(color) => {
+                    self.flush();
+                    self.colors.push(color);
+                }
+                None => return false,
+            },
+
+            ("/color", None) if !self.colors.is_empty() => {
+                self.flush();
+                self.colors.pop();
+            }
+
+            ("font", Some(value)) if !value.is_empty() => {
+                self.flush();
+                self.fonts.push(value.to_owned());
+            }
+
+            ("/font", None) if !self.fonts.is_empty() => {
+                self.flush();
+                self.fonts.pop();
+            }
+
+            ("size", Some(value)) => match value.parse::<f32>() {
+                Ok(scale) if scale > 0.0 && scale.is_finite() => {
+                    self.flush();
+                    self.scales.push(scale);
+                }
+                _ => return false,
+            },
+
+            ("/size", None) if !self.scales.is_empty() => {
+                self.flush();
+                self.scales.pop();
+            }
+
+            ("icon", Some(value)) if !value.is_empty() => {
+                self.flush();
+
+                self.spans.push(Span {
+                    content: SpanContent::Icon(value.to_owned()),
+                    style: self.style(),
+                });
+            }
+
+            _ => return false,
+        }
+
+        true
+    }
+
+    /// Adds any pending text as a span with the current style.
+    fn flush(&mut self) {
+        if !self.text.is_empty() {
+            let text = std::mem::take(&mut self.text);
+
+            self.spans.push(Span {
+                content: SpanContent::Text(text),
+                style: self.style(),
+            });
+        }
+    }
+
+    fn style(&self) -> Style {
+        Style {
+            color: self.colors.last().copied().unwrap_or(Color::WHITE),
+            bold: self.bold > 0,
+            font: self.fonts.last().cloned(),
+            scale: self.scales.last().copied().unwrap_or(1.0),
+        }
+    }
+}
+
+/// Parses a hex color, allowing the three and four digit shorthands.
+fn parse_color(value: &str) -> Option<Color> {
+    let hex = value.strip_prefix('#').unwrap_or(value);
+
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    match hex.len() {
+        3 | 4 => {
+            let expanded: String = hex.chars().flat_map(|c| [c, c]).collect();
+            Color::try_hex(&expanded).ok()
+        }
+        6 | 8 => Color::try_hex(hex).ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(content: &str, style: Style) -> Span {
+        Span {
+            content: SpanContent::Text(content.to_owned()),
+            style,
+        }
+    }
+
+    fn plain() -> Style {
+        Style {
+            color: Color::WHITE,
+            bold: false,
+            font: None,
+            scale: 1.0,
+        }
+    }
+
+    #[test]
+    fn parse_nested_tags() {
+        let spans = parse_markup("Press [b][color=#ff0]A[/color] now[/b]!");
+
+        let bold = Style {
+            bold: true,
+            ..plain()
+        };
+
+        let yellow = Style {
+            color: Color::rgb(1.0, 1.0, 0.0),
+            ..bold.clone()
+        };
+
+        assert_eq!(
+            spans,
+            vec![
+                text("Press ", plain()),
+                text("A", yellow),
+                text(" now", bold),
+                text("!", plain()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_fonts_sizes_and_icons() {
+        let spans = parse_markup("[font=title][size=2]Hi[/size][icon=button_a][/font]");
+
+        let title = Style {
+            font: Some("title".to_owned()),
+            ..plain()
+        };
+
+        assert_eq!(
+            spans,
+            vec![
+                text(
+                    "Hi",
+                    Style {
+                        scale: 2.0,
+                        ..title.clone()
+                    }
+                ),
+                Span {
+                    content: SpanContent::Icon("button_a".to_owned()),
+                    style: title,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_invalid_tags_as_text() {
+        let spans = parse_markup("[[b] [unknown] [color=red] [/b] [size=0] [b");
+
+        assert_eq!(
+            spans,
+            vec![text("[b] [unknown] [color=red] [/b] [size=0] [b", plain())]
+        );
+    }
+
+    #[test]
+    fn parse_colors() {
+        assert_eq!(parse_color("#f00"), Some(Color::rgb8(255, 0, 0)));
+        assert_eq!(parse_color("0f08"), Some(Color::rgba8(0, 255, 0, 136)));
+        assert_eq!(parse_color("#336699"), Some(Color::rgb8(51, 102, 153)));
+        assert_eq!(
+            parse_color("#33669980"),
+            Some(Color::rgba8(51, 102, 153, 128))
+        );
+        assert_eq!(parse_color("#12345"), None);
+        assert_eq!(parse_color("#ggg"), None);
+    }
+}
@@ -211,21 +211,15 @@ impl Texture {
     where
         P: Into<DrawParams>,
     {
-        let params = params.into();
+        let width = self.width() as f32;
+        let height = self.height() as f32;
+
+        let params = params
+            .into()
+            .resolve(Rectangle::new(0.0, 0.0, width, height));
 
         graphics::set_texture(ctx, self);
-        graphics::push_quad(
-            ctx,
-            0.0,
-            0.0,
-            self.width() as f32,
-            self.height() as f32,
-            0.0,
-            0.0,
-            1.0,
-            1.0,
-            &params,
-        );
+        graphics::push_quad(ctx, 0.0, 0.0, width, height, 0.0, 0.0, 1.0, 1.0, &params);
     }
 
     /// Draws a region of the texture to the screen (or to a canvas, if one is enabled).
@@ -233,7 +227,9 @@ impl Texture {
     where
         P: Into<DrawParams>,
     {
-        let params = params.into();
+        let params = params
+            .into()
+            .resolve(Rectangle::new(0.0, 0.0, region.width, region.height));
 
         let texture_width = self.width() as f32;
         let texture_height = self.height() as f32;
@@ -255,6 +251,10 @@ impl Texture {
 
     /// Draws a region of the texture by splitting it into nine slices, allowing it to be stretched or
     /// squashed without distorting the borders.
+    ///
+    /// The way that the edges and center are resized is controlled by the
+    /// [`edge_mode`](NineSlice::edge_mode) and [`center_mode`](NineSlice::center_mode) of the
+    /// configuration.
     pub fn draw_nine_slice<P>(
         &self,
         ctx: &mut Context,
@@ -265,7 +265,9 @@ impl Texture {
     ) where
         P: Into<DrawParams>,
     {
-        let params = params.into();
+        let params = params
+            .into()
+            .resolve(Rectangle::new(0.0, 0.0, width, height));
 
         let texture_width = self.width() as f32;
         let texture_height = self.height() as f32;
@@ -288,31 +290,75 @@ impl Texture {
         let u4 = (config.region.x + config.region.width) / texture_width;
         let v4 = (config.region.y + config.region.height) / texture_height;
 
+        let center_width = config.region.width - config.left - config.right;
+        let center_height = config.region.height - config.top - config.bottom;
+
+        let edge = config.edge_mode;
+        let center = config.center_mode;
+        let stretch = NineSliceMode::Stretch;
+
         graphics::set_texture(ctx, self);
 
         // Top left
         graphics::push_quad(ctx, x1, y1, x2, y2, u1, v1, u2, v2, &params);
 
         // Top
-        graphics::push_quad(ctx, x2, y1, x3, y2, u2, v1, u3, v2, &params);
+        push_tiled(
+            ctx,
+            [x2, y1, x3, y2],
+            [u2, v1, u3, v2],
+            [center_width, config.top],
+            [edge, stretch],
+            &params,
+        );
 
         // Top right
         graphics::push_quad(ctx, x3, y1, x4, y2, u3, v1, u4, v2, &params);
 
         // Left
-        graphics::push_quad(ctx, x1, y2, x2, y3, u1, v2, u2, v3, &params);
+        push_tiled(
+            ctx,
+            [x1, y2, x2, y3],
+            [u1, v2, u2, v3],
+            [config.left, center_height],
+            [stretch, edge],
+            &params,
+        );
 
         // Center
-        graphics::push_quad(ctx, x2, y2, x3, y3, u2, v2, u3, v3, &params);
+        if config.draw_center {
+            push_tiled(
+                ctx,
+                [x2, y2, x3, y3],
+                [u2, v2, u3, v3],
+                [center_width, center_height],
+                [center, center],
+                &params,
+            );
+        }
 
         // Right
-        graphics::push_quad(ctx, x3, y2, x4, y3, u3, v2, u4, v3, &params);
+        push_tiled(
+            ctx,
+            [x3, y2, x4, y3],
+            [u3, v2, u4, v3],
+            [config.right, center_height],
+            [stretch, edge],
+            &params,
+        );
 
         // Bottom left
         graphics::push_quad(ctx, x1, y3, x2, y4, u1, v3, u2, v4, &params);
 
         // Bottom
-        graphics::push_quad(ctx, x2, y3, x3, y4, u2, v3, u3, v4, &params);
+        push_tiled(
+            ctx,
+            [x2, y3, x3, y4],
+            [u2, v3, u3, v4],
+            [center_width, config.bottom],
+            [edge, stretch],
+            &params,
+        );
 
         // Bottom right
         graphics::push_quad(ctx, x3, y3, x4, y4, u3, v3, u4, v4, &params);
@@ -472,11 +518,27 @@ pub enum FilterMode {
 ///
 /// This can be used with [`Texture::draw_nine_slice`] to easily draw things like UI panels.
 ///
+/// By default, the edges and center of the texture will be stretched to fill the space.
+/// They can also be tiled, which usually looks better for pixel art - see [`NineSliceMode`]
+/// for the available options.
+///
+/// # Serde
+///
+/// Serialization and deserialization of this type (via [Serde](https://serde.rs/))
+/// can be enabled via the `serde_support` feature. This allows nine slice layouts to be
+/// defined in data files (e.g. alongside a texture atlas). Any fields other than
+/// `region` and the border offsets can be omitted, in which case they will use the
+/// same defaults as [`NineSlice::new`].
+///
 /// # Examples
 ///
 /// The [`nineslice`](https://github.com/17cupsofcoffee/tetra/blob/main/examples/nineslice.rs)
 /// example demonstrates how to draw a `NineSlice` panel.
 #[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde_support",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub struct NineSlice {
     /// The region of the texture that should be used.
     pub region: Rectangle,
@@ -492,6 +554,26 @@ pub struct NineSlice {
 
     /// The offset of the border on the bottom side.
     pub bottom: f32,
+
+    /// How the edges should be resized to fit the space.
+    ///
+    /// The top and bottom edges will be resized horizontally, and the left and right
+    /// edges will be resized vertically. Defaults to [`NineSliceMode::Stretch`].
+    #[cfg_attr(feature = "serde_support", serde(default))]
+    pub edge_mode: NineSliceMode,
+
+    /// How the center should be resized to fit the space.
+    ///
+    /// Defaults to [`NineSliceMode::Stretch`].
+    #[cfg_attr(feature = "serde_support", serde(default))]
+    pub center_mode: NineSliceMode,
+
+    /// Whether the center should be drawn.
+    ///
+    /// Setting this to `false` is useful for drawing frames and borders around
+    /// other content. Defaults to `true`.
+    #[cfg_attr(feature = "serde_support", serde(default = "default_draw_center"))]
+    pub draw_center: bool,
 }
 
 impl NineSlice {
@@ -503,17 +585,211 @@ impl NineSlice {
             right,
             top,
             bottom,
+            edge_mode: NineSliceMode::Stretch,
+            center_mode: NineSliceMode::Stretch,
+            draw_center: true,
         }
     }
 
     /// Creates a new nine slice configuration, using the same offset for all edges.
     pub fn with_border(region: Rectangle, border: f32) -> NineSlice {
-        NineSlice {
-            region,
-            left: border,
-            right: border,
-            top: border,
-            bottom: border,
+        NineSlice::new(region, border, border, border, border)
+    }
+
+    /// Sets how both the edges and the center should be resized to fit the space.
+    pub fn mode(mut self, mode: NineSliceMode) -> NineSlice {
+        self.edge_mode = mode;
+        self.center_mode = mode;
+        self
+    }
+
+    /// Sets how the edges should be resized to fit the space.
+    pub fn edge_mode(mut self, mode: NineSliceMode) -> NineSlice {
+        self.edge_mode = mode;
+        self
+    }
+
+    /// Sets how the center should be resized to fit the space.
+    pub fn center_mode(mut self, mode: NineSliceMode) -> NineSlice {
+        self.center_mode = mode;
+        self
+    }
+
+    /// Sets whether the center should be drawn.
+    pub fn draw_center(mut self, draw_center: bool) -> NineSlice {
+        self.draw_center = draw_center;
+        self
+    }
+}
+
+#[cfg(feature = "serde_support")]
+fn default_draw_center() -> bool {
+    true
+}
+
+/// Ways that the slices of a [`NineSlice`] can be resized to fit the space.
+///
+/// These are similar to the options for `border-image-repeat` in CSS.
+///
+/// If a slice is smaller than a texel, or would have to be repeated more than 1024 times
+/// along an axis, it will be stretched instead, regardless of the mode.
+///
+/// # Serde
+///
+/// Serialization and deserialization of this type (via [Serde](https://serde.rs/))
+/// can be enabled via the `serde_support` feature.
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(
+    feature = "serde_support",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum NineSliceMode {
+    /// The slice will be stretched to fill the space.
+    #[default]
+    Stretch,
+
+    /// The slice will be repeated to fill the space, starting from the top-left. If the
+    /// space is not an exact multiple of the slice's size, the last repetition will be
+    /// cut off.
+    Repeat,
+
+    /// The slice will be repeated to fill the space, with each repetition scaled slightly
+    /// so that a whole number of them fit exactly.
+    Round,
+
+    /// Works the same as `Repeat`, but every other repetition is mirrored, so that the
+    /// edges of each repetition line up seamlessly.
+    Mirror,
+}
+
+/// Pushes a slice of a nine slice, tiled along each axis according to the given modes.
+fn push_tiled(
+    ctx: &mut Context,
+    [x1, y1, x2, y2]: [f32; 4],
+    [u1, v1, u2, v2]: [f32; 4],
+    [source_width, source_height]: [f32; 2],
+    [mode_x, mode_y]: [NineSliceMode; 2],
+    params: &DrawParams,
+) {
+    let columns = tile_segments(x2 - x1, source_width, mode_x);
+    let rows = tile_segments(y2 - y1, source_height, mode_y);
+
+    for &(row_start, row_end, row_t1, row_t2) in &rows {
+        for &(column_start, column_end, column_t1, column_t2) in &columns {
+            graphics::push_quad(
+                ctx,
+                x1 + column_start,
+                y1 + row_start,
+                x1 + column_end,
+                y1 + row_end,
+                u1 + (u2 - u1) * column_t1,
+                v1 + (v2 - v1) * row_t1,
+                u1 + (u2 - u1) * column_t2,
+                v1 + (v2 - v1) * row_t2,
+                params,
+            );
+        }
+    }
+}
+
+/// The maximum number of times that a slice will be tiled along an axis, before it falls
+/// back to being stretched.
+const MAX_TILE_SEGMENTS: f32 = 1024.0;
+
+/// Splits a span of the given length into segments of the source length, returning the
+/// start and end of each segment, along with how far through the source they should
+/// start and end sampling (from 0.0 to 1.0).
+fn tile_segments(length: f32, source: f32, mode: NineSliceMode) -> Vec<(f32, f32, f32, f32)> {
+    if length <= 0.0 {
+        return Vec::new();
+    }
+
+    // Tiles smaller than a texel can't be displayed meaningfully, and tiling a huge span
+    // would generate an enormous number of quads, so both fall back to stretching.
+    if mode == NineSliceMode::Stretch || source < 1.0 || length / source > MAX_TILE_SEGMENTS {
+        return vec![(0.0, length, 0.0, 1.0)];
+    }
+
+    if mode == NineSliceMode::Round {
+        let count = (length / source).round().max(1.0) as usize;
+        let size = length / count as f32;
+
+        return (0..count)
+            .map(|i| (i as f32 * size, (i + 1) as f32 * size, 0.0, 1.0))
+            .collect();
+    }
+
+    let count = (length / source).ceil() as usize;
+
+    (0..count)
+        .map(|i| {
+            let start = i as f32 * source;
+            let end = (start + source).min(length);
+            let fraction = (end - start) / source;
+
+            if mode == NineSliceMode::Mirror && i % 2 == 1 {
+                (start, end, 1.0, 1.0 - fraction)
+            } else {
+                (start, end, 0.0, fraction)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nine_slice_tile_segments() {
+        assert_eq!(
+            tile_segments(10.0, 4.0, NineSliceMode::Stretch),
+            vec![(0.0, 10.0, 0.0, 1.0)]
+        );
+
+        assert_eq!(
+            tile_segments(10.0, 4.0, NineSliceMode::Repeat),
+            vec![
+                (0.0, 4.0, 0.0, 1.0),
+                (4.0, 8.0, 0.0, 1.0),
+                (8.0, 10.0, 0.0, 0.5)
+            ]
+        );
+
+        assert_eq!(
+            tile_segments(10.0, 4.0, NineSliceMode::Mirror),
+            vec![
+                (0.0, 4.0, 0.0, 1.0),
+                (4.0, 8.0, 1.0, 0.0),
+                (8.0, 10.0, 0.0, 0.5)
+            ]
+        );
+
+        assert_eq!(
+            tile_segments(9.0, 4.0, NineSliceMode::Round),
+            vec![(0.0, 4.5, 0.0, 1.0), (4.5, 9.0, 0.0, 1.0)]
+        );
+
+        assert!(tile_segments(0.0, 4.0, NineSliceMode::Repeat).is_empty());
+    }
+
+    #[test]
+    fn nine_slice_tiny_source_stretches() {
+        for mode in [
+            NineSliceMode::Repeat,
+            NineSliceMode::Round,
+            NineSliceMode::Mirror,
+        ] {
+            assert_eq!(
+                tile_segments(1000.0, 0.001, mode),
+                vec![(0.0, 1000.0, 0.0, 1.0)]
+            );
+
+            assert_eq!(
+                tile_segments(1_000_000.0, 4.0, mode),
+                vec![(0.0, 1_000_000.0, 0.0, 1.0)]
+            );
         }
     }
 }
@@ -0,0 +1,172 @@
+use super::{Camera, Rectangle};
+use crate::graphics;
+use crate::input;
+use crate::math::{Mat4, Vec2};
+use crate::Context;
+
+/// A region of the screen (or a canvas), paired with a [`Camera`] that controls what is
+/// displayed inside of it.
+///
+/// Viewports can be used to implement split-screen multiplayer, minimaps, or anything
+/// else where multiple views of the world need to be displayed at the same time. When a
+/// viewport is activated via [`graphics::set_viewport`](set_viewport), rendering will be
+/// clipped to its rectangle, and the camera's view will be projected into it.
+///
+/// The camera's viewport size is kept in sync with the size of the viewport's rectangle,
+/// so you should use [`set_rect`](Self::set_rect) rather than modifying the camera's
+/// size directly.
+///
+/// # Examples
+///
+/// The [`split_screen`](https://github.com/17cupsofcoffee/tetra/blob/main/examples/split_screen.rs)
+/// example demonstrates how viewports can be used to render a scene from two
+/// different points of view.
+#[derive(Debug, Clone)]
+pub struct Viewport {
+    rect: Rectangle<i32>,
+    camera: Camera,
+}
+
+impl Viewport {
+    /// Creates a new viewport, with a camera that matches the size of the given rectangle.
+    ///
+    /// The rectangle is in screen co-ordinates (or canvas co-ordinates, if the viewport
+    /// will be used while rendering to a canvas).
+    pub fn new(rect: Rectangle<i32>) -> Viewport {
+        Viewport::with_camera(rect, Camera::new(rect.width as f32, rect.height as f32))
+    }
+
+    /// Creates a new viewport, using an existing camera.
+    ///
+    /// The camera's viewport size will be changed to match the size of the given rectangle.
+    pub fn with_camera(rect: Rectangle<i32>, mut camera: Camera) -> Viewport {
+        camera.set_viewport_size(rect.width as f32, rect.height as f32);
+        camera.update();
+
+        Viewport { rect, camera }
+    }
+
+    /// Returns the area of the screen that the viewport covers.
+    pub fn rect(&self) -> Rectangle<i32> {
+        self.rect
+    }
+
+    /// Sets the area of the screen that the viewport covers.
+    ///
+    /// The camera's viewport size will be changed to match.
+    pub fn set_rect(&mut self, rect: Rectangle<i32>) {
+        self.rect = rect;
+
+        self.camera
+            .set_viewport_size(rect.width as f32, rect.height as f32);
+        self.camera.update();
+    }
+
+    /// Returns a reference to the viewport's camera.
+    pub fn camera(&self) -> &Camera {
+        &self.camera
+    }
+
+    /// Returns a mutable reference to the viewport's camera.
+    pub fn camera_mut(&mut self) -> &mut Camera {
+        &mut self.camera
+    }
+
+    /// Returns the transformation matrix for the viewport.
+    ///
+    /// This is the camera's matrix, offset to the position of the viewport's rectangle.
+    pub fn as_matrix(&self) -> Mat4<f32> {
+        let mut matrix = self.camera.as_matrix();
+        matrix.translate_2d(Vec2::new(self.rect.x as f32, self.rect.y as f32));
+        matrix
+    }
+
+    /// Returns `true` if the given point (in screen co-ordinates) is inside the viewport.
+    pub fn contains_point(&self, point: Vec2<f32>) -> bool {
+        let rect = Rectangle::new(
+            self.rect.x as f32,
+            self.rect.y as f32,
+            self.rect.width as f32,
+            self.rect.height as f32,
+        );
+
+        rect.contains_point(point)
+    }
+
+    /// Projects a point from screen co-ordinates to world co-ordinates, as seen through
+    /// this viewport.
+    ///
+    /// If you are rendering via a [`ScreenScaler`](crate::graphics::scaling::ScreenScaler),
+    /// project the point with the scaler first.
+    pub fn project(&self, point: Vec2<f32>) -> Vec2<f32> {
+        self.camera
+            .project(point - Vec2::new(self.rect.x as f32, self.rect.y as f32))
+    }
+
+    /// Projects a point from world co-ordinates to screen co-ordinates, as seen through
+    /// this viewport.
+    pub fn unproject(&self, point: Vec2<f32>) -> Vec2<f32> {
+        self.camera.unproject(point) + Vec2::new(self.rect.x as f32, self.rect.y as f32)
+    }
+
+    /// Returns the mouse's position in world co-ordinates, as seen through this viewport.
+    ///
+    /// This is a shortcut for calling [`project(input::get_mouse_position(ctx))`](Self::project).
+    /// As such, it does not take into account any other transformations being made to
+    /// the view (e.g. screen scaling).
+    pub fn mouse_position(&self, ctx: &Context) -> Vec2<f32> {
+        self.project(input::get_mouse_position(ctx))
+    }
+}
+
+/// Sets the renderer to draw through the specified viewport.
+///
+/// This sets the [scissor rectangle](graphics::set_scissor) to the viewport's rectangle, and
+/// the [transform matrix](graphics::set_transform_matrix) to the viewport's matrix. This will
+/// trigger a [`flush`](graphics::flush) to the graphics hardware.
+///
+/// As this function uses the scissor rectangle, calling [`graphics::clear`] will only
+/// clear the area inside the viewport.
+pub fn set_viewport(ctx: &mut Context, viewport: &Viewport) {
+    graphics::set_logical_scissor(ctx, viewport.rect);
+    graphics::set_transform_matrix(ctx, viewport.as_matrix());
+}
+
+/// Sets the renderer back to drawing to the full screen (or canvas), after calling
+/// [`set_viewport`].
+///
+/// This disables the scissor rectangle and resets the transform matrix.
+pub fn reset_viewport(ctx: &mut Context) {
+    graphics::reset_scissor(ctx);
+    graphics::reset_transform_matrix(ctx);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn viewport_projections() {
+        let mut viewport = Viewport::new(Rectangle::new(400, 0, 400, 600));
+        viewport.camera_mut().position = Vec2::new(100.0, 100.0);
+        viewport.camera_mut().update();
+
+        // The center of the viewport should map to the camera's position.
+        assert_eq!(
+            viewport.project(Vec2::new(600.0, 300.0)),
+            Vec2::new(100.0, 100.0)
+        );
+        assert_eq!(
+            viewport.unproject(Vec2::new(100.0, 100.0)),
+            Vec2::new(600.0, 300.0)
+        );
+
+        let world = Vec2::new(100.0, 100.0);
+        let screen = viewport.as_matrix() * world.with_z(0.0).with_w(1.0);
+
+        assert_eq!(Vec2::new(screen.x, screen.y), Vec2::new(600.0, 300.0));
+
+        assert!(viewport.contains_point(Vec2::new(400.0, 0.0)));
+        assert!(!viewport.contains_point(Vec2::new(399.0, 0.0)));
+    }
+}
@@ -2,7 +2,7 @@ mod device_gl;
 mod window_sdl;
 
 pub use device_gl::{
-    GraphicsDevice, RawCanvas, RawIndexBuffer, RawRenderbuffer, RawShader, RawTexture,
-    RawVertexBuffer,
+    GraphicsDevice, RawCanvas, RawIndexBuffer, RawQuery, RawRenderbuffer, RawShader, RawTexture,
+    RawUniformBuffer, RawVertexBuffer,
 };
 pub use window_sdl::{handle_events, Window};
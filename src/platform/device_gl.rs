@@ -1,4 +1,4 @@
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
 use std::mem;
 use std::rc::Rc;
 use std::slice;
@@ -7,12 +7,12 @@ use glow::{Context as GlowContext, HasContext, PixelPackData, PixelUnpackData};
 
 use crate::error::{Result, TetraError};
 use crate::graphics::{
-    mesh::{BufferUsage, Vertex, VertexWinding},
+    mesh::{BufferUsage, VertexAttribute, VertexAttributeFormat, VertexLayout, VertexWinding},
     StencilState, StencilTest,
 };
 use crate::graphics::{
-    BlendFactor, BlendOperation, BlendState, Color, FilterMode, GraphicsDeviceInfo, StencilAction,
-    TextureFormat,
+    BlendFactor, BlendOperation, BlendState, Color, FilterMode, GraphicsDeviceInfo,
+    ShaderAttribute, ShaderDataType, ShaderSource, ShaderUniform, StencilAction, TextureFormat,
 };
 use crate::math::{Mat2, Mat3, Mat4, Vec2, Vec3, Vec4};
 
@@ -23,6 +23,7 @@ type FramebufferId = <GlowContext as HasContext>::Framebuffer;
 type RenderbufferId = <GlowContext as HasContext>::Renderbuffer;
 type VertexArrayId = <GlowContext as HasContext>::VertexArray;
 type UniformLocation = <GlowContext as HasContext>::UniformLocation;
+type QueryId = <GlowContext as HasContext>::Query;
 
 #[derive(Debug)]
 struct GraphicsState {
@@ -35,6 +36,9 @@ struct GraphicsState {
     current_read_framebuffer: Cell<Option<FramebufferId>>,
     current_draw_framebuffer: Cell<Option<FramebufferId>>,
     current_renderbuffer: Cell<Option<RenderbufferId>>,
+    current_uniform_buffers: Vec<Cell<Option<BufferId>>>,
+    enabled_attributes: Cell<u32>,
+    instanced_attributes: Cell<u32>,
 
     vertex_array: VertexArrayId,
     resolve_framebuffer: FramebufferId,
@@ -77,6 +81,9 @@ impl GraphicsDevice {
 
             let max_samples = gl.get_parameter_i32(glow::MAX_SAMPLES) as u8;
 
+            let uniform_buffer_bindings =
+                gl.get_parameter_i32(glow::MAX_UNIFORM_BUFFER_BINDINGS) as usize;
+
             let state = GraphicsState {
                 gl,
 
@@ -87,6 +94,9 @@ impl GraphicsDevice {
                 current_read_framebuffer: Cell::new(None),
                 current_draw_framebuffer: Cell::new(None),
                 current_renderbuffer: Cell::new(None),
+                current_uniform_buffers: vec![Cell::new(None); uniform_buffer_bindings],
+                enabled_attributes: Cell::new(0),
+                instanced_attributes: Cell::new(0),
 
                 vertex_array,
                 resolve_framebuffer,
@@ -114,6 +124,64 @@ impl GraphicsDevice {
         }
     }
 
+    pub fn supports_timer_queries(&self) -> bool {
+        let version = self.state.gl.version();
+
+        (version.major, version.minor) >= (3, 3)
+            || self
+                .state
+                .gl
+                .supported_extensions()
+                .contains("GL_ARB_timer_query")
+    }
+
+    pub fn new_query(&mut self) -> Result<RawQuery> {
+        unsafe {
+            let id = self
+                .state
+                .gl
+                .create_query()
+                .map_err(TetraError::PlatformError)?;
+
+            Ok(RawQuery {
+                state: Rc::clone(&self.state),
+                id,
+            })
+        }
+    }
+
+    pub fn begin_timer_query(&mut self, query: &RawQuery) {
+        unsafe {
+            self.state.gl.begin_query(glow::TIME_ELAPSED, query.id);
+        }
+    }
+
+    pub fn end_timer_query(&mut self) {
+        unsafe {
+            self.state.gl.end_query(glow::TIME_ELAPSED);
+        }
+    }
+
+    /// Returns the result of the query, or `None` if it is not available yet.
+    pub fn get_query_result(&mut self, query: &RawQuery) -> Option<u32> {
+        unsafe {
+            let available = self
+                .state
+                .gl
+                .get_query_parameter_u32(query.id, glow::QUERY_RESULT_AVAILABLE);
+
+            if available != 0 {
+                Some(
+                    self.state
+                        .gl
+                        .get_query_parameter_u32(query.id, glow::QUERY_RESULT),
+                )
+            } else {
+                None
+            }
+        }
+    }
+
     pub fn clear(&mut self, color: Color) {
         unsafe {
             self.state
@@ -189,11 +257,23 @@ impl GraphicsDevice {
         }
     }
 
-    pub fn new_vertex_buffer(
+    pub fn new_vertex_buffer<V>(
         &mut self,
         count: usize,
         usage: BufferUsage,
-    ) -> Result<RawVertexBuffer> {
+    ) -> Result<RawVertexBuffer>
+    where
+        V: VertexLayout,
+    {
+        let stride = mem::size_of::<V>();
+
+        assert!(
+            V::ATTRIBUTES
+                .iter()
+                .all(|attribute| attribute.offset + attribute.format.size() <= stride),
+            "vertex attributes must fit within the size of the vertex"
+        );
+
         unsafe {
             let id = self
                 .state
@@ -205,6 +285,8 @@ impl GraphicsDevice {
                 state: Rc::clone(&self.state),
                 id,
                 count,
+                stride,
+                attributes: V::ATTRIBUTES,
             };
 
             self.bind_vertex_buffer(Some(buffer.id));
@@ -228,14 +310,17 @@ impl GraphicsDevice {
         }
     }
 
-    pub fn set_vertex_buffer_data(
-        &mut self,
-        buffer: &RawVertexBuffer,
-        data: &[Vertex],
-        offset: usize,
-    ) {
+    pub fn set_vertex_buffer_data<V>(&mut self, buffer: &RawVertexBuffer, data: &[V], offset: usize)
+    where
+        V: VertexLayout,
+    {
         self.bind_vertex_buffer(Some(buffer.id));
 
+        assert!(
+            buffer.stride == mem::size_of::<V>() && buffer.attributes == V::ATTRIBUTES,
+            "tried to write vertex data with a different layout to the buffer"
+        );
+
         assert!(
             data.len() + offset <= buffer.count(),
             "tried to write out of bounds buffer data"
@@ -252,43 +337,98 @@ impl GraphicsDevice {
         }
     }
 
-    fn set_vertex_attributes(&mut self, buffer: &RawVertexBuffer) {
-        // TODO: This only works because we don't let the user set custom
-        // attribute bindings - will need a rethink at that point!
+    fn set_vertex_attributes(
+        &mut self,
+        vertex_buffer: &RawVertexBuffer,
+        instance_buffer: Option<(&RawVertexBuffer, u32)>,
+        shader: &RawShader,
+    ) {
+        let mut enabled = self.bind_attributes(vertex_buffer, 0, shader);
+
+        if let Some((instance_buffer, divisor)) = instance_buffer {
+            enabled |= self.bind_attributes(instance_buffer, divisor, shader);
+        }
+
+        unsafe {
+            // Any arrays that were left enabled by a previous draw call need to be
+            // turned off, as they may point past the end of the current buffer.
+            let stale = self.state.enabled_attributes.get() & !enabled;
+
+            for location in 0..32 {
+                if stale & (1 << location) != 0 {
+                    self.state.gl.disable_vertex_attrib_array(location);
+                }
+            }
+        }
+
+        self.state.enabled_attributes.set(enabled);
+    }
+
+    /// Binds the attributes from a buffer to the matching inputs of a shader, returning
+    /// a bitmask of the locations that were enabled.
+    fn bind_attributes(
+        &mut self,
+        buffer: &RawVertexBuffer,
+        divisor: u32,
+        shader: &RawShader,
+    ) -> u32 {
+        let mut enabled = 0;
+
         unsafe {
             self.bind_vertex_buffer(Some(buffer.id));
 
-            self.state.gl.vertex_attrib_pointer_f32(
-                0,
-                2,
-                glow::FLOAT,
-                false,
-                buffer.stride() as i32,
-                0,
-            );
+            for attribute in buffer.attributes {
+                // Attributes that the shader doesn't use are skipped, so that the same
+                // vertex data can be used with multiple shaders.
+                let location = match self.get_attribute_location(shader, attribute.name) {
+                    Some(location) => location,
+                    None => continue,
+                };
+
+                let format = attribute.format;
+
+                self.state.gl.vertex_attrib_pointer_f32(
+                    location,
+                    format.components(),
+                    format.to_gl_enum(),
+                    format.normalized(),
+                    buffer.stride() as i32,
+                    attribute.offset as i32,
+                );
 
-            self.state.gl.vertex_attrib_pointer_f32(
-                1,
-                2,
-                glow::FLOAT,
-                false,
-                buffer.stride() as i32,
-                8,
-            );
+                self.state.gl.enable_vertex_attrib_array(location);
 
-            self.state.gl.vertex_attrib_pointer_f32(
-                2,
-                4,
-                glow::FLOAT,
-                false,
-                buffer.stride() as i32,
-                16,
-            );
+                let mask = 1u32.checked_shl(location).unwrap_or(0);
+                let instanced = self.state.instanced_attributes.get();
 
-            self.state.gl.enable_vertex_attrib_array(0);
-            self.state.gl.enable_vertex_attrib_array(1);
-            self.state.gl.enable_vertex_attrib_array(2);
+                enabled |= mask;
+
+                // Resetting the divisor is skipped if it was already zero, as the vast
+                // majority of draw calls don't use instancing at all.
+                if divisor != 0 {
+                    self.state.gl.vertex_attrib_divisor(location, divisor);
+                    self.state.instanced_attributes.set(instanced | mask);
+                } else if instanced & mask != 0 || mask == 0 {
+                    self.state.gl.vertex_attrib_divisor(location, 0);
+                    self.state.instanced_attributes.set(instanced & !mask);
+                }
+            }
         }
+
+        enabled
+    }
+
+    fn get_attribute_location(&self, shader: &RawShader, name: &'static str) -> Option<u32> {
+        let mut locations = shader.attribute_locations.borrow_mut();
+
+        if let Some((_, location)) = locations.iter().find(|(n, _)| *n == name) {
+            return *location;
+        }
+
+        let location = unsafe { self.state.gl.get_attrib_location(shader.id, name) };
+        locations.push((name, location));
+
+        location
     }
 
     pub fn new_index_buffer(&mut self, count: usize, usage: BufferUsage) -> Result<RawIndexBuffer> {
@@ -345,7 +485,74 @@ impl GraphicsDevice {
         }
     }
 
-    pub fn new_shader(&mut self, vertex_shader: &str, fragment_shader: &str) -> Result<RawShader> {
+    pub fn new_uniform_buffer(
+        &mut self,
+        size: usize,
+        usage: BufferUsage,
+    ) -> Result<RawUniformBuffer> {
+        unsafe {
+            let id = self
+                .state
+                .gl
+                .create_buffer()
+                .map_err(TetraError::PlatformError)?;
+
+            let buffer = RawUniformBuffer {
+                state: Rc::clone(&self.state),
+                id,
+                size,
+            };
+
+            self.state
+                .gl
+                .bind_buffer(glow::UNIFORM_BUFFER, Some(buffer.id));
+
+            self.clear_errors();
+
+            self.state.gl.buffer_data_size(
+                glow::UNIFORM_BUFFER,
+                buffer.size() as i32,
+                usage.to_gl_enum(),
+            );
+
+            if let Some(e) = self.get_error() {
+                return Err(TetraError::PlatformError(format_gl_error(
+                    "failed to create uniform buffer",
+                    e,
+                )));
+            }
+
+            Ok(buffer)
+        }
+    }
+
+    pub fn set_uniform_buffer_data(
+        &mut self,
+        buffer: &RawUniformBuffer,
+        data: &[u8],
+        offset: usize,
+    ) {
+        assert!(
+            data.len() + offset <= buffer.size(),
+            "tried to write out of bounds buffer data"
+        );
+
+        unsafe {
+            self.state
+                .gl
+                .bind_buffer(glow::UNIFORM_BUFFER, Some(buffer.id));
+
+            self.state
+                .gl
+                .buffer_sub_data_u8_slice(glow::UNIFORM_BUFFER, offset as i32, data);
+        }
+    }
+
+    pub fn new_shader(
+        &mut self,
+        vertex_shader: &ShaderSource,
+        fragment_shader: &ShaderSource,
+    ) -> Result<RawShader> {
         unsafe {
             let program_id = self
                 .state
@@ -366,13 +573,13 @@ impl GraphicsDevice {
                 .create_shader(glow::VERTEX_SHADER)
                 .map_err(TetraError::PlatformError)?;
 
-            self.state.gl.shader_source(vertex_id, vertex_shader);
+            self.state.gl.shader_source(vertex_id, &vertex_shader.code);
             self.state.gl.compile_shader(vertex_id);
             self.state.gl.attach_shader(program_id, vertex_id);
 
             if !self.state.gl.get_shader_compile_status(vertex_id) {
                 return Err(TetraError::InvalidShader(
-                    self.state.gl.get_shader_info_log(vertex_id),
+                    vertex_shader.format_log(&self.state.gl.get_shader_info_log(vertex_id)),
                 ));
             }
 
@@ -382,13 +589,15 @@ impl GraphicsDevice {
                 .create_shader(glow::FRAGMENT_SHADER)
                 .map_err(TetraError::PlatformError)?;
 
-            self.state.gl.shader_source(fragment_id, fragment_shader);
+            self.state
+                .gl
+                .shader_source(fragment_id, &fragment_shader.code);
             self.state.gl.compile_shader(fragment_id);
             self.state.gl.attach_shader(program_id, fragment_id);
 
             if !self.state.gl.get_shader_compile_status(fragment_id) {
                 return Err(TetraError::InvalidShader(
-                    self.state.gl.get_shader_info_log(fragment_id),
+                    fragment_shader.format_log(&self.state.gl.get_shader_info_log(fragment_id)),
                 ));
             }
 
@@ -406,6 +615,7 @@ impl GraphicsDevice {
             let shader = RawShader {
                 state: Rc::clone(&self.state),
                 id: program_id,
+                attribute_locations: RefCell::new(Vec::new()),
             };
 
             let sampler_location = self.get_uniform_location(&shader, "u_texture");
@@ -419,6 +629,79 @@ impl GraphicsDevice {
         unsafe { self.state.gl.get_uniform_location(shader.id, name) }
     }
 
+    pub fn get_active_uniforms(&self, shader: &RawShader) -> Vec<ShaderUniform> {
+        unsafe {
+            let count = self.state.gl.get_active_uniforms(shader.id);
+
+            (0..count)
+                .filter_map(|i| self.state.gl.get_active_uniform(shader.id, i))
+                .map(|uniform| ShaderUniform {
+                    // Arrays are reported using the name of their first element,
+                    // so we strip that off to get the name of the array itself.
+                    name: match uniform.name.strip_suffix("[0]") {
+                        Some(name) => name.to_owned(),
+                        None => uniform.name,
+                    },
+                    data_type: ShaderDataType::from_gl_enum(uniform.utype),
+                    size: uniform.size as usize,
+                })
+                .collect()
+        }
+    }
+
+    pub fn get_active_attributes(&self, shader: &RawShader) -> Vec<ShaderAttribute> {
+        unsafe {
+            let count = self.state.gl.get_active_attributes(shader.id);
+
+            (0..count)
+                .filter_map(|i| self.state.gl.get_active_attribute(shader.id, i))
+                .map(|attribute| ShaderAttribute {
+                    name: attribute.name,
+                    data_type: ShaderDataType::from_gl_enum(attribute.atype),
+                    size: attribute.size as usize,
+                })
+                .collect()
+        }
+    }
+
+    pub fn get_uniform_block_index(&self, shader: &RawShader, name: &str) -> Option<u32> {
+        unsafe { self.state.gl.get_uniform_block_index(shader.id, name) }
+    }
+
+    pub fn set_uniform_block_binding(&mut self, shader: &RawShader, index: u32, binding: u32) {
+        unsafe {
+            self.state
+                .gl
+                .uniform_block_binding(shader.id, index, binding);
+        }
+    }
+
+    pub fn attach_uniform_buffer_to_binding(
+        &mut self,
+        buffer: &RawUniformBuffer,
+        binding: u32,
+    ) -> Result {
+        unsafe {
+            let current = &self
+                .state
+                .current_uniform_buffers
+                .get(binding as usize)
+                .ok_or_else(|| {
+                    TetraError::PlatformError("invalid uniform buffer binding".into())
+                })?;
+
+            if current.get() != Some(buffer.id) {
+                self.state
+                    .gl
+                    .bind_buffer_base(glow::UNIFORM_BUFFER, binding, Some(buffer.id));
+
+                current.set(Some(buffer.id));
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn set_uniform_i32(
         &mut self,
         shader: &RawShader,
@@ -880,10 +1163,49 @@ impl GraphicsDevice {
                 color,
                 multisample_color,
                 depth_stencil,
+                samples: actual_samples,
             })
         }
     }
 
+    pub fn attach_depth_stencil_buffer(
+        &mut self,
+        canvas: &RawCanvas,
+        width: i32,
+        height: i32,
+        samples: u8,
+    ) -> Result<RawRenderbuffer> {
+        unsafe {
+            let previous_read = self.state.current_read_framebuffer.get();
+            let previous_draw = self.state.current_draw_framebuffer.get();
+
+            let renderbuffer = self.new_depth_stencil_renderbuffer(width, height, samples)?;
+
+            self.bind_framebuffer(Some(canvas.id));
+
+            self.state.gl.framebuffer_renderbuffer(
+                glow::FRAMEBUFFER,
+                glow::DEPTH_STENCIL_ATTACHMENT,
+                glow::RENDERBUFFER,
+                Some(renderbuffer.id),
+            );
+
+            let status = self.state.gl.check_framebuffer_status(glow::FRAMEBUFFER);
+
+            self.bind_read_framebuffer(previous_read);
+            self.bind_draw_framebuffer(previous_draw);
+
+            if status != glow::FRAMEBUFFER_COMPLETE {
+                return Err(TetraError::PlatformError(format_gl_framebuffer_status(
+                    "failed to attach stencil buffer",
+                    status,
+                )));
+            }
+
+            Ok(renderbuffer)
+        }
+    }
+
     pub fn set_canvas(&mut self, canvas: Option<&RawCanvas>) {
         self.bind_framebuffer(canvas.map(|f| f.id));
     }
@@ -998,6 +1320,7 @@ impl GraphicsDevice {
         self.draw_instanced(
             vertex_buffer,
             index_buffer,
+            None,
             texture,
             shader,
             offset,
@@ -1011,6 +1334,7 @@ impl GraphicsDevice {
         &mut self,
         vertex_buffer: &RawVertexBuffer,
         index_buffer: Option<&RawIndexBuffer>,
+        instance_buffer: Option<(&RawVertexBuffer, u32)>,
         texture: &RawTexture,
         shader: &RawShader,
         offset: usize,
@@ -1020,7 +1344,7 @@ impl GraphicsDevice {
         self.bind_vertex_buffer(Some(vertex_buffer.id));
         self.bind_default_texture(Some(texture.id));
         self.bind_program(Some(shader.id));
-        self.set_vertex_attributes(vertex_buffer);
+        self.set_vertex_attributes(vertex_buffer, instance_buffer, shader);
 
         match index_buffer {
             Some(index_buffer) => {
@@ -1209,6 +1533,51 @@ impl BufferUsage {
     }
 }
 
+#[doc(hidden)]
+impl VertexAttributeFormat {
+    fn to_gl_enum(self) -> u32 {
+        match self {
+            VertexAttributeFormat::Float
+            | VertexAttributeFormat::Vec2
+            | VertexAttributeFormat::Vec3
+            | VertexAttributeFormat::Vec4 => glow::FLOAT,
+            VertexAttributeFormat::UnsignedByte4
+            | VertexAttributeFormat::UnsignedByte4Normalized => glow::UNSIGNED_BYTE,
+            VertexAttributeFormat::Byte4 | VertexAttributeFormat::Byte4Normalized => glow::BYTE,
+            VertexAttributeFormat::UnsignedShort2
+            | VertexAttributeFormat::UnsignedShort2Normalized => glow::UNSIGNED_SHORT,
+            VertexAttributeFormat::Short2 | VertexAttributeFormat::Short2Normalized => glow::SHORT,
+        }
+    }
+
+    fn components(self) -> i32 {
+        match self {
+            VertexAttributeFormat::Float => 1,
+            VertexAttributeFormat::Vec2
+            | VertexAttributeFormat::UnsignedShort2
+            | VertexAttributeFormat::UnsignedShort2Normalized
+            | VertexAttributeFormat::Short2
+            | VertexAttributeFormat::Short2Normalized => 2,
+            VertexAttributeFormat::Vec3 => 3,
+            VertexAttributeFormat::Vec4
+            | VertexAttributeFormat::UnsignedByte4
+            | VertexAttributeFormat::UnsignedByte4Normalized
+            | VertexAttributeFormat::Byte4
+            | VertexAttributeFormat::Byte4Normalized => 4,
+        }
+    }
+
+    fn normalized(self) -> bool {
+        matches!(
+            self,
+            VertexAttributeFormat::UnsignedByte4Normalized
+                | VertexAttributeFormat::Byte4Normalized
+                | VertexAttributeFormat::UnsignedShort2Normalized
+                | VertexAttributeFormat::Short2Normalized
+        )
+    }
+}
+
 #[doc(hidden)]
 impl VertexWinding {
     fn to_gl_enum(self) -> u32 {
@@ -1268,6 +1637,35 @@ impl TextureFormat {
     }
 }
 
+#[doc(hidden)]
+impl ShaderDataType {
+    fn from_gl_enum(value: u32) -> ShaderDataType {
+        match value {
+            glow::FLOAT => ShaderDataType::Float,
+            glow::FLOAT_VEC2 => ShaderDataType::Vec2,
+            glow::FLOAT_VEC3 => ShaderDataType::Vec3,
+            glow::FLOAT_VEC4 => ShaderDataType::Vec4,
+            glow::INT => ShaderDataType::Int,
+            glow::INT_VEC2 => ShaderDataType::IVec2,
+            glow::INT_VEC3 => ShaderDataType::IVec3,
+            glow::INT_VEC4 => ShaderDataType::IVec4,
+            glow::UNSIGNED_INT => ShaderDataType::UInt,
+            glow::UNSIGNED_INT_VEC2 => ShaderDataType::UVec2,
+            glow::UNSIGNED_INT_VEC3 => ShaderDataType::UVec3,
+            glow::UNSIGNED_INT_VEC4 => ShaderDataType::UVec4,
+            glow::BOOL => ShaderDataType::Bool,
+            glow::BOOL_VEC2 => ShaderDataType::BVec2,
+            glow::BOOL_VEC3 => ShaderDataType::BVec3,
+            glow::BOOL_VEC4 => ShaderDataType::BVec4,
+            glow::FLOAT_MAT2 => ShaderDataType::Mat2,
+            glow::FLOAT_MAT3 => ShaderDataType::Mat3,
+            glow::FLOAT_MAT4 => ShaderDataType::Mat4,
+            glow::SAMPLER_2D => ShaderDataType::Sampler2D,
+            _ => ShaderDataType::Other,
+        }
+    }
+}
+
 #[doc(hidden)]
 impl StencilTest {
     fn to_gl_enum(self) -> u32 {
@@ -1340,6 +1738,8 @@ pub struct RawVertexBuffer {
     id: BufferId,
 
     count: usize,
+    stride: usize,
+    attributes: &'static [VertexAttribute],
 }
 
 impl RawVertexBuffer {
@@ -1350,7 +1750,7 @@ impl RawVertexBuffer {
 
     // The size of each vertex, in bytes.
     pub fn stride(&self) -> usize {
-        std::mem::size_of::<Vertex>()
+        self.stride
     }
 
     /// The size of the buffer, in bytes.
@@ -1420,10 +1820,47 @@ impl Drop for RawIndexBuffer {
     }
 }
 
+#[derive(Debug)]
+pub struct RawUniformBuffer {
+    state: Rc<GraphicsState>,
+    id: BufferId,
+
+    size: usize,
+}
+
+impl RawUniformBuffer {
+    /// The size of the buffer, in bytes.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+}
+
+impl PartialEq for RawUniformBuffer {
+    fn eq(&self, other: &RawUniformBuffer) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Drop for RawUniformBuffer {
+    fn drop(&mut self) {
+        unsafe {
+            for bound in &self.state.current_uniform_buffers {
+                if bound.get() == Some(self.id) {
+                    bound.set(None);
+                }
+            }
+
+            self.state.gl.delete_buffer(self.id);
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct RawShader {
     state: Rc<GraphicsState>,
     id: ProgramId,
+
+    attribute_locations: RefCell<Vec<(&'static str, Option<u32>)>>,
 }
 
 impl PartialEq for RawShader {
@@ -1516,11 +1953,26 @@ impl Drop for RawCanvas {
     }
 }
 
+#[derive(Debug)]
+pub struct RawQuery {
+    state: Rc<GraphicsState>,
+    id: QueryId,
+}
+
+impl Drop for RawQuery {
+    fn drop(&mut self) {
+        unsafe {
+            self.state.gl.delete_query(self.id);
+        }
+    }
+}
+
 pub struct RawCanvasWithAttachments {
     pub canvas: RawCanvas,
     pub color: RawTexture,
     pub multisample_color: Option<RawRenderbuffer>,
     pub depth_stencil: Option<RawRenderbuffer>,
+    pub samples: u8,
 }
 
 #[derive(Debug)]
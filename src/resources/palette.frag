@@ -0,0 +1,22 @@
+#version 150
+
+in vec2 v_uv;
+in vec4 v_color;
+
+uniform sampler2D u_texture;
+uniform sampler2D u_palette;
+uniform vec4 u_diffuse;
+
+out vec4 o_color;
+
+void main() {
+    // The texture is sampled without filtering, as blending between two indices
+    // would give a meaningless result.
+    ivec2 size = textureSize(u_texture, 0);
+    ivec2 coord = clamp(ivec2(v_uv * vec2(size)), ivec2(0), size - 1);
+
+    int index = int(texelFetch(u_texture, coord, 0).r * 255.0 + 0.5);
+    int palette_size = textureSize(u_palette, 0).x;
+
+    o_color = texelFetch(u_palette, ivec2(min(index, palette_size - 1), 0), 0) * u_diffuse * v_color;
+}